@@ -0,0 +1,300 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::vault::{VaultConfig, VaultStorage};
+
+/// List file name, in the app data directory for global lists and in each profile directory for its own
+pub const LISTS_FILE: &str = "address_lists.json";
+
+/// Address list errors
+#[derive(Error, Debug)]
+pub enum AddressListError {
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid list entry: {0}")]
+    InvalidEntry(String),
+    #[error("Unsupported import file: {0}")]
+    UnsupportedFormat(String),
+    #[error("Address list storage error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Address list file error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("Address list CSV error: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+impl From<AddressListError> for String {
+    fn from(err: AddressListError) -> String {
+        err.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListKind {
+    /// Only these recipients are allowed while allowlist-only mode is on
+    Allow,
+    /// These recipients are always refused
+    Deny,
+}
+
+/// Where a list lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListScope {
+    /// Applies to every profile
+    Global,
+    /// Applies to the active profile only
+    Profile,
+}
+
+/// One listed address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressEntry {
+    pub address: String,
+    pub reason: String,
+    pub added_at: DateTime<Utc>,
+    /// The entry stops applying at this time; `None` keeps it until removed
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Feed or file the entry was imported from
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl AddressEntry {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.map_or(true, |expires_at| expires_at > now)
+    }
+}
+
+/// Format of an imported list
+///
+/// CSV rows are `address,reason,expires_at` with the last two optional, an optional
+/// `address` header row and `#` comments. JSON is an array of addresses or of
+/// `{ "address", "reason", "expires_at" }` objects. Expiry times are RFC 3339.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    pub fn from_path(path: &Path) -> Result<Self, AddressListError> {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            _ => Err(AddressListError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    /// Rows that were not imported, with the reason
+    pub skipped: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonRow {
+    Address(String),
+    Entry {
+        address: String,
+        #[serde(default)]
+        reason: Option<String>,
+        #[serde(default)]
+        expires_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// Allow- and denylist of one scope
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AddressLists {
+    #[serde(default)]
+    pub allow: Vec<AddressEntry>,
+    #[serde(default)]
+    pub deny: Vec<AddressEntry>,
+}
+
+impl AddressLists {
+    /// Read the lists at `path`; a missing file holds empty lists
+    pub fn load(path: &Path) -> Result<Self, AddressListError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: PathBuf) -> Result<(), AddressListError> {
+        let storage = VaultStorage::new(path, &VaultConfig { backup_count: 1, ..VaultConfig::default() });
+        storage.write(&serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn list(&self, kind: ListKind) -> &Vec<AddressEntry> {
+        match kind {
+            ListKind::Allow => &self.allow,
+            ListKind::Deny => &self.deny,
+        }
+    }
+
+    fn list_mut(&mut self, kind: ListKind) -> &mut Vec<AddressEntry> {
+        match kind {
+            ListKind::Allow => &mut self.allow,
+            ListKind::Deny => &mut self.deny,
+        }
+    }
+
+    /// Add `entry`, replacing any entry for the same address; true if one was replaced
+    pub fn add(&mut self, kind: ListKind, entry: AddressEntry) -> Result<bool, AddressListError> {
+        Pubkey::from_str(&entry.address).map_err(|_| AddressListError::InvalidAddress(entry.address.clone()))?;
+        if entry.reason.trim().is_empty() {
+            return Err(AddressListError::InvalidEntry(format!("{} needs a reason", entry.address)));
+        }
+
+        let list = self.list_mut(kind);
+        match list.iter_mut().find(|existing| existing.address == entry.address) {
+            Some(existing) => {
+                *existing = entry;
+                Ok(true)
+            }
+            None => {
+                list.push(entry);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Remove the entry for `address`; false if there was none
+    pub fn remove(&mut self, kind: ListKind, address: &str) -> bool {
+        let list = self.list_mut(kind);
+        let before = list.len();
+        list.retain(|entry| entry.address != address);
+        list.len() != before
+    }
+
+    /// The entry for `address` if it is listed and has not expired at `now`
+    pub fn find(&self, kind: ListKind, address: &str, now: DateTime<Utc>) -> Option<&AddressEntry> {
+        self.list(kind).iter().find(|entry| entry.address == address && entry.is_active(now))
+    }
+
+    /// Drop entries expired at `now`
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.allow.retain(|entry| entry.is_active(now));
+        self.deny.retain(|entry| entry.is_active(now));
+    }
+
+    /// These lists together with `other`, e.g. the global lists with a profile's
+    pub fn merged(mut self, other: AddressLists) -> AddressLists {
+        self.allow.extend(other.allow);
+        self.deny.extend(other.deny);
+        self
+    }
+
+    /// Add every valid row of `data` to the `kind` list, tagging entries with `source`
+    ///
+    /// Rows without a reason get one naming the source. Invalid and already expired
+    /// rows are skipped and reported; the rest are still imported.
+    pub fn import(
+        &mut self,
+        kind: ListKind,
+        format: ImportFormat,
+        data: &[u8],
+        source: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ImportReport, AddressListError> {
+        let rows = match format {
+            ImportFormat::Csv => Self::csv_rows(data)?,
+            ImportFormat::Json => Self::json_rows(data)?,
+        };
+
+        let mut report = ImportReport::default();
+        for (row, parsed) in rows {
+            let (address, reason, expires_at) = match parsed {
+                Ok(fields) => fields,
+                Err(e) => {
+                    report.skipped.push(format!("{}: {}", row, e));
+                    continue;
+                }
+            };
+            if expires_at.is_some_and(|expires_at| expires_at <= now) {
+                report.skipped.push(format!("{}: {} has already expired", row, address));
+                continue;
+            }
+
+            let entry = AddressEntry {
+                reason: reason.unwrap_or_else(|| format!("Imported from {}", source)),
+                address,
+                added_at: now,
+                expires_at,
+                source: Some(source.to_string()),
+            };
+            match self.add(kind, entry) {
+                Ok(true) => report.updated += 1,
+                Ok(false) => report.added += 1,
+                Err(e) => report.skipped.push(format!("{}: {}", row, e)),
+            }
+        }
+        Ok(report)
+    }
+
+    fn csv_rows(data: &[u8]) -> Result<Vec<(String, Result<ImportedRow, String>)>, AddressListError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .comment(Some(b'#'))
+            .from_reader(data);
+
+        let mut rows = Vec::new();
+        for (i, record) in reader.records().enumerate() {
+            let record = record?;
+            let field = |index: usize| record.get(index).filter(|value| !value.is_empty());
+            let Some(address) = field(0) else { continue };
+            if i == 0 && address.eq_ignore_ascii_case("address") {
+                continue;
+            }
+
+            let line = record.position().map_or(i as u64 + 1, |position| position.line());
+            let expires_at = field(2)
+                .map(|value| DateTime::parse_from_rfc3339(value).map(|date| date.with_timezone(&Utc)))
+                .transpose()
+                .map_err(|e| format!("invalid expiry: {}", e));
+            rows.push((
+                format!("line {}", line),
+                expires_at.map(|expires_at| (address.to_string(), field(1).map(str::to_string), expires_at)),
+            ));
+        }
+        Ok(rows)
+    }
+
+    fn json_rows(data: &[u8]) -> Result<Vec<(String, Result<ImportedRow, String>)>, AddressListError> {
+        let rows: Vec<JsonRow> = serde_json::from_slice(data)?;
+        Ok(rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let fields = match row {
+                    JsonRow::Address(address) => (address, None, None),
+                    JsonRow::Entry { address, reason, expires_at } => (address, reason.filter(|r| !r.trim().is_empty()), expires_at),
+                };
+                (format!("entry {}", i + 1), Ok(fields))
+            })
+            .collect())
+    }
+}
+
+/// Address, reason and expiry of an imported row
+type ImportedRow = (String, Option<String>, Option<DateTime<Utc>>);
+
+include!("address_lists_tests.rs");
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn address() -> String {
+        Pubkey::new_unique().to_string()
+    }
+
+    fn entry(address: &str, expires_at: Option<DateTime<Utc>>) -> AddressEntry {
+        AddressEntry {
+            address: address.to_string(),
+            reason: "test".to_string(),
+            added_at: Utc::now(),
+            expires_at,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let now = Utc::now();
+        let (permanent, temporary) = (address(), address());
+        let mut lists = AddressLists::default();
+        lists.add(ListKind::Deny, entry(&permanent, None)).unwrap();
+        lists.add(ListKind::Deny, entry(&temporary, Some(now + chrono::Duration::hours(1)))).unwrap();
+
+        assert!(lists.find(ListKind::Deny, &temporary, now).is_some());
+        assert!(lists.find(ListKind::Deny, &temporary, now + chrono::Duration::hours(2)).is_none());
+        assert!(lists.find(ListKind::Allow, &permanent, now).is_none());
+
+        lists.prune(now + chrono::Duration::hours(2));
+        assert_eq!(lists.deny.len(), 1);
+        assert_eq!(lists.deny[0].address, permanent);
+    }
+
+    #[test]
+    fn test_add_replaces_and_validates() {
+        let listed = address();
+        let mut lists = AddressLists::default();
+
+        assert_eq!(lists.add(ListKind::Allow, entry(&listed, None)).unwrap(), false);
+        let renewed = AddressEntry { reason: "renewed".to_string(), ..entry(&listed, None) };
+        assert_eq!(lists.add(ListKind::Allow, renewed).unwrap(), true);
+        assert_eq!(lists.allow.len(), 1);
+        assert_eq!(lists.allow[0].reason, "renewed");
+
+        assert!(matches!(lists.add(ListKind::Allow, entry("not-an-address", None)), Err(AddressListError::InvalidAddress(_))));
+        let unexplained = AddressEntry { reason: " ".to_string(), ..entry(&address(), None) };
+        assert!(lists.add(ListKind::Allow, unexplained).is_err());
+
+        assert!(lists.remove(ListKind::Allow, &listed));
+        assert!(!lists.remove(ListKind::Allow, &listed));
+    }
+
+    #[test]
+    fn test_csv_import() {
+        let now = Utc::now();
+        let (a, b, c) = (address(), address(), address());
+        let csv = format!(
+            "address,reason,expires_at\n\
+             # OFAC update\n\
+             {a},\"sanctioned, SDN list\",\n\
+             {b},,2999-01-01T00:00:00Z\n\
+             {c},expired,2000-01-01T00:00:00Z\n\
+             bogus,bad row,\n\
+             {a},listed twice,\n"
+        );
+
+        let mut lists = AddressLists::default();
+        let report = lists.import(ListKind::Deny, ImportFormat::Csv, csv.as_bytes(), "sdn.csv", now).unwrap();
+
+        assert_eq!((report.added, report.updated, report.skipped.len()), (2, 1, 2));
+        assert!(report.skipped[0].contains("already expired"), "{:?}", report.skipped);
+        assert_eq!(lists.find(ListKind::Deny, &a, now).unwrap().reason, "listed twice");
+        let b_entry = lists.find(ListKind::Deny, &b, now).unwrap();
+        assert_eq!(b_entry.reason, "Imported from sdn.csv");
+        assert_eq!(b_entry.source.as_deref(), Some("sdn.csv"));
+        assert!(b_entry.expires_at.is_some());
+    }
+
+    #[test]
+    fn test_json_import() {
+        let now = Utc::now();
+        let (a, b) = (address(), address());
+        let json = format!(r#"["{a}", {{"address": "{b}", "reason": "partner", "expires_at": "2999-01-01T00:00:00Z"}}]"#);
+
+        let mut lists = AddressLists::default();
+        let report = lists.import(ListKind::Allow, ImportFormat::Json, json.as_bytes(), "partners.json", now).unwrap();
+
+        assert_eq!(report, ImportReport { added: 2, updated: 0, skipped: vec![] });
+        assert_eq!(lists.find(ListKind::Allow, &b, now).unwrap().reason, "partner");
+        assert!(lists.find(ListKind::Allow, &a, now).is_some());
+    }
+
+    #[test]
+    fn test_format_follows_extension() {
+        assert_eq!(ImportFormat::from_path(Path::new("feed.CSV")).unwrap(), ImportFormat::Csv);
+        assert_eq!(ImportFormat::from_path(Path::new("feed.json")).unwrap(), ImportFormat::Json);
+        assert!(ImportFormat::from_path(Path::new("feed.txt")).is_err());
+    }
+
+    #[test]
+    fn test_lists_persist_and_merge() {
+        let dir = tempdir().unwrap();
+        let (global_path, profile_path) = (dir.path().join("global.json"), dir.path().join("profile.json"));
+        let (feed, local) = (address(), address());
+
+        assert!(AddressLists::load(&global_path).unwrap().deny.is_empty());
+
+        let mut global = AddressLists::default();
+        global.add(ListKind::Deny, entry(&feed, None)).unwrap();
+        global.save(global_path.clone()).unwrap();
+        let mut profile = AddressLists::default();
+        profile.add(ListKind::Allow, entry(&local, None)).unwrap();
+        profile.save(profile_path.clone()).unwrap();
+
+        let merged = AddressLists::load(&global_path).unwrap()
+            .merged(AddressLists::load(&profile_path).unwrap());
+        let now = Utc::now();
+        assert!(merged.find(ListKind::Deny, &feed, now).is_some());
+        assert!(merged.find(ListKind::Allow, &local, now).is_some());
+    }
+}
@@ -0,0 +1,338 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use argon2::password_hash::SaltString;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::keystore::SecretKdf;
+use crate::security::{SecureHash, SecurityEvent};
+use crate::vault::{VaultConfig, VaultStorage};
+use crate::{decrypt_data_with_aad, derive_encryption_key, encrypt_data_with_aad, WalletError};
+
+/// Audit log file inside each profile directory, one JSON record per line
+const LOG_FILE: &str = "audit.log";
+
+/// Latest record of the log, kept beside it so truncating the log shows
+const HEAD_FILE: &str = "audit.head";
+
+/// How often the background task moves logged security events into the audit log
+pub const AUDIT_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+const SECRET_LENGTH: usize = 32;
+const SECRET_AAD: &[u8] = b"lesinki-audit-secret:v1";
+const HMAC_INFO: &[u8] = b"lesinki-audit-log-hmac";
+
+/// `prev_hash` of the first record
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Audit log errors
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("Audit log storage error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Audit log record error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("Audit log key error: {0}")]
+    Key(String),
+}
+
+impl From<AuditError> for String {
+    fn from(err: AuditError) -> String {
+        err.to_string()
+    }
+}
+
+/// Random secret the audit log's HMAC key is derived from, sealed in the vault under its password
+///
+/// It is re-sealed when the vault's secrets are re-wrapped, so the log stays
+/// verifiable across password changes and key rotations.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditSecret {
+    pub encrypted_secret: Vec<u8>,
+    pub salt: Vec<u8>,
+    /// Key derivation the secret was sealed with; `None` for secrets sealed before it was recorded
+    #[serde(default)]
+    pub kdf: Option<SecretKdf>,
+}
+
+impl AuditSecret {
+    pub fn generate(password: &str, kdf: &SecretKdf) -> Result<Self, WalletError> {
+        let mut secret = Zeroizing::new(vec![0u8; SECRET_LENGTH]);
+        OsRng.fill_bytes(&mut secret);
+        Self::seal(&secret, password, kdf)
+    }
+
+    fn seal(secret: &[u8], password: &str, kdf: &SecretKdf) -> Result<Self, WalletError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let encryption_key = Zeroizing::new(derive_encryption_key(password, salt.as_str().as_bytes(), kdf)?);
+        Ok(Self {
+            encrypted_secret: encrypt_data_with_aad(&encryption_key, secret, SECRET_AAD)?,
+            salt: salt.as_str().as_bytes().to_vec(),
+            kdf: Some(kdf.clone()),
+        })
+    }
+
+    pub fn open(&self, password: &str) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+        let legacy = SecretKdf::legacy();
+        let encryption_key = Zeroizing::new(derive_encryption_key(password, &self.salt, self.kdf.as_ref().unwrap_or(&legacy))?);
+        decrypt_data_with_aad(&encryption_key, &self.encrypted_secret, SECRET_AAD)
+            .map(Zeroizing::new)
+            .map_err(|_| WalletError::AuthFailed)
+    }
+
+    pub fn reencrypt(&self, old_password: &str, new_password: &str, kdf: &SecretKdf) -> Result<Self, WalletError> {
+        Self::seal(&self.open(old_password)?, new_password, kdf)
+    }
+}
+
+/// One audit log line
+///
+/// `hash` is the BLAKE3 hash of the record's content and `prev_hash`, chaining it
+/// to the record before; `mac` is an HMAC-SHA256 of `hash` under the vault's key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub event_type: String,
+    pub timestamp: DateTime<Utc>,
+    pub event: SecurityEvent,
+    pub prev_hash: String,
+    pub hash: String,
+    pub mac: String,
+}
+
+impl AuditRecord {
+    fn content_hash(&self) -> Result<String, AuditError> {
+        let content = serde_json::to_vec(&(self.seq, &self.event_type, self.timestamp, &self.event, &self.prev_hash))?;
+        Ok(hex::encode(SecureHash::blake3(&content)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct AuditHead {
+    seq: u64,
+    hash: String,
+    mac: String,
+}
+
+/// What to return from `AuditLog::query`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditQuery {
+    /// `SecurityEvent::kind` names to include; all when empty
+    pub event_types: Vec<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Most recent records to return; all when `None`
+    pub limit: Option<usize>,
+}
+
+/// Something `verify` found wrong
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditProblem {
+    /// 1-based line of the log, or 0 for the head file
+    pub line: usize,
+    pub seq: Option<u64>,
+    pub issue: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub records: usize,
+    pub intact: bool,
+    pub problems: Vec<AuditProblem>,
+}
+
+/// Append-only, hash-chained and authenticated audit log of one profile
+///
+/// Only open while its vault is unlocked, since appending and verifying need the key.
+/// Deleting or editing records breaks the chain or their MAC, and cutting records
+/// off the end no longer matches the head file. Rolling back the log and the head
+/// file together to an earlier state cannot be told apart from that state.
+pub struct AuditLog {
+    path: PathBuf,
+    head_path: PathBuf,
+    key: Zeroizing<Vec<u8>>,
+    head: Option<AuditHead>,
+}
+
+impl AuditLog {
+    /// Open the log in `dir` with the HMAC key derived from the vault's audit secret
+    pub fn open(dir: &Path, secret: &[u8]) -> Result<Self, AuditError> {
+        let mut key = Zeroizing::new(vec![0u8; 32]);
+        Hkdf::<Sha256>::new(None, secret)
+            .expand(HMAC_INFO, &mut key)
+            .map_err(|e| AuditError::Key(e.to_string()))?;
+
+        let mut log = Self {
+            path: dir.join(LOG_FILE),
+            head_path: dir.join(HEAD_FILE),
+            key,
+            head: None,
+        };
+        // Continue from the head file rather than the log, so records cut off the
+        // end leave a gap in the chain instead of being papered over
+        log.head = match log.read_head()? {
+            Some(head) => Some(head),
+            None => log.records()?.into_iter().filter_map(|(_, record)| record.ok()).last()
+                .map(|record| AuditHead { seq: record.seq, hash: record.hash, mac: record.mac }),
+        };
+        Ok(log)
+    }
+
+    fn read_head(&self) -> Result<Option<AuditHead>, AuditError> {
+        if !self.head_path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&fs::read(&self.head_path)?)?))
+    }
+
+    fn mac(&self, hash: &str) -> Result<String, AuditError> {
+        SecureHash::hmac_sha256(&self.key, hash.as_bytes())
+            .map(hex::encode)
+            .map_err(|e| AuditError::Key(e.to_string()))
+    }
+
+    fn mac_is_valid(&self, hash: &str, mac: &str) -> bool {
+        let Ok(mac) = hex::decode(mac) else { return false };
+        <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .map(|mut hmac| {
+                hmac.update(hash.as_bytes());
+                hmac.verify_slice(&mac).is_ok()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Append `events` in order and move the head file on
+    pub fn append(&mut self, events: &[SecurityEvent]) -> Result<(), AuditError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut head = self.head.clone();
+        let mut lines = Vec::new();
+        for event in events {
+            let mut record = AuditRecord {
+                seq: head.as_ref().map_or(0, |head| head.seq + 1),
+                event_type: event.kind().to_string(),
+                timestamp: event.timestamp(),
+                event: event.clone(),
+                prev_hash: head.as_ref().map_or_else(|| GENESIS_HASH.to_string(), |head| head.hash.clone()),
+                hash: String::new(),
+                mac: String::new(),
+            };
+            record.hash = record.content_hash()?;
+            record.mac = self.mac(&record.hash)?;
+
+            serde_json::to_writer(&mut lines, &record)?;
+            lines.push(b'\n');
+            head = Some(AuditHead { seq: record.seq, hash: record.hash, mac: record.mac });
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(&lines)?;
+        file.sync_data()?;
+
+        let storage = VaultStorage::new(self.head_path.clone(), &VaultConfig { backup_count: 0, ..VaultConfig::default() });
+        storage.write(&serde_json::to_vec(&head)?)?;
+        self.head = head;
+        Ok(())
+    }
+
+    /// Every line of the log with its number, parsed if it is a record
+    fn records(&self) -> Result<Vec<(usize, Result<AuditRecord, serde_json::Error>)>, AuditError> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        Ok(fs::read_to_string(&self.path)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i + 1, serde_json::from_str(line)))
+            .collect())
+    }
+
+    /// Walk the whole chain and report every record that was deleted, edited or forged
+    pub fn verify(&self) -> Result<AuditVerification, AuditError> {
+        let mut problems = Vec::new();
+        let mut problem = |line: usize, seq: Option<u64>, issue: String| problems.push(AuditProblem { line, seq, issue });
+
+        let records = self.records()?;
+        let mut expected_seq = 0;
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut last: Option<AuditRecord> = None;
+
+        for (line, record) in &records {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    problem(*line, None, format!("unreadable record: {}", e));
+                    continue;
+                }
+            };
+            let seq = Some(record.seq);
+
+            if record.seq != expected_seq {
+                problem(*line, seq, format!("expected record {}, found {}: records were deleted or reordered", expected_seq, record.seq));
+            } else if record.prev_hash != prev_hash {
+                problem(*line, seq, "does not chain to the previous record: records were deleted or replaced".to_string());
+            }
+            if record.content_hash()? != record.hash {
+                problem(*line, seq, "content does not match its hash: the record was edited".to_string());
+            } else if !self.mac_is_valid(&record.hash, &record.mac) {
+                problem(*line, seq, "invalid MAC: the record was forged or written under another key".to_string());
+            }
+
+            // Carry on from this record so later damage is reported separately
+            expected_seq = record.seq + 1;
+            prev_hash = record.hash.clone();
+            last = Some(record.clone());
+        }
+
+        match (self.read_head()?, &last) {
+            (Some(head), _) if !self.mac_is_valid(&head.hash, &head.mac) => {
+                problem(0, Some(head.seq), "the head file is not authentic".to_string());
+            }
+            (Some(head), Some(last)) if head.seq != last.seq || head.hash != last.hash => {
+                problem(0, Some(head.seq), format!("the log ends at record {} but was written up to {}: records were removed from the end", last.seq, head.seq));
+            }
+            (Some(head), None) => {
+                problem(0, Some(head.seq), format!("the log is empty but was written up to record {}", head.seq));
+            }
+            (None, Some(_)) => problem(0, None, "the head file is missing".to_string()),
+            _ => {}
+        }
+
+        Ok(AuditVerification {
+            records: records.len(),
+            intact: problems.is_empty(),
+            problems,
+        })
+    }
+
+    /// Readable records matching `query`, most recent first
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, AuditError> {
+        let mut matches: Vec<AuditRecord> = self.records()?
+            .into_iter()
+            .filter_map(|(_, record)| record.ok())
+            .filter(|record| query.event_types.is_empty() || query.event_types.contains(&record.event_type))
+            .filter(|record| query.from.map_or(true, |from| record.timestamp >= from))
+            .filter(|record| query.to.map_or(true, |to| record.timestamp <= to))
+            .collect();
+
+        matches.reverse();
+        if let Some(limit) = query.limit {
+            matches.truncate(limit);
+        }
+        Ok(matches)
+    }
+}
+
+include!("audit_tests.rs");
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn accessed(key_id: &str, timestamp: DateTime<Utc>) -> SecurityEvent {
+        SecurityEvent::KeyAccessed { key_id: key_id.to_string(), timestamp }
+    }
+
+    fn violation(timestamp: DateTime<Utc>) -> SecurityEvent {
+        SecurityEvent::SecurityViolation {
+            violation_type: "daily_limit".to_string(),
+            details: "transfer_tokens: over the limit".to_string(),
+            timestamp,
+        }
+    }
+
+    /// A log of five records, one per minute, the third a violation
+    fn written_log(dir: &Path) -> AuditLog {
+        let start = Utc::now() - chrono::Duration::minutes(10);
+        let events: Vec<SecurityEvent> = (0..5)
+            .map(|i| {
+                let at = start + chrono::Duration::minutes(i);
+                if i == 2 { violation(at) } else { accessed(&format!("wallet:{}", i), at) }
+            })
+            .collect();
+
+        let mut log = AuditLog::open(dir, SECRET).unwrap();
+        log.append(&events[..2]).unwrap();
+        log.append(&events[2..]).unwrap();
+        log
+    }
+
+    fn rewrite_lines<F: FnOnce(&mut Vec<String>)>(dir: &Path, edit: F) {
+        let path = dir.join(LOG_FILE);
+        let mut lines: Vec<String> = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect();
+        edit(&mut lines);
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_untouched_log_verifies() {
+        let dir = tempdir().unwrap();
+        let log = written_log(dir.path());
+
+        let verification = log.verify().unwrap();
+        assert_eq!(verification.records, 5);
+        assert!(verification.intact, "{:?}", verification.problems);
+
+        // Reopening continues the same chain
+        let mut reopened = AuditLog::open(dir.path(), SECRET).unwrap();
+        reopened.append(&[accessed("wallet:5", Utc::now())]).unwrap();
+        let verification = reopened.verify().unwrap();
+        assert_eq!(verification.records, 6);
+        assert!(verification.intact, "{:?}", verification.problems);
+    }
+
+    #[test]
+    fn test_deleted_record_detected() {
+        let dir = tempdir().unwrap();
+        let log = written_log(dir.path());
+        rewrite_lines(dir.path(), |lines| {
+            lines.remove(2);
+        });
+
+        let verification = log.verify().unwrap();
+        assert!(!verification.intact);
+        assert_eq!(verification.problems.len(), 1);
+        assert_eq!(verification.problems[0].seq, Some(3));
+    }
+
+    #[test]
+    fn test_edited_record_detected() {
+        let dir = tempdir().unwrap();
+        let log = written_log(dir.path());
+        rewrite_lines(dir.path(), |lines| {
+            lines[2] = lines[2].replace("daily_limit", "nothing_to_see");
+        });
+
+        let verification = log.verify().unwrap();
+        assert!(!verification.intact);
+        assert_eq!(verification.problems[0].line, 3);
+        assert!(verification.problems[0].issue.contains("edited"));
+    }
+
+    #[test]
+    fn test_rehashed_forgery_detected() {
+        let dir = tempdir().unwrap();
+        let log = written_log(dir.path());
+
+        // Recomputing the hash after an edit does not help without the key
+        rewrite_lines(dir.path(), |lines| {
+            let mut record: AuditRecord = serde_json::from_str(&lines[4]).unwrap();
+            record.event = accessed("wallet:forged", record.timestamp);
+            record.hash = record.content_hash().unwrap();
+            lines[4] = serde_json::to_string(&record).unwrap();
+        });
+
+        let problems = log.verify().unwrap().problems;
+        assert!(problems.iter().any(|problem| problem.seq == Some(4) && problem.issue.contains("MAC")), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_truncated_log_detected() {
+        let dir = tempdir().unwrap();
+        let log = written_log(dir.path());
+        rewrite_lines(dir.path(), |lines| lines.truncate(3));
+
+        let verification = log.verify().unwrap();
+        assert!(!verification.intact);
+        assert_eq!(verification.problems[0].line, 0);
+        assert!(verification.problems[0].issue.contains("removed from the end"));
+    }
+
+    #[test]
+    fn test_other_key_does_not_verify() {
+        let dir = tempdir().unwrap();
+        written_log(dir.path());
+
+        let other = AuditLog::open(dir.path(), b"another vault's audit secret....").unwrap();
+        let verification = other.verify().unwrap();
+        assert!(!verification.intact);
+        assert_eq!(verification.problems.len(), 6);
+    }
+
+    #[test]
+    fn test_query_by_type_and_time() {
+        let dir = tempdir().unwrap();
+        let log = written_log(dir.path());
+        let all = log.query(&AuditQuery::default()).unwrap();
+        assert_eq!(all.iter().map(|record| record.seq).collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+
+        let violations = log.query(&AuditQuery {
+            event_types: vec!["security_violation".to_string()],
+            ..AuditQuery::default()
+        }).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].event_type, "security_violation");
+
+        let window = log.query(&AuditQuery {
+            from: Some(all[3].timestamp),
+            to: Some(all[1].timestamp),
+            limit: Some(2),
+            ..AuditQuery::default()
+        }).unwrap();
+        assert_eq!(window.iter().map(|record| record.seq).collect::<Vec<_>>(), vec![3, 2]);
+    }
+
+    #[test]
+    fn test_audit_secret_survives_password_change() {
+        let secret = AuditSecret::generate("old password", &SecretKdf::legacy()).unwrap();
+        let opened = secret.open("old password").unwrap();
+
+        // Resealing records the costs it used, which opening then follows
+        let cheap = SecretKdf {
+            kdf_params: crate::keystore::KdfParams { memory_cost: 256, time_cost: 1, ..crate::keystore::KdfParams::legacy() },
+            ..SecretKdf::legacy()
+        };
+        let resealed = secret.reencrypt("old password", "new password", &cheap).unwrap();
+        assert_eq!(resealed.kdf, Some(cheap));
+        assert_eq!(&resealed.open("new password").unwrap()[..], &opened[..]);
+        assert!(matches!(resealed.open("old password"), Err(WalletError::AuthFailed)));
+    }
+}
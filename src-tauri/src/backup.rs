@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::keystore::{self, KeystoreError};
+use crate::seeds::SeedEntry;
+use crate::session::AutoLockPolicy;
+use crate::vault::VaultConfig;
+use crate::{Wallet, Wallets};
+
+/// Version of the decrypted backup bundle layout
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Backup bundle errors
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("Backup password must be at least 8 characters")]
+    WeakPassword,
+    #[error("Unsupported backup version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Backup is not a wallet backup bundle: {0}")]
+    Malformed(String),
+    #[error("Backup encryption error: {0}")]
+    Keystore(#[from] KeystoreError),
+}
+
+impl From<BackupError> for String {
+    fn from(err: BackupError) -> String {
+        err.to_string()
+    }
+}
+
+/// Profile settings carried in a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSettings {
+    pub vault_config: VaultConfig,
+    pub auto_lock: AutoLockPolicy,
+}
+
+/// A wallet and its plaintext secret key, as stored inside the encrypted bundle
+///
+/// The wallet's own `encrypted_private_key` and `salt` are cleared: they are tied
+/// to the source vault's password and are re-created on import.
+#[derive(Serialize, Deserialize)]
+pub struct BackupWallet {
+    pub wallet: Wallet,
+    /// Empty for watch-only wallets
+    pub secret_key: Vec<u8>,
+}
+
+impl Drop for BackupWallet {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
+/// A stored seed phrase in plaintext, as kept inside the encrypted bundle
+///
+/// Like `BackupWallet::wallet`, the entry's ciphertext and salt are cleared.
+#[derive(Serialize, Deserialize)]
+pub struct BackupSeed {
+    pub seed: SeedEntry,
+    pub phrase: String,
+    /// Only set when the vault stores the passphrase
+    pub passphrase: Option<String>,
+}
+
+impl Drop for BackupSeed {
+    fn drop(&mut self) {
+        self.phrase.zeroize();
+        self.passphrase.zeroize();
+    }
+}
+
+/// Everything needed to rebuild a vault on another machine
+///
+/// The bundle is serialized to JSON and sealed in a keystore container under the
+/// backup password, which is independent of the vault password.
+#[derive(Serialize, Deserialize)]
+pub struct BackupBundle {
+    pub backup_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub profile: String,
+    pub wallets: Vec<BackupWallet>,
+    #[serde(default)]
+    pub seeds: Vec<BackupSeed>,
+    pub settings: BackupSettings,
+}
+
+impl BackupBundle {
+    pub fn new(profile: &str, wallets: Vec<BackupWallet>, seeds: Vec<BackupSeed>, settings: BackupSettings) -> Self {
+        Self {
+            backup_version: BACKUP_FORMAT_VERSION,
+            created_at: Utc::now(),
+            profile: profile.to_string(),
+            wallets,
+            seeds,
+            settings,
+        }
+    }
+
+    /// Encrypt the bundle under `password`
+    pub fn seal(&self, password: &str) -> Result<Vec<u8>, BackupError> {
+        if password.chars().count() < 8 {
+            return Err(BackupError::WeakPassword);
+        }
+
+        let json = Zeroizing::new(
+            serde_json::to_vec(self).map_err(|e| BackupError::Malformed(e.to_string()))?
+        );
+        Ok(keystore::seal(password, &json)?)
+    }
+
+    /// Decrypt a bundle written by `seal`
+    pub fn open(password: &str, data: &[u8]) -> Result<Self, BackupError> {
+        let opened = keystore::open(password, data)?;
+        let plaintext = Zeroizing::new(opened.plaintext);
+
+        let bundle: BackupBundle = serde_json::from_slice(&plaintext)
+            .map_err(|e| BackupError::Malformed(e.to_string()))?;
+        if bundle.backup_version > BACKUP_FORMAT_VERSION {
+            return Err(BackupError::UnsupportedVersion(bundle.backup_version));
+        }
+        Ok(bundle)
+    }
+}
+
+/// What importing a bundle changes, by public key
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupImportReport {
+    /// Wallets not yet in the vault
+    pub added: Vec<String>,
+    /// Watch-only wallets in the vault for which the backup holds the key
+    pub upgraded: Vec<String>,
+    /// Wallets already in the vault, or repeated in the backup, left untouched
+    pub duplicates: Vec<String>,
+    /// Seeds not yet in the vault, by the address of their first account
+    #[serde(default)]
+    pub seeds_added: Vec<String>,
+    /// Seeds already in the vault, or repeated in the backup
+    #[serde(default)]
+    pub seed_duplicates: Vec<String>,
+    pub settings_restored: bool,
+    pub dry_run: bool,
+}
+
+/// Classify each backup wallet against the vault without changing anything
+pub fn plan_import(existing: &Wallets, bundle: &BackupBundle) -> BackupImportReport {
+    let mut report = BackupImportReport::default();
+    let mut seen = HashSet::new();
+
+    for entry in &bundle.wallets {
+        let public_key = &entry.wallet.public_key;
+        if !seen.insert(public_key.clone()) {
+            report.duplicates.push(public_key.clone());
+            continue;
+        }
+
+        match existing.wallets.iter().find(|wallet| wallet.public_key == *public_key) {
+            None => report.added.push(public_key.clone()),
+            Some(wallet) if wallet.is_watch_only() && !entry.secret_key.is_empty() => {
+                report.upgraded.push(public_key.clone())
+            }
+            Some(_) => report.duplicates.push(public_key.clone()),
+        }
+    }
+
+    // A seed is the same when it derives the same first account under the same scheme
+    let mut seen_seeds = Vec::new();
+    for entry in &bundle.seeds {
+        let seed = &entry.seed;
+        let is_duplicate = seen_seeds.contains(&(&seed.fingerprint, &seed.path_template))
+            || existing.seeds.iter().any(|other| other.fingerprint == seed.fingerprint && other.path_template == seed.path_template);
+        seen_seeds.push((&seed.fingerprint, &seed.path_template));
+
+        if is_duplicate {
+            report.seed_duplicates.push(seed.fingerprint.clone());
+        } else {
+            report.seeds_added.push(seed.fingerprint.clone());
+        }
+    }
+    report
+}
+
+include!("backup_tests.rs");
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use crate::WalletKind;
+    use crate::derivation::PathTemplate;
+
+    fn wallet(public_key: &str, kind: WalletKind) -> Wallet {
+        let now = Utc::now();
+        Wallet {
+            id: format!("id-{}", public_key),
+            public_key: public_key.to_string(),
+            kind,
+            encrypted_private_key: vec![],
+            salt: vec![],
+            kdf: None,
+            balance: 0,
+            created_at: now,
+            last_updated: now,
+            network: "mainnet".to_string(),
+            metadata: Default::default(),
+            derivation_path: None,
+            seed_id: None,
+            secret_version: 0,
+        }
+    }
+
+    fn entry(public_key: &str, secret_key: Vec<u8>) -> BackupWallet {
+        let kind = if secret_key.is_empty() { WalletKind::WatchOnly } else { WalletKind::Keyed };
+        BackupWallet {
+            wallet: wallet(public_key, kind),
+            secret_key,
+        }
+    }
+
+    fn settings() -> BackupSettings {
+        BackupSettings {
+            vault_config: VaultConfig::default(),
+            auto_lock: AutoLockPolicy::default(),
+        }
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let bundle = BackupBundle::new("treasury", vec![entry("A", vec![7; 64])], vec![], settings());
+        let sealed = bundle.seal("backup-password").unwrap();
+
+        let opened = BackupBundle::open("backup-password", &sealed).unwrap();
+        assert_eq!(opened.profile, "treasury");
+        assert_eq!(opened.wallets.len(), 1);
+        assert_eq!(opened.wallets[0].secret_key, vec![7; 64]);
+
+        assert!(BackupBundle::open("vault-password", &sealed).is_err());
+    }
+
+    #[test]
+    fn test_weak_backup_password_rejected() {
+        let bundle = BackupBundle::new("default", vec![], vec![], settings());
+        assert!(matches!(bundle.seal("short"), Err(BackupError::WeakPassword)));
+    }
+
+    #[test]
+    fn test_plan_import_detects_duplicates_by_public_key() {
+        let mut existing = Wallets::empty();
+        existing.wallets.push(wallet("A", WalletKind::Keyed));
+        existing.wallets.push(wallet("B", WalletKind::WatchOnly));
+        existing.wallets.push(wallet("C", WalletKind::WatchOnly));
+
+        let bundle = BackupBundle::new("default", vec![
+            entry("A", vec![1; 64]),
+            entry("B", vec![2; 64]),
+            entry("C", vec![]),
+            entry("D", vec![4; 64]),
+            entry("D", vec![4; 64]),
+        ], vec![], settings());
+
+        let report = plan_import(&existing, &bundle);
+        assert_eq!(report.added, vec!["D".to_string()]);
+        assert_eq!(report.upgraded, vec!["B".to_string()]);
+        assert_eq!(report.duplicates, vec!["A".to_string(), "C".to_string(), "D".to_string()]);
+    }
+
+    #[test]
+    fn test_plan_import_matches_seeds_by_first_account() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let stored = SeedEntry::new("main", phrase, None, false, None, PathTemplate::Bip44Change, "vault-password", &crate::keystore::SecretKdf::legacy()).unwrap();
+        let mut existing = Wallets::empty();
+        existing.seeds.push(stored.clone());
+
+        let backup_seed = |template: PathTemplate| BackupSeed {
+            seed: SeedEntry { path_template: template, ..stored.clone() },
+            phrase: phrase.to_string(),
+            passphrase: None,
+        };
+        let bundle = BackupBundle::new("default", vec![], vec![
+            backup_seed(PathTemplate::Bip44Change),
+            backup_seed(PathTemplate::Bip44),
+            backup_seed(PathTemplate::Bip44),
+        ], settings());
+
+        let report = plan_import(&existing, &bundle);
+        assert_eq!(report.seeds_added.len(), 1);
+        assert_eq!(report.seed_duplicates.len(), 2);
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use solana_sdk::signature::{keypair_from_seed, Keypair};
+use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Offset added to an index to make it hardened
+pub const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// SLIP-0044 coin type of Solana
+pub const SOLANA_COIN_TYPE: u32 = 501;
+
+/// HMAC key for the SLIP-0010 ed25519 master key
+const ED25519_CURVE_KEY: &[u8] = b"ed25519 seed";
+
+/// Placeholder for the account index in custom path templates
+const ACCOUNT_PLACEHOLDER: &str = "{account}";
+
+/// Key derivation errors
+#[derive(Error, Debug)]
+pub enum DerivationError {
+    #[error("Invalid derivation path '{0}'")]
+    InvalidPath(String),
+    #[error("ed25519 only supports hardened derivation, '{0}' is not hardened")]
+    NotHardened(String),
+    #[error("Seed must be 16 to 64 bytes, got {0}")]
+    InvalidSeed(usize),
+    #[error("Account index {0} is out of range")]
+    InvalidAccount(u32),
+}
+
+impl From<DerivationError> for String {
+    fn from(err: DerivationError) -> String {
+        err.to_string()
+    }
+}
+
+/// A fully hardened BIP32 path such as `m/44'/501'/0'/0'`
+///
+/// Indices are stored without the hardened offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath {
+    indices: Vec<u32>,
+}
+
+impl DerivationPath {
+    pub fn new(indices: Vec<u32>) -> Result<Self, DerivationError> {
+        if let Some(index) = indices.iter().find(|index| **index >= HARDENED_OFFSET) {
+            return Err(DerivationError::InvalidAccount(*index));
+        }
+        Ok(Self { indices })
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = DerivationError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let mut segments = path.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(DerivationError::InvalidPath(path.to_string()));
+        }
+
+        let indices = segments
+            .map(|segment| {
+                let index = segment
+                    .strip_suffix('\'')
+                    .or_else(|| segment.strip_suffix('h'))
+                    .or_else(|| segment.strip_suffix('H'))
+                    .ok_or_else(|| DerivationError::NotHardened(segment.to_string()))?;
+                index.parse::<u32>()
+                    .ok()
+                    .filter(|index| *index < HARDENED_OFFSET)
+                    .ok_or_else(|| DerivationError::InvalidPath(path.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { indices })
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.indices {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// How a seed phrase is turned into the keypair of a given account
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PathTemplate {
+    /// `m/44'/501'/{account}'/0'`, used by Phantom, Solflare and `solana-keygen --derivation-path`
+    #[default]
+    Bip44Change,
+    /// `m/44'/501'/{account}'`, used by Trust Wallet and some hardware wallet setups
+    Bip44,
+    /// No derivation: the first 32 bytes of the BIP39 seed, as `solana-keygen new` without a
+    /// derivation path and earlier versions of this wallet. Only account 0 exists.
+    SeedRoot,
+    /// Any hardened path, with `{account}` standing for the account index
+    Custom { template: String },
+}
+
+impl PathTemplate {
+    /// The path of `account`, or `None` for `SeedRoot`
+    pub fn path(&self, account: u32) -> Result<Option<DerivationPath>, DerivationError> {
+        if account >= HARDENED_OFFSET {
+            return Err(DerivationError::InvalidAccount(account));
+        }
+
+        let path = match self {
+            PathTemplate::Bip44Change => DerivationPath::new(vec![44, SOLANA_COIN_TYPE, account, 0])?,
+            PathTemplate::Bip44 => DerivationPath::new(vec![44, SOLANA_COIN_TYPE, account])?,
+            PathTemplate::SeedRoot if account == 0 => return Ok(None),
+            PathTemplate::SeedRoot => return Err(DerivationError::InvalidAccount(account)),
+            PathTemplate::Custom { template } => {
+                template.replace(ACCOUNT_PLACEHOLDER, &account.to_string()).parse()?
+            }
+        };
+        Ok(Some(path))
+    }
+
+    /// Keypair of `account` derived from a BIP39 seed
+    pub fn derive_keypair(&self, seed: &[u8], account: u32) -> Result<Keypair, DerivationError> {
+        match self.path(account)? {
+            Some(path) => ExtendedKey::derive(seed, &path)?.keypair(),
+            None => seed_keypair(seed),
+        }
+    }
+}
+
+/// A SLIP-0010 ed25519 node: secret key and chain code
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct ExtendedKey {
+    secret_key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+impl ExtendedKey {
+    /// Master node of a seed
+    pub fn master(seed: &[u8]) -> Result<Self, DerivationError> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(DerivationError::InvalidSeed(seed.len()));
+        }
+        Ok(Self::from_hmac(ED25519_CURVE_KEY, &[seed]))
+    }
+
+    /// Hardened child at `index`, given without the hardened offset
+    pub fn child(&self, index: u32) -> Result<Self, DerivationError> {
+        if index >= HARDENED_OFFSET {
+            return Err(DerivationError::InvalidAccount(index));
+        }
+        let hardened = (index | HARDENED_OFFSET).to_be_bytes();
+        Ok(Self::from_hmac(&self.chain_code, &[&[0u8][..], &self.secret_key[..], &hardened[..]]))
+    }
+
+    /// Node at `path` below the master node of `seed`
+    pub fn derive(seed: &[u8], path: &DerivationPath) -> Result<Self, DerivationError> {
+        path.indices().iter().try_fold(Self::master(seed)?, |node, index| node.child(*index))
+    }
+
+    pub fn secret_key(&self) -> &[u8; 32] {
+        &self.secret_key
+    }
+
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    pub fn keypair(&self) -> Result<Keypair, DerivationError> {
+        seed_keypair(&self.secret_key)
+    }
+
+    fn from_hmac(key: &[u8], parts: &[&[u8]]) -> Self {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        for part in parts {
+            mac.update(part);
+        }
+        let mut output = mac.finalize().into_bytes();
+
+        let mut node = Self {
+            secret_key: [0u8; 32],
+            chain_code: [0u8; 32],
+        };
+        node.secret_key.copy_from_slice(&output[..32]);
+        node.chain_code.copy_from_slice(&output[32..]);
+        output.as_mut_slice().zeroize();
+        node
+    }
+}
+
+fn seed_keypair(seed: &[u8]) -> Result<Keypair, DerivationError> {
+    keypair_from_seed(seed).map_err(|_| DerivationError::InvalidSeed(seed.len()))
+}
+
+include!("derivation_tests.rs");
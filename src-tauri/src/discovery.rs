@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use thiserror::Error;
+
+use crate::derivation::{DerivationError, PathTemplate};
+
+/// Account discovery errors
+#[derive(Error, Debug)]
+pub enum DiscoveryError {
+    #[error("Derivation failed: {0}")]
+    Derivation(#[from] DerivationError),
+    #[error("RPC error while scanning {0}: {1}")]
+    Rpc(String, String),
+    #[error("Invalid discovery config: {0}")]
+    InvalidConfig(String),
+}
+
+impl From<DiscoveryError> for String {
+    fn from(err: DiscoveryError) -> String {
+        err.to_string()
+    }
+}
+
+/// Account discovery configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryConfig {
+    /// Path schemes to scan, each independently
+    pub templates: Vec<PathTemplate>,
+    /// Stop a scheme after this many consecutive unused accounts
+    pub gap_limit: u32,
+    /// Upper bound on accounts scanned per scheme
+    pub max_accounts: u32,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            templates: vec![PathTemplate::Bip44Change, PathTemplate::Bip44, PathTemplate::SeedRoot],
+            gap_limit: 5,
+            max_accounts: 100,
+        }
+    }
+}
+
+impl DiscoveryConfig {
+    pub fn validate(&self) -> Result<(), DiscoveryError> {
+        if self.templates.is_empty() {
+            return Err(DiscoveryError::InvalidConfig("at least one path template is required".to_string()));
+        }
+        if self.gap_limit == 0 || self.gap_limit > 50 {
+            return Err(DiscoveryError::InvalidConfig("gap limit must be between 1 and 50".to_string()));
+        }
+        if self.max_accounts == 0 || self.max_accounts > 1000 {
+            return Err(DiscoveryError::InvalidConfig("max accounts must be between 1 and 1000".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// On-chain footprint of one address
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountActivity {
+    pub balance: u64,
+    /// Most recent transaction signature, if the address has any history
+    pub last_signature: Option<String>,
+    pub last_block_time: Option<i64>,
+}
+
+impl AccountActivity {
+    pub fn is_used(&self) -> bool {
+        self.balance > 0 || self.last_signature.is_some()
+    }
+}
+
+/// A used account found by the scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredAccount {
+    pub template: PathTemplate,
+    pub account_index: u32,
+    pub derivation_path: Option<String>,
+    pub public_key: String,
+    pub activity: AccountActivity,
+}
+
+/// Scan every configured scheme of a BIP39 seed, probing each derived address with `probe`
+///
+/// Within a scheme, accounts are derived in order until `gap_limit` consecutive
+/// unused accounts were seen or `max_accounts` is reached.
+pub fn discover_accounts<F>(seed: &[u8], config: &DiscoveryConfig, mut probe: F) -> Result<Vec<DiscoveredAccount>, DiscoveryError>
+where
+    F: FnMut(&Pubkey) -> Result<AccountActivity, DiscoveryError>,
+{
+    config.validate()?;
+    let mut found = Vec::new();
+
+    for template in &config.templates {
+        let mut gap = 0;
+        let mut account = 0;
+
+        while account < config.max_accounts && gap < config.gap_limit {
+            // Seed-root has a single account
+            if *template == PathTemplate::SeedRoot && account > 0 {
+                break;
+            }
+
+            let pubkey = template.derive_keypair(seed, account)?.pubkey();
+            let activity = probe(&pubkey)?;
+
+            if activity.is_used() {
+                gap = 0;
+                found.push(DiscoveredAccount {
+                    template: template.clone(),
+                    account_index: account,
+                    derivation_path: template.path(account)?.map(|path| path.to_string()),
+                    public_key: pubkey.to_string(),
+                    activity,
+                });
+            } else {
+                gap += 1;
+            }
+            account += 1;
+        }
+    }
+    Ok(found)
+}
+
+/// Balance and latest signature of `pubkey` from an RPC node
+pub fn probe_account(rpc_client: &RpcClient, pubkey: &Pubkey) -> Result<AccountActivity, DiscoveryError> {
+    let rpc_error = |e: solana_client::client_error::ClientError| DiscoveryError::Rpc(pubkey.to_string(), e.to_string());
+
+    let balance = rpc_client.get_balance(pubkey).map_err(rpc_error)?;
+    let latest = rpc_client
+        .get_signatures_for_address_with_config(pubkey, GetConfirmedSignaturesForAddress2Config {
+            limit: Some(1),
+            ..Default::default()
+        })
+        .map_err(rpc_error)?
+        .into_iter()
+        .next();
+
+    Ok(AccountActivity {
+        balance,
+        last_block_time: latest.as_ref().and_then(|status| status.block_time),
+        last_signature: latest.map(|status| status.signature),
+    })
+}
+
+include!("discovery_tests.rs");
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn seed() -> [u8; 64] {
+        bip39::Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        ).unwrap().to_seed("")
+    }
+
+    fn address(template: &PathTemplate, account: u32) -> Pubkey {
+        template.derive_keypair(&seed(), account).unwrap().pubkey()
+    }
+
+    fn used() -> AccountActivity {
+        AccountActivity {
+            balance: 1_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scan_stops_after_gap() {
+        let active = [address(&PathTemplate::Bip44Change, 0), address(&PathTemplate::Bip44Change, 3)];
+        let config = DiscoveryConfig {
+            templates: vec![PathTemplate::Bip44Change],
+            gap_limit: 3,
+            max_accounts: 100,
+        };
+
+        let mut probed = 0;
+        let found = discover_accounts(&seed(), &config, |pubkey| {
+            probed += 1;
+            Ok(if active.contains(pubkey) { used() } else { AccountActivity::default() })
+        }).unwrap();
+
+        assert_eq!(found.iter().map(|a| a.account_index).collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(found[1].derivation_path.as_deref(), Some("m/44'/501'/3'/0'"));
+        // Accounts 0-3, then the gap of 4, 5 and 6
+        assert_eq!(probed, 7);
+    }
+
+    #[test]
+    fn test_small_gap_misses_distant_accounts() {
+        let active = [address(&PathTemplate::Bip44Change, 0), address(&PathTemplate::Bip44Change, 3)];
+        let config = DiscoveryConfig {
+            templates: vec![PathTemplate::Bip44Change],
+            gap_limit: 2,
+            max_accounts: 100,
+        };
+
+        let found = discover_accounts(&seed(), &config, |pubkey| {
+            Ok(if active.contains(pubkey) { used() } else { AccountActivity::default() })
+        }).unwrap();
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn test_scans_each_template_and_history_counts_as_use() {
+        let history_only = address(&PathTemplate::Bip44, 0);
+        let seed_root = address(&PathTemplate::SeedRoot, 0);
+
+        let mut probed = 0;
+        let found = discover_accounts(&seed(), &DiscoveryConfig::default(), |pubkey| {
+            probed += 1;
+            Ok(if *pubkey == history_only {
+                AccountActivity {
+                    last_signature: Some("sig".to_string()),
+                    ..Default::default()
+                }
+            } else if *pubkey == seed_root {
+                used()
+            } else {
+                AccountActivity::default()
+            })
+        }).unwrap();
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].template, PathTemplate::Bip44);
+        assert_eq!(found[1].template, PathTemplate::SeedRoot);
+        assert_eq!(found[1].derivation_path, None);
+        // Bip44Change: 5 unused; Bip44: 1 used + 5 unused; SeedRoot: its single account
+        assert_eq!(probed, 12);
+    }
+
+    #[test]
+    fn test_probe_errors_abort_scan() {
+        let result = discover_accounts(&seed(), &DiscoveryConfig::default(), |pubkey| {
+            Err(DiscoveryError::Rpc(pubkey.to_string(), "connection refused".to_string()))
+        });
+        assert!(matches!(result, Err(DiscoveryError::Rpc(_, _))));
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls::{Certificate, CertificateError, ClientConfig, Error as TlsError, OwnedTrustAnchor, RootCertStore, ServerName};
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::security::{get_security_manager, SecurityEvent, SecurityManager};
+
+/// Timeout of every outbound request, the same as solana's own RPC clients
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Which certificate checks a client applies on top of the usual chain and name verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointPolicy {
+    /// The wallet's own RPC, Jupiter, Jito and update endpoints: the host must be
+    /// allowlisted and present a pinned public key if it has pins
+    Pinned,
+    /// Hosts named by on-chain data, e.g. NFT metadata URIs: any host is allowed,
+    /// but one with pins must still match them
+    Public,
+}
+
+/// Verifies server certificates against the web PKI roots, then against the security
+/// manager's certificate pinning policy
+struct PinnedVerifier {
+    webpki: WebPkiVerifier,
+    security: Arc<SecurityManager>,
+    policy: EndpointPolicy,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        let domain = match server_name {
+            ServerName::DnsName(name) => name.as_ref().to_string(),
+            ServerName::IpAddress(ip) => ip.to_string(),
+            _ => return Err(TlsError::General("Unsupported server name".to_string())),
+        };
+        let allowlisted_only = self.policy == EndpointPolicy::Pinned;
+
+        let verified = match self.webpki.verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now) {
+            Ok(verified) => verified,
+            Err(e) => {
+                // A chain that does not verify fails the pinning check as well
+                if self.security.certificate_pinning().applies_to(&domain, allowlisted_only) {
+                    self.security.log_event(SecurityEvent::CertificatePinned {
+                        domain,
+                        valid: false,
+                        timestamp: chrono::Utc::now(),
+                    });
+                }
+                return Err(e);
+            }
+        };
+
+        self.security
+            .validate_certificate(&domain, &end_entity.0, allowlisted_only)
+            .map_err(|e| TlsError::InvalidCertificate(CertificateError::Other(Arc::new(e))))?;
+        Ok(verified)
+    }
+}
+
+/// The Mozilla root store shipped with the binary
+fn webpki_roots() -> RootCertStore {
+    let mut roots = RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)
+    }));
+    roots
+}
+
+fn tls_config(security: Arc<SecurityManager>, roots: RootCertStore, policy: EndpointPolicy) -> ClientConfig {
+    let verifier = PinnedVerifier {
+        webpki: WebPkiVerifier::new(roots, None),
+        security,
+        policy,
+    };
+    ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth()
+}
+
+/// Build a client that verifies certificates against `roots` and `security`'s pinning policy
+pub fn build_client(
+    security: Arc<SecurityManager>,
+    roots: RootCertStore,
+    policy: EndpointPolicy,
+) -> reqwest::Result<Client> {
+    Client::builder()
+        .use_preconfigured_tls(tls_config(security, roots, policy))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+}
+
+static PINNED_TLS: Lazy<ClientConfig> = Lazy::new(|| {
+    tls_config(get_security_manager().clone(), webpki_roots(), EndpointPolicy::Pinned)
+});
+
+// Like `Client::new()`, these only fail if the TLS backend cannot be set up at all
+static PINNED_CLIENT: Lazy<Client> = Lazy::new(|| {
+    build_client(get_security_manager().clone(), webpki_roots(), EndpointPolicy::Pinned)
+        .expect("pinned HTTP client configuration is valid")
+});
+
+static PUBLIC_CLIENT: Lazy<Client> = Lazy::new(|| {
+    build_client(get_security_manager().clone(), webpki_roots(), EndpointPolicy::Public)
+        .expect("public HTTP client configuration is valid")
+});
+
+/// Shared client for the wallet's own endpoints
+pub fn client() -> Client {
+    PINNED_CLIENT.clone()
+}
+
+/// Shared client for hosts named by on-chain data
+pub fn public_client() -> Client {
+    PUBLIC_CLIENT.clone()
+}
+
+/// Blocking RPC client for `url` that sends its requests through a pinned client
+///
+/// Each RPC client drives its requests on a runtime of its own, so it gets its own
+/// connection pool rather than sharing `client()`'s, whose connections belong to the app's runtime.
+pub fn rpc_client(url: impl ToString) -> RpcClient {
+    let client = Client::builder()
+        .use_preconfigured_tls(PINNED_TLS.clone())
+        .default_headers(HttpSender::default_headers())
+        .timeout(REQUEST_TIMEOUT)
+        .pool_idle_timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("pinned RPC client configuration is valid");
+    RpcClient::new_sender(
+        HttpSender::new_with_client(url, client),
+        RpcClientConfig::with_commitment(CommitmentConfig::default()),
+    )
+}
+
+include!("http_client_tests.rs");
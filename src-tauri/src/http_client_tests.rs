@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crossbeam::channel::{self, Receiver};
+    use pretty_assertions::assert_eq;
+    use rustls::{PrivateKey, ServerConfig};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+    use crate::security::CertificatePinning;
+
+    const HOST: &str = "localhost";
+
+    /// A TLS server on a local port with a self-signed certificate for `localhost`
+    struct Server {
+        port: u16,
+        certificate: Vec<u8>,
+    }
+
+    impl Server {
+        async fn start() -> Self {
+            let generated = rcgen::generate_simple_self_signed(vec![HOST.to_string()]).unwrap();
+            let certificate = generated.serialize_der().unwrap();
+            let config = ServerConfig::builder()
+                .with_safe_defaults()
+                .with_no_client_auth()
+                .with_single_cert(vec![Certificate(certificate.clone())], PrivateKey(generated.serialize_private_key_der()))
+                .unwrap();
+            let acceptor = TlsAcceptor::from(Arc::new(config));
+            let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let acceptor = acceptor.clone();
+                    tokio::spawn(async move {
+                        // Handshakes the client refuses end here
+                        let Ok(mut stream) = acceptor.accept(stream).await else { return };
+                        let mut request = [0u8; 1024];
+                        let _ = stream.read(&mut request).await;
+                        let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok").await;
+                        let _ = stream.shutdown().await;
+                    });
+                }
+            });
+            Self { port, certificate }
+        }
+
+        fn url(&self) -> String {
+            format!("https://{}:{}/", HOST, self.port)
+        }
+
+        /// A root store that trusts only this server's certificate
+        fn roots(&self) -> RootCertStore {
+            let mut roots = RootCertStore::empty();
+            roots.add(&Certificate(self.certificate.clone())).unwrap();
+            roots
+        }
+
+        fn pin(&self) -> String {
+            CertificatePinning::spki_pin(&self.certificate).unwrap()
+        }
+    }
+
+    fn pinning(allowed_domains: &[&str], pins: Vec<String>) -> CertificatePinning {
+        CertificatePinning {
+            enabled: true,
+            allowed_domains: allowed_domains.iter().map(|domain| domain.to_string()).collect(),
+            public_key_pins: HashMap::from([(HOST.to_string(), pins)]),
+            expiry_checking: true,
+        }
+    }
+
+    fn client_with(pinning: CertificatePinning, roots: RootCertStore, policy: EndpointPolicy) -> (Client, Receiver<SecurityEvent>) {
+        let (tx, rx) = channel::unbounded();
+        let security = Arc::new(SecurityManager::new(tx).with_certificate_pinning(pinning));
+        (build_client(security, roots, policy).unwrap(), rx)
+    }
+
+    /// Domain and outcome of every `CertificatePinned` event logged so far
+    fn pin_checks(events: &Receiver<SecurityEvent>) -> Vec<(String, bool)> {
+        events.try_iter()
+            .filter_map(|event| match event {
+                SecurityEvent::CertificatePinned { domain, valid, .. } => Some((domain, valid)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_pinned_certificate_accepted() {
+        let server = Server::start().await;
+        let (client, events) = client_with(pinning(&[HOST], vec![server.pin()]), server.roots(), EndpointPolicy::Pinned);
+
+        let response = client.get(server.url()).send().await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+        assert_eq!(pin_checks(&events), vec![(HOST.to_string(), true)]);
+    }
+
+    #[tokio::test]
+    async fn test_pin_mismatch_rejected() {
+        let server = Server::start().await;
+        let other_pin = CertificatePinning::spki_pin(&Server::start().await.certificate).unwrap();
+        let (client, events) = client_with(pinning(&[HOST], vec![other_pin]), server.roots(), EndpointPolicy::Pinned);
+
+        assert!(client.get(server.url()).send().await.is_err());
+        assert_eq!(pin_checks(&events), vec![(HOST.to_string(), false)]);
+    }
+
+    #[tokio::test]
+    async fn test_allowlist_applies_to_pinned_endpoints_only() {
+        let server = Server::start().await;
+        let pinning = pinning(&["api.mainnet-beta.solana.com"], vec![]);
+
+        let (client, events) = client_with(pinning.clone(), server.roots(), EndpointPolicy::Pinned);
+        assert!(client.get(server.url()).send().await.is_err());
+        assert_eq!(pin_checks(&events), vec![(HOST.to_string(), false)]);
+
+        // An unpinned host is fine for public content, and not reported
+        let (client, events) = client_with(pinning, server.roots(), EndpointPolicy::Public);
+        assert!(client.get(server.url()).send().await.is_ok());
+        assert!(pin_checks(&events).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_public_client_still_enforces_pins() {
+        let server = Server::start().await;
+        let (client, events) = client_with(pinning(&[], vec!["bm90LXRoZS1waW4=".to_string()]), server.roots(), EndpointPolicy::Public);
+
+        assert!(client.get(server.url()).send().await.is_err());
+        assert_eq!(pin_checks(&events), vec![(HOST.to_string(), false)]);
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_certificate_rejected_despite_matching_pin() {
+        let server = Server::start().await;
+        let (client, events) = client_with(pinning(&[HOST], vec![server.pin()]), RootCertStore::empty(), EndpointPolicy::Pinned);
+
+        assert!(client.get(server.url()).send().await.is_err());
+        assert_eq!(pin_checks(&events), vec![(HOST.to_string(), false)]);
+    }
+
+    #[test]
+    fn test_spki_pin_requires_a_certificate() {
+        assert!(CertificatePinning::spki_pin(b"not a certificate").is_err());
+    }
+}
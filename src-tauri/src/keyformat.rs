@@ -0,0 +1,127 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::{keypair_from_seed, write_keypair_file, Keypair, Signer};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Length of an ed25519 secret seed
+const SEED_LENGTH: usize = 32;
+
+/// Length of a Solana keypair: secret seed followed by the public key
+const KEYPAIR_LENGTH: usize = 64;
+
+/// Secret key encoding errors
+#[derive(Error, Debug)]
+pub enum KeyFormatError {
+    #[error("Invalid {0:?} secret key: {1}")]
+    Decode(SecretKeyFormat, String),
+    #[error("Secret key must be 32 or 64 bytes, got {0}")]
+    Length(usize),
+    #[error("Public key half of the keypair does not match its secret key")]
+    PublicKeyMismatch,
+    #[error("Keypair file already exists: {0}")]
+    FileExists(String),
+    #[error("Failed to write keypair file: {0}")]
+    Write(String),
+}
+
+impl From<KeyFormatError> for String {
+    fn from(err: KeyFormatError) -> String {
+        err.to_string()
+    }
+}
+
+/// Text encodings of a Solana secret key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKeyFormat {
+    /// Hex of the 64-byte keypair (32-byte seeds are accepted on import)
+    Hex,
+    /// Base58 of the 64-byte keypair, as exported by Phantom and Solflare
+    Base58,
+    /// `solana-keygen` JSON byte array, as in `~/.config/solana/id.json`
+    KeypairJson,
+}
+
+/// Guess the format of a pasted secret key
+///
+/// A JSON array is `KeypairJson`; 64 or 128 hex digits are `Hex`; anything else is
+/// treated as `Base58`. Base58 keys of those lengths cannot be all-hex in practice,
+/// since a 64-byte key encodes to 87-88 base58 characters.
+pub fn detect_format(input: &str) -> SecretKeyFormat {
+    let input = input.trim();
+    if input.starts_with('[') {
+        return SecretKeyFormat::KeypairJson;
+    }
+
+    let hex_input = input.strip_prefix("0x").unwrap_or(input);
+    let is_hex = matches!(hex_input.len(), 64 | 128) && hex_input.chars().all(|c| c.is_ascii_hexdigit());
+    if is_hex {
+        SecretKeyFormat::Hex
+    } else {
+        SecretKeyFormat::Base58
+    }
+}
+
+/// Decode a secret key in `format`, or in the detected format when `None`
+pub fn parse_secret_key(input: &str, format: Option<SecretKeyFormat>) -> Result<Keypair, KeyFormatError> {
+    let input = input.trim();
+    let format = format.unwrap_or_else(|| detect_format(input));
+
+    let bytes = Zeroizing::new(match format {
+        SecretKeyFormat::Hex => hex::decode(input.strip_prefix("0x").unwrap_or(input))
+            .map_err(|e| KeyFormatError::Decode(format, e.to_string()))?,
+        SecretKeyFormat::Base58 => bs58::decode(input)
+            .into_vec()
+            .map_err(|e| KeyFormatError::Decode(format, e.to_string()))?,
+        SecretKeyFormat::KeypairJson => serde_json::from_str::<Vec<u8>>(input)
+            .map_err(|e| KeyFormatError::Decode(format, e.to_string()))?,
+    });
+
+    keypair_from_secret(&bytes)
+}
+
+/// Build a keypair from a 32-byte seed or a 64-byte keypair
+///
+/// For 64-byte input the public half is checked against the one derived from the
+/// seed, so a corrupted or spliced key is rejected instead of signing as someone else.
+pub fn keypair_from_secret(bytes: &[u8]) -> Result<Keypair, KeyFormatError> {
+    match bytes.len() {
+        SEED_LENGTH | KEYPAIR_LENGTH => {}
+        len => return Err(KeyFormatError::Length(len)),
+    }
+
+    let keypair = keypair_from_seed(&bytes[..SEED_LENGTH])
+        .map_err(|_| KeyFormatError::Length(bytes.len()))?;
+
+    if bytes.len() == KEYPAIR_LENGTH && keypair.pubkey().to_bytes() != bytes[SEED_LENGTH..] {
+        return Err(KeyFormatError::PublicKeyMismatch);
+    }
+    Ok(keypair)
+}
+
+/// Encode a keypair's full 64 bytes in `format`
+pub fn encode_secret_key(keypair: &Keypair, format: SecretKeyFormat) -> Zeroizing<String> {
+    let mut bytes = keypair.to_bytes();
+    let encoded = match format {
+        SecretKeyFormat::Hex => hex::encode(bytes),
+        SecretKeyFormat::Base58 => bs58::encode(bytes).into_string(),
+        SecretKeyFormat::KeypairJson => serde_json::to_string(&bytes[..])
+            .expect("byte arrays always serialize"),
+    };
+    bytes.zeroize();
+    Zeroizing::new(encoded)
+}
+
+/// Write a `solana-keygen` compatible keypair file, readable only by the owner on unix
+pub fn write_keypair_to_file(keypair: &Keypair, path: &Path, overwrite: bool) -> Result<(), KeyFormatError> {
+    if path.exists() && !overwrite {
+        return Err(KeyFormatError::FileExists(path.display().to_string()));
+    }
+
+    write_keypair_file(keypair, path)
+        .map_err(|e| KeyFormatError::Write(e.to_string()))?;
+    Ok(())
+}
+
+include!("keyformat_tests.rs");
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use solana_sdk::signature::read_keypair_file;
+    use tempfile::tempdir;
+
+    /// Keypair for the seed 00 01 02 .. 1f
+    const KEYPAIR_HEX: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8";
+    const KEYPAIR_BASE58: &str = "1GMkH3brNXiNNs1tiFZHu4yZSRrzJwxi5wB9bHFtMikjwpAW9DMZzU2Pqakc5it8X3N5vPmqdN7KF4CCUpmKhq";
+    const PUBLIC_KEY: &str = "FAe4sisG95oZ42w7buUn5qEE4TAnfTTFPiguZUHmhiF";
+
+    fn keypair_json() -> String {
+        serde_json::to_string(&hex::decode(KEYPAIR_HEX).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_detects_formats() {
+        assert_eq!(detect_format(KEYPAIR_HEX), SecretKeyFormat::Hex);
+        assert_eq!(detect_format(&KEYPAIR_HEX[..64]), SecretKeyFormat::Hex);
+        assert_eq!(detect_format(KEYPAIR_BASE58), SecretKeyFormat::Base58);
+        assert_eq!(detect_format(&format!("  {}\n", keypair_json())), SecretKeyFormat::KeypairJson);
+    }
+
+    #[test]
+    fn test_all_formats_decode_to_same_keypair() {
+        for input in [KEYPAIR_HEX.to_string(), KEYPAIR_BASE58.to_string(), keypair_json(), KEYPAIR_HEX[..64].to_string()] {
+            let keypair = parse_secret_key(&input, None).unwrap();
+            assert_eq!(keypair.pubkey().to_string(), PUBLIC_KEY);
+        }
+    }
+
+    #[test]
+    fn test_encodes_all_formats() {
+        let keypair = parse_secret_key(KEYPAIR_HEX, Some(SecretKeyFormat::Hex)).unwrap();
+
+        assert_eq!(*encode_secret_key(&keypair, SecretKeyFormat::Hex), KEYPAIR_HEX);
+        assert_eq!(*encode_secret_key(&keypair, SecretKeyFormat::Base58), KEYPAIR_BASE58);
+        assert_eq!(*encode_secret_key(&keypair, SecretKeyFormat::KeypairJson), keypair_json());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_public_key() {
+        let mut bytes = hex::decode(KEYPAIR_HEX).unwrap();
+        bytes[63] ^= 1;
+        assert!(matches!(keypair_from_secret(&bytes), Err(KeyFormatError::PublicKeyMismatch)));
+        assert!(matches!(keypair_from_secret(&bytes[..40]), Err(KeyFormatError::Length(40))));
+    }
+
+    #[test]
+    fn test_keypair_file_readable_by_solana_tooling() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        let keypair = parse_secret_key(KEYPAIR_BASE58, None).unwrap();
+
+        write_keypair_to_file(&keypair, &path, false).unwrap();
+        assert_eq!(read_keypair_file(&path).unwrap().pubkey().to_string(), PUBLIC_KEY);
+
+        assert!(matches!(write_keypair_to_file(&keypair, &path, false), Err(KeyFormatError::FileExists(_))));
+        write_keypair_to_file(&keypair, &path, true).unwrap();
+    }
+}
@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::security::{
+    get_security_manager, EncryptionAlgorithm, EncryptionParams, KeyDerivationMethod, SecurityError,
+    MAX_CALIBRATED_MEMORY_COST, MAX_CALIBRATED_PARALLELISM, MAX_CALIBRATED_TIME_COST,
+};
+
+/// Magic bytes identifying a Lesinki keystore container
+pub const KEYSTORE_MAGIC: &[u8; 4] = b"LSKW";
+
+/// Current keystore container version
+pub const KEYSTORE_VERSION: u16 = 1;
+
+/// Salt length used by pre-container vaults (`salt || nonce || ciphertext`)
+const LEGACY_SALT_LENGTH: usize = 16;
+
+/// Argon2 version every key is derived with (0x13)
+pub const ARGON2_VERSION: u32 = 0x13;
+
+/// Upper bound on the header size, so a corrupt length field cannot trigger a huge allocation
+const MAX_HEADER_LENGTH: usize = 64 * 1024;
+
+/// Headroom over the calibration ceilings that recorded key derivation costs may use
+const KDF_COST_HEADROOM: u32 = 4;
+
+/// Upper bound on recorded PBKDF2 iterations, 100 times the default
+const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+/// Upper bound on a recorded derived key length in bytes; every cipher takes 32
+const MAX_KEY_LENGTH: u32 = 64;
+
+/// Keystore errors
+#[derive(Error, Debug)]
+pub enum KeystoreError {
+    #[error("Keystore is truncated or malformed: {0}")]
+    Malformed(String),
+    #[error("Unsupported keystore version: {0}")]
+    UnsupportedVersion(u16),
+    #[error("Keystore header error: {0}")]
+    Header(#[from] serde_json::Error),
+    #[error("Keystore decryption failed (wrong password or corrupted data)")]
+    Authentication,
+    #[error(transparent)]
+    Security(#[from] SecurityError),
+}
+
+impl From<KeystoreError> for String {
+    fn from(err: KeystoreError) -> String {
+        err.to_string()
+    }
+}
+
+/// Key derivation cost parameters recorded alongside the ciphertext
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    pub iterations: u32,
+    pub key_length: u32,
+}
+
+impl KdfParams {
+    /// Parameters every vault written before the container format was derived with
+    pub fn legacy() -> Self {
+        Self {
+            memory_cost: 19456,
+            time_cost: 2,
+            parallelism: 1,
+            iterations: 100_000,
+            key_length: 32,
+        }
+    }
+
+    /// Reject costs far beyond anything this wallet writes, so a corrupt or hostile header
+    /// fails to open rather than exhausting memory or time in key derivation
+    pub fn check_bounds(&self) -> Result<(), KeystoreError> {
+        let within_bounds = self.memory_cost <= MAX_CALIBRATED_MEMORY_COST * KDF_COST_HEADROOM
+            && self.time_cost <= MAX_CALIBRATED_TIME_COST * KDF_COST_HEADROOM
+            && (1..=MAX_CALIBRATED_PARALLELISM * KDF_COST_HEADROOM).contains(&self.parallelism)
+            && self.iterations <= MAX_KDF_ITERATIONS
+            && (1..=MAX_KEY_LENGTH).contains(&self.key_length);
+        if within_bounds {
+            Ok(())
+        } else {
+            Err(KeystoreError::Malformed(format!("key derivation costs out of range: {:?}", self)))
+        }
+    }
+
+    /// `base` with these costs in place of its own
+    pub fn apply(&self, base: &EncryptionParams) -> EncryptionParams {
+        EncryptionParams {
+            memory_cost: self.memory_cost,
+            time_cost: self.time_cost,
+            parallelism: self.parallelism,
+            iterations: self.iterations,
+            key_length: self.key_length,
+            ..base.clone()
+        }
+    }
+}
+
+impl From<&EncryptionParams> for KdfParams {
+    fn from(params: &EncryptionParams) -> Self {
+        Self {
+            memory_cost: params.memory_cost,
+            time_cost: params.time_cost,
+            parallelism: params.parallelism,
+            iterations: params.iterations,
+            key_length: params.key_length,
+        }
+    }
+}
+
+/// Key derivation of one secret sealed inside the vault, recorded next to its ciphertext
+///
+/// Like the container header's, so secrets sealed under earlier defaults or
+/// calibrated costs keep opening after those change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretKdf {
+    pub kdf: KeyDerivationMethod,
+    /// Argon2 version; only `ARGON2_VERSION` is supported
+    pub version: u32,
+    pub kdf_params: KdfParams,
+}
+
+impl SecretKdf {
+    /// What secrets sealed before their parameters were recorded were derived with
+    pub fn legacy() -> Self {
+        Self {
+            kdf: KeyDerivationMethod::Argon2id,
+            version: ARGON2_VERSION,
+            kdf_params: KdfParams::legacy(),
+        }
+    }
+
+    /// Argon2id with the security manager's default costs
+    ///
+    /// Vault calibration does not change these; it only covers the container.
+    pub fn current() -> Self {
+        Self {
+            kdf: KeyDerivationMethod::Argon2id,
+            version: ARGON2_VERSION,
+            kdf_params: KdfParams::from(get_security_manager().encryption_params()),
+        }
+    }
+
+    /// Derive the key of a secret sealed with `salt`
+    pub fn derive_key(&self, password: &str, salt: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+        if self.version != ARGON2_VERSION {
+            return Err(KeystoreError::Malformed(format!("unsupported Argon2 version {:#x}", self.version)));
+        }
+        self.kdf_params.check_bounds()?;
+
+        let security_manager = get_security_manager();
+        let params = self.kdf_params.apply(&EncryptionParams {
+            key_derivation: self.kdf.clone(),
+            ..security_manager.encryption_params().clone()
+        });
+        Ok(security_manager.derive_key_with_params(password, salt, &params)?)
+    }
+}
+
+/// Self-describing keystore header, stored in clear and authenticated as AEAD associated data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreHeader {
+    pub version: u16,
+    pub kdf: KeyDerivationMethod,
+    pub kdf_params: KdfParams,
+    pub salt: Vec<u8>,
+    pub cipher: EncryptionAlgorithm,
+    pub nonce: Vec<u8>,
+}
+
+impl KeystoreHeader {
+    /// Build a fresh header from the given parameters with a new salt and nonce
+    pub fn new(params: &EncryptionParams) -> Self {
+        let security_manager = get_security_manager();
+        Self {
+            version: KEYSTORE_VERSION,
+            kdf: params.key_derivation.clone(),
+            kdf_params: KdfParams::from(params),
+            salt: security_manager.generate_random_bytes(params.salt_length as usize),
+            cipher: params.algorithm.clone(),
+            nonce: security_manager.generate_random_bytes(params.nonce_length as usize),
+        }
+    }
+
+    /// Header describing a legacy `salt || nonce || ciphertext` vault
+    fn legacy(salt: &[u8], nonce: &[u8]) -> Self {
+        Self {
+            version: 0,
+            kdf: KeyDerivationMethod::Argon2id,
+            kdf_params: KdfParams::legacy(),
+            salt: salt.to_vec(),
+            cipher: EncryptionAlgorithm::Aes256Gcm,
+            nonce: nonce.to_vec(),
+        }
+    }
+
+    /// Full encryption parameters needed to re-derive this header's key
+    pub fn encryption_params(&self) -> EncryptionParams {
+        EncryptionParams {
+            algorithm: self.cipher.clone(),
+            key_derivation: self.kdf.clone(),
+            iterations: self.kdf_params.iterations,
+            memory_cost: self.kdf_params.memory_cost,
+            time_cost: self.kdf_params.time_cost,
+            parallelism: self.kdf_params.parallelism,
+            key_length: self.kdf_params.key_length,
+            salt_length: self.salt.len() as u32,
+            nonce_length: self.nonce.len() as u32,
+        }
+    }
+
+    /// Derive the encryption key for this header
+    pub fn derive_key(&self, password: &str) -> Result<Vec<u8>, KeystoreError> {
+        let security_manager = get_security_manager();
+        Ok(security_manager.derive_key_with_params(password, &self.salt, &self.encryption_params())?)
+    }
+}
+
+/// On-disk format of a keystore file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystoreFormat {
+    /// Bare `salt || nonce || ciphertext`, written before the container format existed
+    Legacy,
+    /// `magic || header length || header || ciphertext`
+    Container(u16),
+}
+
+/// Result of opening a keystore
+pub struct OpenedKeystore {
+    pub plaintext: Vec<u8>,
+    pub header: KeystoreHeader,
+    pub format: KeystoreFormat,
+}
+
+impl OpenedKeystore {
+    /// Whether the file should be rewritten in the current container format
+    pub fn needs_migration(&self) -> bool {
+        self.format != KeystoreFormat::Container(KEYSTORE_VERSION)
+    }
+}
+
+/// Detect the on-disk format of a keystore file
+pub fn detect_format(data: &[u8]) -> KeystoreFormat {
+    if data.len() >= 6 && &data[..4] == KEYSTORE_MAGIC {
+        KeystoreFormat::Container(u16::from_le_bytes([data[4], data[5]]))
+    } else {
+        KeystoreFormat::Legacy
+    }
+}
+
+/// Encrypt `plaintext` into a keystore container using the current default parameters
+pub fn seal(password: &str, plaintext: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    let header = KeystoreHeader::new(get_security_manager().encryption_params());
+    seal_with_header(password, &header, plaintext)
+}
+
+/// Encrypt `plaintext` into a keystore container with the given costs, e.g. calibrated ones
+pub fn seal_with_kdf(password: &str, kdf_params: &KdfParams, plaintext: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    let params = kdf_params.apply(get_security_manager().encryption_params());
+    seal_with_header(password, &KeystoreHeader::new(&params), plaintext)
+}
+
+/// Encrypt `plaintext` into a keystore container using the given header
+pub fn seal_with_header(password: &str, header: &KeystoreHeader, plaintext: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    let key = header.derive_key(password)?;
+    seal_with_key(&key, header, plaintext)
+}
+
+/// Encrypt `plaintext` into a keystore container with an already derived key
+pub fn seal_with_key(key: &[u8], header: &KeystoreHeader, plaintext: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    let preamble = encode_preamble(header)?;
+    let ciphertext = get_security_manager().seal(&header.cipher, key, &header.nonce, plaintext, &preamble)?;
+
+    let mut output = preamble;
+    output.extend(ciphertext);
+    Ok(output)
+}
+
+/// Decrypt a keystore file in either the legacy or the container format
+pub fn open(password: &str, data: &[u8]) -> Result<OpenedKeystore, KeystoreError> {
+    match detect_format(data) {
+        KeystoreFormat::Legacy => open_legacy(password, data),
+        KeystoreFormat::Container(version) => {
+            let (header, preamble_length) = decode_header(data)?;
+            let key = header.derive_key(password)?;
+            let plaintext = open_container(&key, &header, data, preamble_length)?;
+            Ok(OpenedKeystore {
+                plaintext,
+                header,
+                format: KeystoreFormat::Container(version),
+            })
+        }
+    }
+}
+
+/// Read the header of a container keystore without decrypting it
+pub fn read_header(data: &[u8]) -> Result<KeystoreHeader, KeystoreError> {
+    decode_header(data).map(|(header, _)| header)
+}
+
+fn open_legacy(password: &str, data: &[u8]) -> Result<OpenedKeystore, KeystoreError> {
+    if data.len() < LEGACY_SALT_LENGTH + 12 {
+        return Err(KeystoreError::Malformed("legacy vault too short".to_string()));
+    }
+
+    let (salt, rest) = data.split_at(LEGACY_SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(12);
+    let header = KeystoreHeader::legacy(salt, nonce);
+    let key = header.derive_key(password)?;
+
+    let plaintext = get_security_manager()
+        .open(&header.cipher, &key, nonce, ciphertext, &[])
+        .map_err(|_| KeystoreError::Authentication)?;
+
+    Ok(OpenedKeystore {
+        plaintext,
+        header,
+        format: KeystoreFormat::Legacy,
+    })
+}
+
+fn open_container(key: &[u8], header: &KeystoreHeader, data: &[u8], preamble_length: usize) -> Result<Vec<u8>, KeystoreError> {
+    let (preamble, ciphertext) = data.split_at(preamble_length);
+    get_security_manager()
+        .open(&header.cipher, key, &header.nonce, ciphertext, preamble)
+        .map_err(|_| KeystoreError::Authentication)
+}
+
+fn encode_preamble(header: &KeystoreHeader) -> Result<Vec<u8>, KeystoreError> {
+    let header_json = serde_json::to_vec(header)?;
+
+    let mut preamble = KEYSTORE_MAGIC.to_vec();
+    preamble.extend(header.version.to_le_bytes());
+    preamble.extend((header_json.len() as u32).to_le_bytes());
+    preamble.extend(header_json);
+    Ok(preamble)
+}
+
+fn decode_header(data: &[u8]) -> Result<(KeystoreHeader, usize), KeystoreError> {
+    if data.len() < 10 || &data[..4] != KEYSTORE_MAGIC {
+        return Err(KeystoreError::Malformed("missing keystore magic".to_string()));
+    }
+
+    let version = u16::from_le_bytes([data[4], data[5]]);
+    if version == 0 || version > KEYSTORE_VERSION {
+        return Err(KeystoreError::UnsupportedVersion(version));
+    }
+
+    let header_length = u32::from_le_bytes([data[6], data[7], data[8], data[9]]) as usize;
+    if header_length > MAX_HEADER_LENGTH || data.len() < 10 + header_length {
+        return Err(KeystoreError::Malformed("header length out of range".to_string()));
+    }
+
+    let header: KeystoreHeader = serde_json::from_slice(&data[10..10 + header_length])?;
+    if header.version != version {
+        return Err(KeystoreError::Malformed("header version mismatch".to_string()));
+    }
+    header.kdf_params.check_bounds()?;
+
+    Ok((header, 10 + header_length))
+}
+
+include!("keystore_tests.rs");
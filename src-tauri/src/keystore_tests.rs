@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let sealed = seal("test_password", b"{\"wallets\":[]}").expect("Sealing should succeed");
+        assert_eq!(detect_format(&sealed), KeystoreFormat::Container(KEYSTORE_VERSION));
+
+        let opened = open("test_password", &sealed).expect("Opening should succeed");
+        assert_eq!(opened.plaintext, b"{\"wallets\":[]}".to_vec());
+        assert!(!opened.needs_migration());
+    }
+
+    #[test]
+    fn test_header_is_self_describing() {
+        let sealed = seal("test_password", b"data").unwrap();
+        let header = read_header(&sealed).expect("Header should decode");
+
+        let params = get_security_manager().encryption_params();
+        assert_eq!(header.version, KEYSTORE_VERSION);
+        assert_eq!(header.kdf, params.key_derivation);
+        assert_eq!(header.cipher, params.algorithm);
+        assert_eq!(header.kdf_params, KdfParams::from(params));
+        assert_eq!(header.nonce.len(), params.nonce_length as usize);
+    }
+
+    #[test]
+    fn test_calibrated_costs_are_recorded_and_reopen() {
+        let kdf_params = KdfParams { memory_cost: 256, time_cost: 3, parallelism: 2, ..KdfParams::legacy() };
+        let sealed = seal_with_kdf("test_password", &kdf_params, b"data").unwrap();
+        assert_eq!(read_header(&sealed).unwrap().kdf_params, kdf_params);
+
+        // Opening needs nothing but the file, whatever the current defaults are
+        assert_eq!(open("test_password", &sealed).unwrap().plaintext, b"data".to_vec());
+    }
+
+    #[test]
+    fn test_secret_kdf_derives_with_its_own_costs() {
+        let salt = b"secret-kdf-salt!";
+
+        // Secrets sealed before their costs were recorded used the then-default Argon2id
+        let legacy = SecretKdf::legacy().derive_key("test_password", salt).unwrap();
+        let default = get_security_manager().derive_key("test_password", salt, KeyDerivationMethod::Argon2id).unwrap();
+        assert_eq!(legacy, default);
+
+        let cheap = SecretKdf {
+            kdf_params: KdfParams { memory_cost: 256, time_cost: 1, ..KdfParams::legacy() },
+            ..SecretKdf::legacy()
+        };
+        assert_ne!(cheap.derive_key("test_password", salt).unwrap(), legacy);
+
+        let unknown = SecretKdf { version: 0x10, ..SecretKdf::legacy() };
+        assert!(unknown.derive_key("test_password", salt).is_err());
+    }
+
+    #[test]
+    fn test_wrong_password_fails_authentication() {
+        let sealed = seal("test_password", b"data").unwrap();
+        assert!(matches!(open("wrong_password", &sealed), Err(KeystoreError::Authentication)));
+    }
+
+    #[test]
+    fn test_tampered_header_is_rejected() {
+        let mut sealed = seal("test_password", b"data").unwrap();
+        let mut header = read_header(&sealed).unwrap();
+        header.kdf_params.iterations += 1;
+
+        // Re-encode the header with the same length so only the authenticated bytes differ
+        let original_length = u32::from_le_bytes([sealed[6], sealed[7], sealed[8], sealed[9]]) as usize;
+        let tampered = serde_json::to_vec(&header).unwrap();
+        if tampered.len() == original_length {
+            sealed[10..10 + original_length].copy_from_slice(&tampered);
+            assert!(open("test_password", &sealed).is_err());
+        }
+    }
+
+    #[test]
+    fn test_excessive_kdf_costs_are_malformed() {
+        let sealed = seal("test_password", b"data").unwrap();
+        let header_length = u32::from_le_bytes([sealed[6], sealed[7], sealed[8], sealed[9]]) as usize;
+        let mut header = read_header(&sealed).unwrap();
+        header.kdf_params.memory_cost = u32::MAX;
+
+        let encoded = serde_json::to_vec(&header).unwrap();
+        let mut hostile = sealed[..6].to_vec();
+        hostile.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        hostile.extend_from_slice(&encoded);
+        hostile.extend_from_slice(&sealed[10 + header_length..]);
+
+        // Rejected before any key is derived
+        assert!(matches!(read_header(&hostile), Err(KeystoreError::Malformed(_))));
+        assert!(matches!(open("test_password", &hostile), Err(KeystoreError::Malformed(_))));
+
+        let secret = SecretKdf { kdf_params: KdfParams { time_cost: u32::MAX, ..KdfParams::legacy() }, ..SecretKdf::legacy() };
+        assert!(matches!(secret.derive_key("test_password", b"secret-kdf-salt!"), Err(KeystoreError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_legacy_vault_opens_and_needs_migration() {
+        let security_manager = get_security_manager();
+        let salt = [7u8; LEGACY_SALT_LENGTH];
+        let nonce = [9u8; 12];
+        let header = KeystoreHeader::legacy(&salt, &nonce);
+        let key = header.derive_key("test_password").unwrap();
+        let encrypted = security_manager.seal(&header.cipher, &key, &nonce, b"legacy", &[]).unwrap();
+
+        // Legacy vaults are salt || nonce || ciphertext
+        let mut legacy = salt.to_vec();
+        legacy.extend(nonce);
+        legacy.extend(encrypted);
+        assert_eq!(detect_format(&legacy), KeystoreFormat::Legacy);
+
+        let opened = open("test_password", &legacy).expect("Legacy vault should open");
+        assert_eq!(opened.plaintext, b"legacy".to_vec());
+        assert!(opened.needs_migration());
+    }
+
+    #[test]
+    fn test_truncated_container_is_malformed() {
+        let sealed = seal("test_password", b"data").unwrap();
+        assert!(open("test_password", &sealed[..8]).is_err());
+        assert!(matches!(decode_header(&sealed[..12]), Err(KeystoreError::Malformed(_))));
+    }
+}
@@ -1,15 +1,15 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
-use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer, SeedDerivable}, system_instruction, stake, stake::instruction as stake_instruction, stake::state::{StakeStateV2, Authorized, Lockup}, program_pack::Pack};
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer}, system_instruction, stake, stake::instruction as stake_instruction, stake::state::{StakeStateV2, Authorized, Lockup}, program_pack::Pack};
 use solana_client::rpc_request::TokenAccountsFilter;
-use solana_client::rpc_client::RpcClient;
-use bip39::{Mnemonic, Language};
-use tauri::command;
+use zeroize::Zeroizing;
+use tauri::{command, AppHandle, Emitter, Manager};
 use tokio::sync::Mutex;
 use aes_gcm::{Aes256Gcm, Nonce};
 use aes_gcm::aead::{Aead, KeyInit};
@@ -18,7 +18,6 @@ use rand::rngs::OsRng;
 use rand::RngCore;
 use thiserror::Error;
 use chrono::{DateTime, Utc};
-use reqwest::Client;
 extern crate bincode;
 use mpl_token_metadata::accounts::Metadata;
 use borsh::BorshDeserialize;
@@ -26,14 +25,47 @@ use solana_program::pubkey::Pubkey as SolanaPubkey;
 
 // Import our enhanced modules
 mod security;
+mod keystore;
+mod vault;
+mod profiles;
+mod session;
+mod policy;
+mod address_lists;
+mod audit;
+mod http_client;
+mod metadata;
+mod keyformat;
+mod backup;
+mod derivation;
+mod discovery;
+mod mnemonic;
+mod seeds;
+mod slip39;
+mod vanity;
 mod performance;
 mod monitoring;
 mod pumpfun_bundler;
 
-use security::{SecurityManager, get_security_manager, SecurityEvent, SecurityError};
+use security::{SecurityManager, get_security_manager, security_events, SecurityError, SecurityEvent, KdfCalibration};
 use performance::{PerformanceCache, PerformanceConfig, PerformanceMetrics};
-use monitoring::{AnalyticsManager, UserEvent, SystemMetrics};
-use pumpfun_bundler::{PumpfunInterface, PumpfunTokenMetadata, PumpfunTokenResponse, BundleBuyResponse, SwapDapp, JitoConfig, MevProtection, PumpfunBundlerError};
+use monitoring::{MonitoringManager, AnalyticsConfig, UserEvent, SystemMetrics};
+use vault::{VaultConfig, VaultStorage};
+use profiles::{ProfileManager, ProfileSummary, ProfileError};
+use backup::{plan_import, BackupBundle, BackupImportReport, BackupSeed, BackupSettings, BackupWallet};
+use derivation::PathTemplate;
+use mnemonic::{MnemonicLanguage, PhraseDiagnosis};
+use seeds::{SeedEntry, SeedSecrets, SeedSummary};
+use slip39::{GroupSpec, ShareGroup, ShareInfo};
+use vanity::{VanityError, VanityFound, VanityPattern, VANITY_CANCELLED_EVENT, VANITY_FOUND_EVENT, VANITY_PROGRESS_EVENT};
+use discovery::{discover_accounts, probe_account, DiscoveredAccount, DiscoveryConfig};
+use keyformat::{detect_format, encode_secret_key, keypair_from_secret, parse_secret_key, write_keypair_to_file, SecretKeyFormat};
+use metadata::{normalize_tag, WalletMetadata};
+use keystore::SecretKdf;
+use audit::{AuditError, AuditLog, AuditQuery, AuditRecord, AuditSecret, AuditVerification, AUDIT_FLUSH_INTERVAL};
+use address_lists::{AddressEntry, AddressListError, AddressLists, ImportFormat, ImportReport, ListKind, ListScope, LISTS_FILE};
+use policy::{ApprovalQueue, PendingApproval, PolicyError, PolicyStore, Spend, SpendVolume, TransactionPolicy, NATIVE_ASSET};
+use session::{wallet_key_id, AutoLockPolicy, LockReason, VaultLockEvent, VaultSession, AUTO_LOCK_CHECK_INTERVAL, VAULT_LOCKED_EVENT, VAULT_UNLOCKED_EVENT};
+use pumpfun_bundler::{PumpfunInterface, PumpfunTokenMetadata, PumpfunTokenResponse, BundleBuyResponse, SwapDapp, JitoConfig, MevProtection, PumpfunBundlerError, LaunchSnipeConfig, LaunchSnipeResponse};
 
 #[derive(Error, Debug)]
 pub enum WalletError {
@@ -61,23 +93,191 @@ pub enum WalletError {
     Performance(String),
     #[error("Pumpfun/Bundler error: {0}")]
     PumpfunBundler(String),
+    #[error("Vault is locked")]
+    VaultLocked,
+    #[error("Wallet not found: {0}")]
+    WalletNotFound(String),
+    #[error("Wallet {0} is watch-only and cannot sign")]
+    WatchOnly(String),
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    AddressList(#[from] AddressListError),
+    #[error(transparent)]
+    Audit(#[from] AuditError),
+}
+
+/// Layout of `Wallet::encrypted_private_key`; from 1 the ciphertext is bound to its wallet
+pub const WALLET_SECRET_VERSION: u32 = 1;
+
+/// Whether the vault holds a wallet's private key
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletKind {
+    #[default]
+    Keyed,
+    /// Tracked address only; no private key is stored
+    WatchOnly,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Wallet {
+    #[serde(default)]
+    pub id: String,
     pub public_key: String,
+    #[serde(default)]
+    pub kind: WalletKind,
+    /// Empty for watch-only wallets
+    #[serde(default)]
     pub encrypted_private_key: Vec<u8>,
+    /// Empty for watch-only wallets
+    #[serde(default)]
     pub salt: Vec<u8>,
+    /// Key derivation the key was sealed with; `None` for keys sealed before it was recorded
+    #[serde(default)]
+    pub kdf: Option<SecretKdf>,
     pub balance: u64,
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
     pub network: String,
+    #[serde(default)]
+    pub metadata: WalletMetadata,
+    /// SLIP-0010 path the key was derived at, for wallets imported from a seed phrase
+    #[serde(default)]
+    pub derivation_path: Option<String>,
+    /// Vault seed entry the key was derived from
+    #[serde(default)]
+    pub seed_id: Option<String>,
+    /// `WALLET_SECRET_VERSION` the key was sealed with; 0 for keys sealed without associated data
+    #[serde(default)]
+    pub secret_version: u32,
+}
+
+impl Wallet {
+    /// A new wallet for `public_key` without a key; `seal_secret` makes it keyed
+    pub fn new(public_key: String, network: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: new_wallet_id(),
+            public_key,
+            kind: WalletKind::WatchOnly,
+            encrypted_private_key: vec![],
+            salt: vec![],
+            kdf: None,
+            balance: 0,
+            created_at: now,
+            last_updated: now,
+            network,
+            metadata: WalletMetadata::default(),
+            derivation_path: None,
+            seed_id: None,
+            secret_version: 0,
+        }
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.kind == WalletKind::WatchOnly
+    }
+
+    /// Encrypt `private_key` under `password` with a fresh salt, bound to this wallet's id and address
+    pub fn seal_secret(&mut self, private_key: &[u8], password: &str, kdf: &SecretKdf) -> Result<(), WalletError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let encryption_key = Zeroizing::new(derive_encryption_key(password, salt.as_str().as_bytes(), kdf)?);
+
+        self.secret_version = WALLET_SECRET_VERSION;
+        self.encrypted_private_key = encrypt_data_with_aad(&encryption_key, private_key, &self.secret_aad())?;
+        self.salt = salt.as_str().as_bytes().to_vec();
+        self.kdf = Some(kdf.clone());
+        self.kind = WalletKind::Keyed;
+        Ok(())
+    }
+
+    /// Decrypt the private key; fails if the ciphertext was sealed for another wallet
+    pub fn open_secret(&self, password: &str) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+        if self.secret_version > WALLET_SECRET_VERSION {
+            return Err(WalletError::Encryption(format!("Unsupported key version {} for wallet {}", self.secret_version, self.id)));
+        }
+
+        let legacy = SecretKdf::legacy();
+        let encryption_key = Zeroizing::new(derive_encryption_key(password, &self.salt, self.kdf.as_ref().unwrap_or(&legacy))?);
+        if self.secret_version > 0 {
+            let private_key = decrypt_data_with_aad(&encryption_key, &self.encrypted_private_key, &self.secret_aad())
+                .map_err(|_| WalletError::AuthFailed)?;
+            return Ok(Zeroizing::new(private_key));
+        }
+
+        // Unbound keys carry nothing tying them to the wallet but the address they derive
+        let private_key = Zeroizing::new(
+            decrypt_data(&encryption_key, &self.encrypted_private_key).map_err(|_| WalletError::AuthFailed)?
+        );
+        let keypair = keypair_from_secret(&private_key).map_err(|e| WalletError::Encryption(e.to_string()))?;
+        if keypair.pubkey().to_string() != self.public_key {
+            return Err(WalletError::Encryption(format!("Stored key does not belong to wallet {}", self.public_key)));
+        }
+        Ok(private_key)
+    }
+
+    /// Whether the key was sealed before keys were bound to their wallet
+    pub fn needs_secret_binding(&self) -> bool {
+        !self.is_watch_only() && self.secret_version < WALLET_SECRET_VERSION
+    }
+
+    fn secret_aad(&self) -> Vec<u8> {
+        format!("lesinki-wallet-secret:v{}:{}:{}", self.secret_version, self.id, self.public_key).into_bytes()
+    }
+
+    /// Fail with `WalletError::WatchOnly` unless the vault holds this wallet's key
+    pub fn ensure_can_sign(&self) -> Result<(), WalletError> {
+        if self.is_watch_only() {
+            return Err(WalletError::WatchOnly(self.public_key.clone()));
+        }
+        Ok(())
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+/// An address derivable from a seed phrase, before it is imported
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DerivedAddress {
+    pub account_index: u32,
+    pub derivation_path: Option<String>,
+    pub public_key: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Wallets {
     pub wallets: Vec<Wallet>,
     pub version: String,
+    #[serde(default = "default_key_version")]
+    pub key_version: u32,
+    /// When every secret was last re-wrapped by a key rotation or password change
+    #[serde(default)]
+    pub key_rotated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub seeds: Vec<SeedEntry>,
+    /// Source of the audit log's HMAC key; created on first unlock
+    #[serde(default)]
+    pub audit_secret: Option<AuditSecret>,
+    /// Set once a spending policy is saved, so a deleted policy file is an error rather than no limits
+    #[serde(default)]
+    pub policy_saved: bool,
+}
+
+fn default_key_version() -> u32 {
+    1
+}
+
+impl Wallets {
+    fn empty() -> Self {
+        Self {
+            wallets: vec![],
+            version: "1.0".to_string(),
+            key_version: default_key_version(),
+            key_rotated_at: None,
+            seeds: vec![],
+            audit_secret: None,
+            policy_saved: false,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -276,22 +476,404 @@ struct AppState {
     cache: HashMap<String, (u64, Instant)>,
     rate_limiter: HashMap<String, (u32, Instant)>,
     encryption_key: Vec<u8>,
+    profiles: ProfileManager,
+    session: Option<VaultSession>,
+    /// Cancel flag of the running vanity search, if any
+    vanity_cancel: Option<Arc<AtomicBool>>,
+    /// Transactions held back by the `require_approval` policy
+    approvals: ApprovalQueue,
+    /// Audit log of the unlocked vault's profile
+    audit_log: Option<AuditLog>,
+    /// Key authenticating the unlocked vault's policy files
+    policy_key: Option<Zeroizing<Vec<u8>>>,
     performance_cache: Arc<PerformanceCache>,
-    analytics_manager: Arc<AnalyticsManager>,
+    analytics_manager: Arc<MonitoringManager>,
     pumpfun_interface: Arc<PumpfunInterface>,
 }
 
 impl AppState {
-    fn new() -> Self {
-        Self {
+    fn new(data_dir: PathBuf) -> Result<Self, ProfileError> {
+        let profiles = ProfileManager::load(data_dir)?;
+        let analytics_manager = MonitoringManager::new(AnalyticsConfig::default(), profiles.data_dir());
+
+        Ok(Self {
             cache: HashMap::new(),
             rate_limiter: HashMap::new(),
             encryption_key: vec![],
+            profiles,
+            session: None,
+            vanity_cancel: None,
+            approvals: ApprovalQueue::default(),
+            audit_log: None,
+            policy_key: None,
             performance_cache: Arc::new(PerformanceCache::new()),
-            analytics_manager: Arc::new(AnalyticsManager::new()),
+            analytics_manager: Arc::new(analytics_manager),
             pumpfun_interface: Arc::new(PumpfunInterface::new()),
+        })
+    }
+
+    fn vault_storage(&self) -> VaultStorage {
+        self.profiles.active_vault_storage()
+    }
+
+    /// The unlocked session; an expired session counts as locked even before the auto-lock task wipes it
+    fn session(&self) -> Result<&VaultSession, WalletError> {
+        self.session.as_ref()
+            .filter(|session| session.is_unlocked() && session.expiry(Utc::now()).is_none())
+            .ok_or(WalletError::VaultLocked)
+    }
+
+    fn lock_session(&mut self, reason: LockReason) -> Option<VaultLockEvent> {
+        self.approvals = ApprovalQueue::default();
+        self.flush_audit_events();
+        self.audit_log = None;
+        self.policy_key = None;
+        self.session.take().map(|session| session.lock(reason))
+    }
+
+    /// Open the active profile's audit log and policy key, first giving the vault an audit secret if it has none
+    fn open_audit_log(&mut self) -> Result<(), WalletError> {
+        let session = self.session()?;
+        let password = Zeroizing::new(session.password()?);
+        let secret = match session.wallets.audit_secret.clone() {
+            Some(secret) => secret,
+            None => {
+                let secret = AuditSecret::generate(&password, &self.secret_kdf())?;
+                self.update_session_wallets(|wallets, _, _| {
+                    wallets.audit_secret = Some(secret.clone());
+                    Ok(())
+                })?;
+                secret
+            }
+        };
+
+        let dir = self.profiles.profile_dir(&self.profiles.active().name);
+        let secret = secret.open(&password)?;
+        self.policy_key = Some(policy::derive_key(&secret)?);
+        let audit_log = AuditLog::open(&dir, &secret)?;
+
+        // Events logged while no vault was unlocked belong to no profile's log
+        let discarded = security_events().try_iter().count();
+        if discarded > 0 {
+            log::warn!("Discarded {} security events logged while the vault was locked", discarded);
+        }
+        self.audit_log = Some(audit_log);
+        Ok(())
+    }
+
+    /// Write the security events logged so far to the open audit log
+    ///
+    /// While the vault is locked there is no log to write them to; they are discarded when
+    /// a vault is next unlocked, so they never end up in another profile's log.
+    fn flush_audit_events(&mut self) {
+        let Some(audit_log) = self.audit_log.as_mut() else { return };
+        let events: Vec<SecurityEvent> = security_events().try_iter().collect();
+        if let Err(e) = audit_log.append(&events) {
+            log::error!("Failed to write {} security events to the audit log: {}", events.len(), e);
+        }
+    }
+
+    /// Policy files of the active profile; readable only while its vault is unlocked
+    fn policy_store(&self) -> Result<PolicyStore, WalletError> {
+        let key = self.policy_key.as_ref().ok_or(WalletError::VaultLocked)?;
+        let policy_saved = self.session.as_ref().is_some_and(|session| session.wallets.policy_saved);
+        Ok(PolicyStore::new(self.profiles.profile_dir(&self.profiles.active().name), key).with_saved_policy(policy_saved))
+    }
+
+    /// Save the active profile's policy, first recording in the vault that it has one
+    fn save_policy(&mut self, policy: &TransactionPolicy) -> Result<(), WalletError> {
+        policy.validate()?;
+        if !self.session()?.wallets.policy_saved {
+            self.update_session_wallets(|wallets, _, _| {
+                wallets.policy_saved = true;
+                Ok(())
+            })?;
+        }
+        self.policy_store()?.save_policy(policy)?;
+        Ok(())
+    }
+
+    fn address_lists_path(&self, scope: ListScope) -> PathBuf {
+        match scope {
+            ListScope::Global => self.profiles.data_dir().join(LISTS_FILE),
+            ListScope::Profile => self.profiles.profile_dir(&self.profiles.active().name).join(LISTS_FILE),
+        }
+    }
+
+    /// The global address lists together with the active profile's
+    fn address_lists(&self) -> Result<AddressLists, AddressListError> {
+        let global = AddressLists::load(&self.address_lists_path(ListScope::Global))?;
+        Ok(global.merged(AddressLists::load(&self.address_lists_path(ListScope::Profile))?))
+    }
+
+    /// Apply `update` to the address lists of `scope` and save them without expired entries
+    ///
+    /// Changing what may be sent where needs an unlocked vault.
+    fn update_address_lists<F, R>(&self, scope: ListScope, update: F) -> Result<R, WalletError>
+    where
+        F: FnOnce(&mut AddressLists) -> Result<R, AddressListError>,
+    {
+        self.session()?;
+        let path = self.address_lists_path(scope);
+        let mut lists = AddressLists::load(&path)?;
+
+        let result = update(&mut lists)?;
+        lists.prune(Utc::now());
+        lists.save(path)?;
+        Ok(result)
+    }
+
+    /// Check a signing operation's spends against the active profile's policy
+    ///
+    /// `simulated` tells whether the transaction is simulated before it is submitted.
+    /// Callers keep the state locked until `record_spends`, so concurrent operations
+    /// cannot each fit under a limit that they exceed together. Both outcomes are
+    /// logged as security events.
+    fn authorize_spends(&mut self, operation: &str, spends: &[Spend], simulated: bool) -> Result<(), WalletError> {
+        let result = self.evaluate_spends(operation, spends, simulated);
+
+        let security_manager = get_security_manager();
+        match &result {
+            Ok(()) => {
+                for spend in spends {
+                    security_manager.log_event(SecurityEvent::TransactionAttempt {
+                        from: spend.wallet.clone(),
+                        to: spend.recipient.clone().unwrap_or_default(),
+                        amount: spend.amount,
+                        timestamp: Utc::now(),
+                    });
+                }
+            }
+            Err(WalletError::Policy(PolicyError::Blocked { policy, reason })) => {
+                security_manager.log_event(SecurityEvent::SecurityViolation {
+                    violation_type: policy.clone(),
+                    details: format!("{}: {}", operation, reason),
+                    timestamp: Utc::now(),
+                });
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn evaluate_spends(&mut self, operation: &str, spends: &[Spend], simulated: bool) -> Result<(), WalletError> {
+        let store = self.policy_store()?;
+        let policy = store.load_policy()?;
+        let ledger = store.load_ledger(&policy)?;
+        let lists = self.address_lists()?;
+        let now = Utc::now();
+
+        let security_manager = get_security_manager();
+        for spend in spends {
+            if let Some(recipient) = &spend.recipient {
+                security_manager.validate_transaction(&spend.wallet, recipient, &policy.security, &lists)
+                    .map_err(PolicyError::from)?;
+            }
+        }
+        policy.evaluate(&ledger, spends, simulated, now)?;
+
+        if policy.security.require_approval {
+            self.approvals.check(operation, spends, policy.security.transaction_timeout, now)?;
+        }
+        Ok(())
+    }
+
+    /// Add submitted spends to the active profile's rolling ledger
+    ///
+    /// The transaction has already gone out, so a failure is logged rather than returned.
+    fn record_spends(&self, operation: &str, spends: &[Spend]) {
+        let now = Utc::now();
+        let result = self.policy_store().and_then(|store| {
+            let mut ledger = store.load_ledger(&store.load_policy()?)?;
+            ledger.record(operation, spends, now);
+            Ok(store.save_ledger(&mut ledger, now)?)
+        });
+        if let Err(e) = result {
+            log::error!("Failed to record {} spends in the ledger: {}", operation, e);
+        }
+    }
+
+    /// Add a new wallet to the unlocked vault, sealing `private_key` into it unless it is watch-only
+    fn add_session_wallet(&mut self, mut wallet: Wallet, private_key: Option<&[u8]>) -> Result<Wallet, WalletError> {
+        self.update_session_wallets(|wallets, password, kdf| {
+            if wallets.wallets.iter().any(|existing| existing.public_key == wallet.public_key) {
+                return Err(WalletError::InvalidInput(format!("Wallet {} is already in the vault", wallet.public_key)));
+            }
+            if let Some(private_key) = private_key {
+                wallet.seal_secret(private_key, password, kdf)?;
+            }
+            wallets.wallets.push(wallet.clone());
+            Ok(wallet)
+        })
+    }
+
+    /// KDF that secrets sealed into the active vault are recorded with
+    fn secret_kdf(&self) -> SecretKdf {
+        SecretKdf::current()
+    }
+
+    /// Apply `update` to the unlocked vault's wallets and write the vault with the session password
+    ///
+    /// `update` also receives the password and key derivation, to encrypt keys of wallets
+    /// it adds. Nothing is written if it fails. Keys of added keyed wallets are loaded
+    /// into the session.
+    fn update_session_wallets<F, R>(&mut self, update: F) -> Result<R, WalletError>
+    where
+        F: FnOnce(&mut Wallets, &str, &SecretKdf) -> Result<R, WalletError>,
+    {
+        let session = self.session()?;
+        let password = Zeroizing::new(session.password()?);
+        let mut wallets = session.wallets.clone();
+
+        let result = update(&mut wallets, &password, &self.secret_kdf())?;
+        write_vault(&self.vault_storage(), &wallets, &password)?;
+
+        let security_manager = get_security_manager();
+        for wallet in wallets.wallets.iter().filter(|wallet| !wallet.is_watch_only()) {
+            if !security_manager.has_key(&wallet_key_id(&wallet.public_key)) {
+                VaultSession::load_wallet_key(wallet, &password, wallets.key_version)?;
+            }
+        }
+
+        if let Some(session) = self.session.as_mut() {
+            session.wallets = wallets;
+        }
+        Ok(result)
+    }
+
+    /// Key id the security manager tracks the active vault's rotation under
+    fn vault_key_id(&self) -> String {
+        format!("vault:{}", self.profiles.active().name)
+    }
+
+    /// When the active vault's secrets are next due to be re-wrapped, if rotation is enabled
+    ///
+    /// A vault never rotated before counts from its first wallet or seed, or else from
+    /// when it was unlocked.
+    fn next_vault_rotation(&self, wallets: &Wallets) -> Option<DateTime<Utc>> {
+        let days = self.profiles.active().vault_config.key_rotation_days?;
+        let since = wallets.key_rotated_at
+            .or_else(|| {
+                let wallets_created = wallets.wallets.iter().map(|wallet| wallet.created_at);
+                wallets_created.chain(wallets.seeds.iter().map(|seed| seed.created_at)).min()
+            })
+            .or_else(|| self.session.as_ref().map(|session| session.unlocked_at))
+            .unwrap_or_else(Utc::now);
+        Some(since + chrono::Duration::days(days.into()))
+    }
+
+    /// Put the active vault on the security manager's rotation schedule, or take it off
+    fn schedule_vault_rotation(&self, wallets: &Wallets) {
+        let security_manager = get_security_manager();
+        match self.next_vault_rotation(wallets) {
+            Some(date) => security_manager.schedule_key_rotation(&self.vault_key_id(), date),
+            None => security_manager.cancel_key_rotation(&self.vault_key_id()),
         }
     }
+
+    /// Whether the vault is unlocked and its scheduled rotation date has passed
+    fn vault_key_rotation_due(&self) -> bool {
+        self.session().is_ok() && get_security_manager().check_key_rotation(&self.vault_key_id())
+    }
+
+    /// Lock the session if its auto-lock policy says it has expired
+    fn expire_session(&mut self, now: DateTime<Utc>) -> Option<VaultLockEvent> {
+        let reason = self.session.as_ref()?.expiry(now)?;
+        self.lock_session(reason)
+    }
+}
+
+/// Tell the frontend a vault was locked
+fn emit_vault_locked(app: &AppHandle, event: Option<VaultLockEvent>) {
+    if let Some(event) = event {
+        if let Err(e) = app.emit(VAULT_LOCKED_EVENT, &event) {
+            log::warn!("Failed to emit {}: {}", VAULT_LOCKED_EVENT, e);
+        }
+    }
+}
+
+/// Periodically wipe the session's key material once its auto-lock policy expires
+async fn run_auto_lock(app: AppHandle) {
+    let mut interval = tokio::time::interval(AUTO_LOCK_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let state = app.state::<Arc<Mutex<AppState>>>();
+        let event = state.lock().await.expire_session(Utc::now());
+        emit_vault_locked(&app, event);
+    }
+}
+
+/// Periodically move logged security events into the unlocked vault's audit log
+async fn run_audit_log(app: AppHandle) {
+    let mut interval = tokio::time::interval(AUDIT_FLUSH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let state = app.state::<Arc<Mutex<AppState>>>();
+        state.lock().await.flush_audit_events();
+    }
+}
+
+/// How often the background task checks whether the vault key is due for rotation
+const KEY_ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically re-wrap the unlocked vault's secrets once its rotation date has passed
+async fn run_key_rotation(app: AppHandle) {
+    let mut interval = tokio::time::interval(KEY_ROTATION_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let state = app.state::<Arc<Mutex<AppState>>>();
+        if !state.lock().await.vault_key_rotation_due() {
+            continue;
+        }
+        if let Err(e) = rotate_session_vault_key(&state).await {
+            log::warn!("Scheduled vault key rotation failed: {}", e);
+        }
+    }
+}
+
+/// Re-wrap every secret of the unlocked vault under freshly derived keys as the next key version
+///
+/// The secrets are re-derived on a blocking thread; the state lock is only held to
+/// read the session and to swap the result in. The rotated vault is committed by a
+/// single atomic write, unless the vault was rewritten in the meantime; until then
+/// the vault file and session keep working with the current version. The password
+/// is unchanged, so backups of earlier versions still open.
+async fn rotate_session_vault_key(state: &Mutex<AppState>) -> Result<u32, WalletError> {
+    let (storage, wallets, password, kdf, snapshot) = {
+        let app_state = state.lock().await;
+        let session = app_state.session()?;
+        let storage = app_state.vault_storage();
+        let snapshot = vault_snapshot(&storage);
+        (storage, session.wallets.clone(), Zeroizing::new(session.password()?), app_state.secret_kdf(), snapshot)
+    };
+    let old_version = wallets.key_version;
+
+    let rotate_storage = storage.clone();
+    let (rotated, sealed) = tokio::task::spawn_blocking(move || -> Result<_, WalletError> {
+        let rotated = rewrap_vault(&wallets, &password, &password, &kdf)?;
+        let sealed = seal_vault(&rotate_storage, &password, serde_json::to_string(&rotated)?.as_bytes())?;
+        Ok((rotated, sealed))
+    })
+    .await
+    .map_err(|e| WalletError::InvalidInput(e.to_string()))??;
+
+    let mut app_state = state.lock().await;
+    let session = app_state.session()?;
+    if session.wallets.key_version != old_version
+        || app_state.vault_storage().path() != storage.path()
+        || vault_snapshot(&storage) != snapshot
+    {
+        return Err(WalletError::InvalidInput("The vault changed while its key was being rotated; try again".to_string()));
+    }
+    storage.write(&sealed)?;
+
+    let new_version = rotated.key_version;
+    let next_rotation = app_state.next_vault_rotation(&rotated);
+    app_state.session = Some(session.rewrapped(rotated)?);
+
+    get_security_manager().complete_key_rotation(&app_state.vault_key_id(), old_version, new_version, next_rotation);
+    log::info!("Rotated vault key of profile '{}' to version {}", app_state.profiles.active().name, new_version);
+    Ok(new_version)
 }
 
 #[derive(Serialize, Deserialize)]
@@ -311,12 +893,8 @@ impl Network {
     }
 }
 
-fn derive_encryption_key(password: &str, salt: &[u8]) -> Result<Vec<u8>, WalletError> {
-    let security_manager = get_security_manager();
-    match security_manager.derive_key(password, salt, security::KeyDerivationMethod::Argon2id) {
-        Ok(key) => Ok(key),
-        Err(e) => Err(WalletError::Encryption(e.to_string())),
-    }
+fn derive_encryption_key(password: &str, salt: &[u8], kdf: &SecretKdf) -> Result<Vec<u8>, WalletError> {
+    kdf.derive_key(password, salt).map_err(|e| WalletError::Encryption(e.to_string()))
 }
 
 fn generate_salt() -> [u8; 16] {
@@ -341,6 +919,18 @@ fn decrypt_data(key: &[u8], encrypted_data: &[u8]) -> Result<Vec<u8>, WalletErro
     }
 }
 
+fn encrypt_data_with_aad(key: &[u8], data: &[u8], aad: &[u8]) -> Result<Vec<u8>, WalletError> {
+    get_security_manager()
+        .encrypt_data_with_aad(key, data, aad)
+        .map_err(|e| WalletError::Encryption(e.to_string()))
+}
+
+fn decrypt_data_with_aad(key: &[u8], encrypted_data: &[u8], aad: &[u8]) -> Result<Vec<u8>, WalletError> {
+    get_security_manager()
+        .decrypt_data_with_aad(key, encrypted_data, aad)
+        .map_err(|e| WalletError::Encryption(e.to_string()))
+}
+
 impl From<WalletError> for String {
     fn from(err: WalletError) -> String {
         err.to_string()
@@ -352,6 +942,10 @@ fn validate_public_key(pubkey_str: &str) -> Result<Pubkey, WalletError> {
         .map_err(|_| WalletError::InvalidInput("Invalid public key format".to_string()))
 }
 
+fn new_wallet_id() -> String {
+    hex::encode(get_security_manager().generate_random_bytes(16))
+}
+
 fn validate_amount(amount: u64) -> Result<(), WalletError> {
     if amount == 0 {
         return Err(WalletError::InvalidInput("Amount must be greater than 0".to_string()));
@@ -359,29 +953,24 @@ fn validate_amount(amount: u64) -> Result<(), WalletError> {
     Ok(())
 }
 
+/// Lamports of a SOL amount given as a float; negative, non-finite and out-of-range amounts are rejected
+fn sol_to_lamports(sol: f64) -> Result<u64, WalletError> {
+    let lamports = (sol * 1_000_000_000.0).round();
+    if !lamports.is_finite() || lamports < 0.0 || lamports >= u64::MAX as f64 {
+        return Err(WalletError::InvalidInput(format!("Invalid SOL amount: {}", sol)));
+    }
+    Ok(lamports as u64)
+}
+
 #[command]
-async fn generate_wallet(password: String, network: String) -> Result<Wallet, String> {
+async fn generate_wallet(network: String, state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<Wallet, String> {
     let keypair = Keypair::new();
     let public_key = keypair.pubkey().to_string();
-    let private_key = keypair.secret().to_bytes().to_vec();
-
-    // Generate salt for encryption
-    let salt = SaltString::generate(&mut OsRng);
-    let encryption_key = derive_encryption_key(&password, salt.as_str().as_bytes())?;
+    let private_key = Zeroizing::new(keypair.secret().to_bytes().to_vec());
 
-    // Encrypt private key
-    let encrypted_private_key = encrypt_data(&encryption_key, &private_key)?;
-
-    let now = Utc::now();
-    Ok(Wallet {
-        public_key,
-        encrypted_private_key,
-        salt: salt.as_str().as_bytes().to_vec(),
-        balance: 0,
-        created_at: now,
-        last_updated: now,
-        network,
-    })
+    let wallet = Wallet::new(public_key, network);
+    let mut app_state = state.lock().await;
+    Ok(app_state.add_session_wallet(wallet, Some(&private_key))?)
 }
 
 #[command]
@@ -424,7 +1013,7 @@ async fn get_balance(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let pubkey = validate_public_key(&public_key)?;
 
     let balance = rpc_client.get_balance(&pubkey)
@@ -440,75 +1029,770 @@ async fn get_balance(
     Ok(balance)
 }
 
+/// Read and decrypt a vault, recovering from backups and migrating old formats
+fn read_vault(storage: &VaultStorage, password: &str) -> Result<Wallets, WalletError> {
+    if !storage.exists() {
+        return Ok(Wallets::empty());
+    }
+
+    // Fall back to the newest backup that decrypts if the primary is truncated or corrupted
+    let recovered = storage.load_with_recovery(|encrypted_data| -> Result<_, WalletError> {
+        let opened = keystore::open(password, encrypted_data)
+            .map_err(|e| WalletError::Encryption(e.to_string()))?;
+        let wallets: Wallets = serde_json::from_slice(&opened.plaintext)?;
+        Ok((wallets, opened))
+    })?;
+    let (mut wallets, opened) = recovered.value;
+
+    // Ensure version is set
+    if wallets.version.is_empty() {
+        wallets.version = "1.0".to_string();
+    }
+
+    // Migration: Add salt and id to wallets that don't have them (backward compatibility)
+    for wallet in &mut wallets.wallets {
+        if wallet.salt.is_empty() && !wallet.is_watch_only() {
+            wallet.salt = generate_salt().to_vec();
+        }
+        if wallet.id.is_empty() {
+            wallet.id = new_wallet_id();
+        }
+    }
+
+    // Migration: Bind keys sealed without associated data to their wallet's id and address
+    let mut rebound = 0;
+    let kdf = SecretKdf::current();
+    for wallet in wallets.wallets.iter_mut().filter(|wallet| wallet.needs_secret_binding()) {
+        let private_key = wallet.open_secret(password)?;
+        wallet.seal_secret(&private_key, password, &kdf)?;
+        rebound += 1;
+    }
+    if rebound > 0 {
+        log::info!("Bound {} wallet keys to their wallets", rebound);
+    }
+
+    // Migration: Upgrade legacy keystore files, apply newly calibrated costs, and restore the primary after a recovery
+    let recalibrated = storage.kdf_params().is_some_and(|kdf_params| *kdf_params != opened.header.kdf_params);
+    if opened.needs_migration() || recalibrated || rebound > 0 || recovered.from_backup {
+        let plaintext = if rebound > 0 {
+            Zeroizing::new(serde_json::to_vec(&wallets)?)
+        } else {
+            Zeroizing::new(opened.plaintext)
+        };
+        let upgraded = seal_vault(storage, password, &plaintext)?;
+        storage.write(&upgraded)?;
+        log::info!(
+            "Rewrote vault from {} ({:?}) as keystore v{}",
+            recovered.source.display(),
+            opened.format,
+            keystore::KEYSTORE_VERSION
+        );
+    }
+
+    Ok(wallets)
+}
+
+/// Encrypt and atomically write a vault
+fn write_vault(storage: &VaultStorage, wallets: &Wallets, password: &str) -> Result<(), WalletError> {
+    let wallets_json = serde_json::to_string(wallets)?;
+
+    // Encrypt the entire wallets structure into a self-describing keystore container
+    let final_data = seal_vault(storage, password, wallets_json.as_bytes())?;
+
+    storage.write(&final_data)?;
+    Ok(())
+}
+
+/// The vault file as it is on disk, to tell whether it was rewritten in the meantime
+fn vault_snapshot(storage: &VaultStorage) -> Option<Vec<u8>> {
+    fs::read(storage.path()).ok()
+}
+
+/// Seal vault contents with the storage's calibrated key derivation costs, or the defaults
+fn seal_vault(storage: &VaultStorage, password: &str, plaintext: &[u8]) -> Result<Vec<u8>, WalletError> {
+    let sealed = match storage.kdf_params() {
+        Some(kdf_params) => keystore::seal_with_kdf(password, kdf_params, plaintext),
+        None => keystore::seal(password, plaintext),
+    };
+    sealed.map_err(|e| WalletError::Encryption(e.to_string()))
+}
+
 #[command]
-async fn save_wallets(wallets: Wallets, password: String) -> Result<(), String> {
-    // Encrypt the entire wallets structure
-    let salt = generate_salt();
-    let encryption_key = derive_encryption_key(&password, &salt)?;
+async fn unlock_vault(
+    password: String,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallets, String> {
+    let mut app_state = state.lock().await;
+    app_state.lock_session(LockReason::Manual);
+
+    let storage = app_state.vault_storage();
+    let wallets = read_vault(&storage, &password)?;
+    let profile = app_state.profiles.active();
+    let (profile_name, policy) = (profile.name.clone(), profile.auto_lock.clone());
 
-    let wallets_json = serde_json::to_string(&wallets)
-        .map_err(|e| WalletError::Serde(e))?;
+    let session = VaultSession::unlock(&profile_name, wallets.clone(), &password, policy)?;
+    if let Err(e) = app.emit(VAULT_UNLOCKED_EVENT, session.unlock_event()) {
+        log::warn!("Failed to emit {}: {}", VAULT_UNLOCKED_EVENT, e);
+    }
+    app_state.session = Some(session);
+    app_state.schedule_vault_rotation(&wallets);
+    if let Err(e) = app_state.open_audit_log() {
+        log::error!("Failed to open the audit log of profile '{}': {}", profile_name, e);
+    }
+    Ok(wallets)
+}
 
-    let encrypted_data = encrypt_data(&encryption_key, wallets_json.as_bytes())?;
+#[command]
+async fn lock_vault(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    let event = state.lock().await.lock_session(LockReason::Manual);
+    emit_vault_locked(&app, event);
+    Ok(())
+}
 
-    // Store salt and encrypted data
-    let mut final_data = salt.to_vec();
-    final_data.extend(encrypted_data);
+#[command]
+async fn load_wallets(
+    password: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallets, String> {
+    let storage = state.lock().await.vault_storage();
+    Ok(read_vault(&storage, &password)?)
+}
 
-    fs::write("wallets.enc", final_data)
-        .map_err(|e| WalletError::Io(e))?;
+/// Re-encrypt one wallet's private key under a new password and fresh salt
+fn reencrypt_wallet(wallet: &Wallet, old_password: &str, new_password: &str, kdf: &SecretKdf) -> Result<Wallet, WalletError> {
+    if wallet.is_watch_only() {
+        return Ok(wallet.clone());
+    }
+
+    let private_key = wallet.open_secret(old_password)?;
+    let mut updated = Wallet {
+        last_updated: Utc::now(),
+        ..wallet.clone()
+    };
+    updated.seal_secret(&private_key, new_password, kdf)?;
+    Ok(updated)
+}
+
+#[command]
+async fn change_password(
+    old_password: String,
+    new_password: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    if new_password.is_empty() {
+        return Err(WalletError::InvalidInput("New password must not be empty".to_string()).to_string());
+    }
+
+    let (storage, vault_id, kdf) = {
+        let app_state = state.lock().await;
+        (app_state.vault_storage(), format!("vault:{}", app_state.profiles.active().name), app_state.secret_kdf())
+    };
+
+    // Re-derives and re-seals every secret; keep it off the async runtime and out of the state lock
+    let rewrap_storage = storage.clone();
+    let (wallets, updated, sealed, backups, snapshot, new_password) = tokio::task::spawn_blocking(move || -> Result<_, WalletError> {
+        let wallets = read_vault(&rewrap_storage, &old_password)?;
+        let snapshot = vault_snapshot(&rewrap_storage);
+        let updated = rewrap_vault(&wallets, &old_password, &new_password, &kdf)?;
+        let sealed = seal_vault(&rewrap_storage, &new_password, serde_json::to_string(&updated)?.as_bytes())?;
+        let backups = rewrap_backups(&rewrap_storage, &old_password, &new_password, &kdf)?;
+        Ok((wallets, updated, sealed, backups, snapshot, new_password))
+    })
+    .await
+    .map_err(|e| WalletError::InvalidInput(e.to_string()))??;
+
+    let mut app_state = state.lock().await;
+    if app_state.vault_storage().path() != storage.path() || vault_snapshot(&storage) != snapshot {
+        return Err(WalletError::InvalidInput("The vault changed while its password was being changed; try again".to_string()).to_string());
+    }
+
+    // Single atomic replace of the whole vault
+    storage.write(&sealed).map_err(WalletError::from)?;
+
+    // The write rotated the old primary into the backups; replace them all with the re-sealed ones
+    storage.replace_backups(&backups).map_err(WalletError::from)?;
+
+    if let Some(session) = &app_state.session {
+        app_state.session = Some(session.rewrapped_with_password(updated.clone(), &new_password)?);
+    }
+
+    let next_rotation = app_state.next_vault_rotation(&updated);
+    get_security_manager().complete_key_rotation(&vault_id, wallets.key_version, updated.key_version, next_rotation);
 
     Ok(())
 }
 
+/// The vault's backups re-sealed under `new_password`, newest first, so they still recover the vault
+///
+/// A backup the old password does not open is corrupt or from an earlier password; it is
+/// dropped rather than kept readable with a password that is being replaced.
+fn rewrap_backups(storage: &VaultStorage, old_password: &str, new_password: &str, kdf: &SecretKdf) -> Result<Vec<Vec<u8>>, WalletError> {
+    let mut rewrapped = vec![];
+    for backup in storage.read_backups()? {
+        let result = keystore::open(old_password, &backup)
+            .map_err(|e| WalletError::Encryption(e.to_string()))
+            .and_then(|opened| Ok(serde_json::from_slice::<Wallets>(&opened.plaintext)?))
+            .and_then(|wallets| rewrap_vault(&wallets, old_password, new_password, kdf))
+            .and_then(|updated| seal_vault(storage, new_password, serde_json::to_string(&updated)?.as_bytes()));
+        match result {
+            Ok(sealed) => rewrapped.push(sealed),
+            Err(e) => log::warn!("Dropping a vault backup that could not be re-sealed: {}", e),
+        }
+    }
+    Ok(rewrapped)
+}
+
+/// Re-encrypt every wallet key and seed under `new_password` with fresh salts, as the next key version
+///
+/// Every secret is resealed with `kdf`. Works on a copy in memory; nothing changes
+/// unless every secret re-encrypts.
+fn rewrap_vault(wallets: &Wallets, old_password: &str, new_password: &str, kdf: &SecretKdf) -> Result<Wallets, WalletError> {
+    let reencrypted = wallets.wallets.iter()
+        .map(|wallet| reencrypt_wallet(wallet, old_password, new_password, kdf))
+        .collect::<Result<Vec<_>, _>>()?;
+    let seeds = wallets.seeds.iter()
+        .map(|seed| seed.reencrypt(old_password, new_password, kdf))
+        .collect::<Result<Vec<_>, _>>()?;
+    let audit_secret = wallets.audit_secret.as_ref()
+        .map(|secret| secret.reencrypt(old_password, new_password, kdf))
+        .transpose()?;
+
+    Ok(Wallets {
+        wallets: reencrypted,
+        version: wallets.version.clone(),
+        key_version: wallets.key_version + 1,
+        key_rotated_at: Some(Utc::now()),
+        seeds,
+        audit_secret,
+        policy_saved: wallets.policy_saved,
+    })
+}
+
+/// Re-wrap the unlocked vault's secrets now instead of waiting for its scheduled rotation
 #[command]
-async fn load_wallets(password: String) -> Result<Wallets, String> {
-    if !Path::new("wallets.enc").exists() {
-        return Ok(Wallets {
-            wallets: vec![],
-            version: "1.0".to_string(),
-        });
+async fn rotate_vault_key(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<u32, String> {
+    Ok(rotate_session_vault_key(&state).await?)
+}
+
+/// Rotate the active profile's vault key every `days` days, or never with `None`
+#[command]
+async fn set_key_rotation_interval(
+    days: Option<u32>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    if days.is_some_and(|days| !(1..=3650).contains(&days)) {
+        return Err(WalletError::InvalidInput("Key rotation interval must be 1 to 3650 days".to_string()).to_string());
     }
 
-    let encrypted_data = fs::read("wallets.enc")
-        .map_err(|e| WalletError::Io(e).to_string())?;
+    let mut app_state = state.lock().await;
+    let mut vault_config = app_state.profiles.active().vault_config.clone();
+    vault_config.key_rotation_days = days;
+    app_state.profiles.set_active_vault_config(vault_config)?;
+
+    if let Ok(session) = app_state.session() {
+        app_state.schedule_vault_rotation(&session.wallets);
+    }
+    Ok(())
+}
 
-    if encrypted_data.len() < 16 {
-        return Err(WalletError::Encryption("Invalid encrypted file".to_string()).to_string());
+#[command]
+async fn set_vault_backup_count(
+    backup_count: usize,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    if backup_count > 32 {
+        return Err(WalletError::InvalidInput("Backup count must be 32 or less".to_string()).to_string());
     }
 
-    let salt_bytes = &encrypted_data[..16];
-    let encrypted_wallets = &encrypted_data[16..];
+    let mut app_state = state.lock().await;
+    let mut vault_config = app_state.profiles.active().vault_config.clone();
+    vault_config.backup_count = backup_count;
+    app_state.profiles.set_active_vault_config(vault_config)?;
+    Ok(())
+}
 
-    let encryption_key = derive_encryption_key(&password, &salt_bytes)?;
+/// Benchmark Argon2id and store the strongest costs that unlock the vault within `target_ms`
+///
+/// An unlocked vault is rewritten with the new costs right away; a locked one on its
+/// next unlock. Each vault file records the costs it was sealed with, so older files
+/// and backups still open.
+///
+/// Only the vault container is calibrated. Keys and seeds inside it keep the default
+/// costs recorded with each of them: unlocking derives one key per secret, so
+/// calibrated costs there would multiply the unlock time by the number of wallets.
+#[command]
+async fn calibrate_vault_kdf(
+    target_ms: Option<u64>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<KdfCalibration, String> {
+    let target_ms = target_ms.unwrap_or(1000);
+    if !(100..=10_000).contains(&target_ms) {
+        return Err(WalletError::InvalidInput("Unlock target must be 100 to 10000 ms".to_string()).to_string());
+    }
 
-    let decrypted_json = decrypt_data(&encryption_key, encrypted_wallets)?;
-    let wallets_json = String::from_utf8(decrypted_json)
-        .map_err(|e| WalletError::Encryption(e.to_string()))?;
+    // Runs several full-cost derivations; keep them off the async runtime
+    let calibration = tokio::task::spawn_blocking(move || {
+        get_security_manager().calibrate_argon2(Duration::from_millis(target_ms))
+    })
+    .await
+    .map_err(|e| WalletError::InvalidInput(e.to_string()))??;
 
-    let mut wallets: Wallets = serde_json::from_str(&wallets_json)
-        .map_err(|e| WalletError::Serde(e))?;
+    let mut app_state = state.lock().await;
+    let mut vault_config = app_state.profiles.active().vault_config.clone();
+    vault_config.kdf_params = Some(keystore::KdfParams::from(&calibration.params));
+    app_state.profiles.set_active_vault_config(vault_config)?;
 
-    // Ensure version is set
-    if wallets.version.is_empty() {
-        wallets.version = "1.0".to_string();
+    if let Ok(session) = app_state.session() {
+        let password = Zeroizing::new(session.password()?);
+        write_vault(&app_state.vault_storage(), &session.wallets, &password)?;
     }
 
-    // Migration: Add salt to wallets that don't have it (backward compatibility)
-    for wallet in &mut wallets.wallets {
-        if wallet.salt.is_empty() {
-            wallet.salt = generate_salt().to_vec();
+    log::info!(
+        "Calibrated vault key derivation to {} KiB, {} passes in {} ms",
+        calibration.params.memory_cost,
+        calibration.params.time_cost,
+        calibration.elapsed_ms
+    );
+    Ok(calibration)
+}
+
+#[command]
+async fn get_auto_lock_policy(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<AutoLockPolicy, String> {
+    Ok(state.lock().await.profiles.active().auto_lock.clone())
+}
+
+#[command]
+async fn set_auto_lock_policy(
+    policy: AutoLockPolicy,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    policy.validate()?;
+
+    let mut app_state = state.lock().await;
+    app_state.profiles.set_active_auto_lock(policy.clone())?;
+
+    // Applies to the current session too; the auto-lock task picks it up on its next check
+    if let Some(session) = app_state.session.as_mut() {
+        session.policy = policy;
+    }
+    Ok(())
+}
+
+#[command]
+async fn get_transaction_policy(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<TransactionPolicy, String> {
+    Ok(state.lock().await.policy_store()?.load_policy()?)
+}
+
+/// Replace the active profile's spending policy; only an unlocked vault may change its own limits
+#[command]
+async fn set_transaction_policy(
+    policy: TransactionPolicy,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    for rule in &policy.rules {
+        if let Some(wallet) = &rule.wallet {
+            validate_public_key(wallet)?;
+        }
+        if rule.asset != NATIVE_ASSET {
+            validate_public_key(&rule.asset)?;
         }
     }
 
-    Ok(wallets)
+    state.lock().await.save_policy(&policy)?;
+    Ok(())
+}
+
+/// Amount each wallet sent of each asset over the last 24 hours
+#[command]
+async fn get_spend_volume(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<Vec<SpendVolume>, String> {
+    let store = state.lock().await.policy_store()?;
+    let ledger = store.load_ledger(&store.load_policy()?)?;
+    Ok(ledger.volumes(Utc::now()))
+}
+
+#[command]
+async fn list_pending_approvals(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<Vec<PendingApproval>, String> {
+    let mut app_state = state.lock().await;
+    let timeout = app_state.policy_store()?.load_policy()?.security.transaction_timeout;
+    Ok(app_state.approvals.pending(timeout, Utc::now()))
+}
+
+/// Approve a transaction blocked by `require_approval`; submitting it again within the timeout goes through once
+#[command]
+async fn approve_transaction(
+    request_id: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<PendingApproval, String> {
+    let mut app_state = state.lock().await;
+    app_state.session()?;
+    let timeout = app_state.policy_store()?.load_policy()?.security.transaction_timeout;
+    Ok(app_state.approvals.approve(&request_id, timeout, Utc::now())?)
+}
+
+#[command]
+async fn reject_transaction(
+    request_id: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    Ok(state.lock().await.approvals.reject(&request_id)?)
+}
+
+#[command]
+async fn get_address_lists(
+    scope: ListScope,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<AddressLists, String> {
+    let app_state = state.lock().await;
+    Ok(AddressLists::load(&app_state.address_lists_path(scope))?)
+}
+
+/// List `address`, replacing its existing entry on that list
+#[command]
+async fn add_address_list_entry(
+    kind: ListKind,
+    address: String,
+    reason: String,
+    expires_at: Option<DateTime<Utc>>,
+    scope: ListScope,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<AddressEntry, String> {
+    if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(WalletError::InvalidInput("Expiry must be in the future".to_string()).to_string());
+    }
+
+    let entry = AddressEntry {
+        address,
+        reason,
+        added_at: Utc::now(),
+        expires_at,
+        source: None,
+    };
+    let app_state = state.lock().await;
+    app_state.update_address_lists(scope, |lists| lists.add(kind, entry.clone()))?;
+    Ok(entry)
+}
+
+#[command]
+async fn remove_address_list_entry(
+    kind: ListKind,
+    address: String,
+    scope: ListScope,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<bool, String> {
+    let app_state = state.lock().await;
+    Ok(app_state.update_address_lists(scope, |lists| Ok(lists.remove(kind, &address)))?)
+}
+
+/// Import a CSV or JSON list file, e.g. a compliance feed; the format follows the file extension unless given
+#[command]
+async fn import_address_list(
+    kind: ListKind,
+    path: String,
+    format: Option<ImportFormat>,
+    scope: ListScope,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<ImportReport, String> {
+    let path = PathBuf::from(path);
+    let format = match format {
+        Some(format) => format,
+        None => ImportFormat::from_path(&path)?,
+    };
+    let data = fs::read(&path).map_err(WalletError::from)?;
+    let source = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned());
+
+    let app_state = state.lock().await;
+    let report = app_state.update_address_lists(scope, |lists| lists.import(kind, format, &data, &source, Utc::now()))?;
+    log::info!(
+        "Imported {:?} list from {}: {} added, {} updated, {} skipped",
+        kind, source, report.added, report.updated, report.skipped.len()
+    );
+    Ok(report)
+}
+
+/// Switch allowlist-only mode of the active profile: while on, only allowlisted recipients can be paid
+#[command]
+async fn set_allowlist_only(
+    enabled: bool,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<(), String> {
+    let mut app_state = state.lock().await;
+    app_state.session()?;
+    let mut policy = app_state.policy_store()?.load_policy()?;
+    policy.security.whitelist_enabled = enabled;
+    app_state.save_policy(&policy)?;
+    Ok(())
+}
+
+/// Check the active profile's audit log for deleted, edited or forged records
+#[command]
+async fn verify_audit_log(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<AuditVerification, String> {
+    let mut app_state = state.lock().await;
+    app_state.session()?;
+    app_state.flush_audit_events();
+    let audit_log = app_state.audit_log.as_ref().ok_or(WalletError::VaultLocked)?;
+    Ok(audit_log.verify()?)
+}
+
+/// Audit log records by event type and time range, most recent first
+#[command]
+async fn query_audit_log(
+    query: AuditQuery,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Vec<AuditRecord>, String> {
+    let mut app_state = state.lock().await;
+    app_state.session()?;
+    app_state.flush_audit_events();
+    let audit_log = app_state.audit_log.as_ref().ok_or(WalletError::VaultLocked)?;
+    Ok(audit_log.query(&query)?)
+}
+
+/// Write the unlocked vault and its profile settings to one file encrypted under a separate backup password
+#[command]
+async fn export_backup(
+    backup_password: String,
+    path: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<String, String> {
+    let app_state = state.lock().await;
+    let session = app_state.session()?;
+    let profile = app_state.profiles.active();
+
+    let wallets = session.wallets.wallets.iter()
+        .map(|wallet| -> Result<BackupWallet, WalletError> {
+            let secret_key = if wallet.is_watch_only() {
+                vec![]
+            } else {
+                session.keypair(&wallet.public_key)?.to_bytes().to_vec()
+            };
+            Ok(BackupWallet {
+                wallet: Wallet {
+                    encrypted_private_key: vec![],
+                    salt: vec![],
+                    kdf: None,
+                    secret_version: 0,
+                    ..wallet.clone()
+                },
+                secret_key,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let password = Zeroizing::new(session.password()?);
+    let seeds = session.wallets.seeds.iter()
+        .map(|seed| -> Result<BackupSeed, WalletError> {
+            let secrets = seed.open(&password)?;
+            Ok(BackupSeed {
+                seed: SeedEntry {
+                    encrypted_mnemonic: vec![],
+                    encrypted_passphrase: vec![],
+                    salt: vec![],
+                    kdf: None,
+                    ..seed.clone()
+                },
+                phrase: secrets.phrase.to_string(),
+                passphrase: secrets.passphrase.map(|passphrase| passphrase.to_string()),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let settings = BackupSettings {
+        vault_config: profile.vault_config.clone(),
+        auto_lock: profile.auto_lock.clone(),
+    };
+    let sealed = BackupBundle::new(&profile.name, wallets, seeds, settings).seal(&backup_password)?;
+
+    // Atomic write, without rotating copies of older backups next to it
+    let path = PathBuf::from(path);
+    VaultStorage::new(&path, &VaultConfig { backup_count: 0, ..VaultConfig::default() })
+        .write(&sealed)
+        .map_err(WalletError::from)?;
+
+    log::info!("Exported vault backup of profile '{}' to {}", profile.name, path.display());
+    Ok(path.display().to_string())
+}
+
+/// Merge a backup bundle into the unlocked vault
+///
+/// Wallets are matched by public key: new ones are added, watch-only wallets are
+/// upgraded when the backup holds their key, and everything else is left as is.
+/// With `dry_run` only the report of what would change is returned.
+#[command]
+async fn import_backup(
+    path: String,
+    backup_password: String,
+    dry_run: Option<bool>,
+    restore_settings: Option<bool>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<BackupImportReport, String> {
+    let data = fs::read(&path).map_err(WalletError::from)?;
+    let bundle = BackupBundle::open(&backup_password, &data)?;
+
+    let mut app_state = state.lock().await;
+    let mut report = plan_import(&app_state.session()?.wallets, &bundle);
+    report.dry_run = dry_run.unwrap_or(false);
+    report.settings_restored = restore_settings.unwrap_or(false);
+    if report.dry_run {
+        return Ok(report);
+    }
+
+    app_state.update_session_wallets(|wallets, password, kdf| {
+        // Seed ids of the bundle, mapped to the vault's entry for the same seed
+        let mut seed_ids = HashMap::new();
+        for entry in &bundle.seeds {
+            let existing = wallets.seeds.iter()
+                .find(|seed| seed.fingerprint == entry.seed.fingerprint && seed.path_template == entry.seed.path_template);
+            if let Some(existing) = existing {
+                seed_ids.insert(entry.seed.id.clone(), existing.id.clone());
+                continue;
+            }
+            if !report.seeds_added.contains(&entry.seed.fingerprint) {
+                continue;
+            }
+
+            let mut seed = entry.seed.clone();
+            if seed.id.is_empty() || wallets.seeds.iter().any(|other| other.id == seed.id) {
+                seed.id = new_wallet_id();
+            }
+            let secrets = SeedSecrets {
+                phrase: Zeroizing::new(entry.phrase.clone()),
+                passphrase: entry.passphrase.clone().map(Zeroizing::new),
+            };
+            mnemonic::parse(&secrets.phrase, Some(seed.language)).map_err(|e| WalletError::Bip39(e.to_string()))?;
+            seed.seal(&secrets, password, kdf)?;
+
+            // Without the passphrase the phrase cannot be checked against the fingerprint yet
+            if !seed.uses_passphrase || secrets.passphrase.is_some() {
+                seed.seed(password, None)?;
+            }
+            seed_ids.insert(entry.seed.id.clone(), seed.id.clone());
+            wallets.seeds.push(seed);
+        }
+
+        for entry in &bundle.wallets {
+            let public_key = &entry.wallet.public_key;
+            let is_added = report.added.contains(public_key)
+                && !wallets.wallets.iter().any(|wallet| wallet.public_key == *public_key);
+            let is_upgraded = report.upgraded.contains(public_key);
+            if !is_added && !is_upgraded {
+                continue;
+            }
+
+            if !entry.secret_key.is_empty() {
+                // The secret decides the address, not whatever public key the bundle claims
+                let keypair = keypair_from_secret(&entry.secret_key)
+                    .map_err(|e| WalletError::InvalidInput(e.to_string()))?;
+                if keypair.pubkey().to_string() != *public_key {
+                    return Err(WalletError::InvalidInput(format!("Backup key does not match wallet {}", public_key)));
+                }
+            }
+
+            if is_upgraded {
+                if let Some(wallet) = wallets.wallets.iter_mut().find(|wallet| wallet.public_key == *public_key) {
+                    wallet.seal_secret(&entry.secret_key, password, kdf)?;
+                    wallet.last_updated = Utc::now();
+                }
+                continue;
+            }
+
+            let id_taken = wallets.wallets.iter().any(|wallet| wallet.id == entry.wallet.id);
+            let mut wallet = Wallet {
+                id: if id_taken || entry.wallet.id.is_empty() { new_wallet_id() } else { entry.wallet.id.clone() },
+                kind: WalletKind::WatchOnly,
+                encrypted_private_key: vec![],
+                salt: vec![],
+                kdf: None,
+                secret_version: 0,
+                last_updated: Utc::now(),
+                seed_id: entry.wallet.seed_id.as_ref().and_then(|id| seed_ids.get(id).cloned()),
+                ..entry.wallet.clone()
+            };
+            // Sealed once the id is final, as the key is bound to it
+            if !entry.secret_key.is_empty() {
+                wallet.seal_secret(&entry.secret_key, password, kdf)?;
+            }
+            wallets.wallets.push(wallet);
+        }
+        Ok(())
+    })?;
+
+    if report.settings_restored {
+        app_state.profiles.set_active_vault_config(bundle.settings.vault_config.clone())?;
+        app_state.profiles.set_active_auto_lock(bundle.settings.auto_lock.clone())?;
+        if let Some(session) = app_state.session.as_mut() {
+            session.policy = bundle.settings.auto_lock.clone();
+        }
+    }
+
+    log::info!(
+        "Imported vault backup: {} added, {} upgraded, {} duplicates, {} seeds added",
+        report.added.len(), report.upgraded.len(), report.duplicates.len(), report.seeds_added.len()
+    );
+    Ok(report)
+}
+
+#[command]
+async fn list_profiles(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<Vec<ProfileSummary>, String> {
+    Ok(state.lock().await.profiles.list())
+}
+
+#[command]
+async fn create_profile(
+    name: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Vec<ProfileSummary>, String> {
+    let mut app_state = state.lock().await;
+    app_state.profiles.create(&name)?;
+    Ok(app_state.profiles.list())
+}
+
+#[command]
+async fn switch_profile(
+    name: String,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Vec<ProfileSummary>, String> {
+    let mut app_state = state.lock().await;
+    let event = app_state.lock_session(LockReason::ProfileChanged);
+    emit_vault_locked(&app, event);
+    app_state.profiles.switch(&name)?;
+    Ok(app_state.profiles.list())
+}
+
+#[command]
+async fn rename_profile(
+    old_name: String,
+    new_name: String,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Vec<ProfileSummary>, String> {
+    let mut app_state = state.lock().await;
+    if app_state.profiles.active().name == old_name {
+        let event = app_state.lock_session(LockReason::ProfileChanged);
+        emit_vault_locked(&app, event);
+    }
+    app_state.profiles.rename(&old_name, &new_name)?;
+    Ok(app_state.profiles.list())
+}
+
+/// Delete an inactive profile; one that still has a vault is only deleted with `force`
+#[command]
+async fn delete_profile(
+    name: String,
+    force: Option<bool>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Vec<ProfileSummary>, String> {
+    let mut app_state = state.lock().await;
+    app_state.profiles.delete(&name, force.unwrap_or(false))?;
+    Ok(app_state.profiles.list())
 }
 
 #[command]
 async fn transfer_tokens(
-    from_public_key: String,
-    encrypted_private_key: Vec<u8>,
-    password: String,
+    wallet_id: String,
     to_public_key: String,
     amount: u64,
     token_mint: Option<String>,
@@ -520,10 +1804,9 @@ async fn transfer_tokens(
     let mut app_state = state.lock().await;
     let security_manager = get_security_manager();
 
-    // Security validation
-    if let Err(e) = security_manager.validate_transaction(&from_public_key, &to_public_key, amount) {
-        return Err(e.to_string());
-    }
+    // Signing key comes from the unlocked vault session
+    let from_keypair = app_state.session()?.keypair(&wallet_id)?;
+    let from_public_key = from_keypair.pubkey().to_string();
 
     // Check rate limit for transactions
     let client_key = format!("transfer_{}", from_public_key);
@@ -544,13 +1827,6 @@ async fn transfer_tokens(
     }
     *count += 1;
 
-    // Decrypt private key using stored salt
-    let encryption_key = derive_encryption_key(&password, &encrypted_private_key)?;
-    let private_key = decrypt_data(&encryption_key, &encrypted_private_key)?;
-
-    let from_keypair = Keypair::from_bytes(&private_key)
-        .map_err(|e| WalletError::Encryption(e.to_string()).to_string())?;
-
     let to_pubkey = validate_public_key(&to_public_key)?;
 
     let network_enum = match network.as_str() {
@@ -560,7 +1836,16 @@ async fn transfer_tokens(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let spends = [Spend {
+        wallet: from_public_key.clone(),
+        asset: token_mint.clone().unwrap_or_else(|| NATIVE_ASSET.to_string()),
+        amount,
+        recipient: Some(to_public_key.clone()),
+    }];
+    // Sent with a preflight simulation
+    app_state.authorize_spends("transfer_tokens", &spends, true)?;
+
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let recent_blockhash = rpc_client.get_latest_blockhash()
         .map_err(|e| WalletError::Solana(e.to_string()).to_string())?;
 
@@ -590,6 +1875,7 @@ async fn transfer_tokens(
 
     let signature = rpc_client.send_and_confirm_transaction(&transaction)
         .map_err(|e| WalletError::Solana(e.to_string()).to_string())?;
+    app_state.record_spends("transfer_tokens", &spends);
 
     // Clear balance cache for involved addresses
     app_state.performance_cache.invalidate(&format!("balance_{}_{}", from_public_key, network)).await;
@@ -605,7 +1891,7 @@ async fn get_jupiter_quote(
     amount: String,
     slippage_bps: u16
 ) -> Result<JupiterQuote, String> {
-    let client = Client::new();
+    let client = http_client::client();
     let url = format!(
         "https://quote-api.jup.ag/v6/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}",
         input_mint, output_mint, amount, slippage_bps
@@ -626,17 +1912,18 @@ async fn get_jupiter_quote(
 #[command]
 async fn execute_jupiter_swap(
     quote_response: String,
-    user_public_key: String,
-    wallet: Wallet,
-    password: String,
-    network: String
+    wallet_id: String,
+    network: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<String, String> {
+    let user_public_key = state.lock().await.session()?.resolve(&wallet_id)?.public_key.clone();
+
     // Decode the quote response
     let quote_data: serde_json::Value = serde_json::from_str(&quote_response)
         .map_err(|e| format!("Invalid quote response: {}", e))?;
 
     // Get swap transaction from Jupiter
-    let client = Client::new();
+    let client = http_client::client();
     let swap_url = "https://quote-api.jup.ag/v6/swap";
 
     let swap_payload = serde_json::json!({
@@ -663,8 +1950,7 @@ async fn execute_jupiter_swap(
 
 #[command]
 async fn send_bundle_transaction(
-    public_key: String,
-    private_key: Vec<u8>,
+    wallet_id: String,
     recipient: String,
     amount: u64,
     network: String,
@@ -674,61 +1960,90 @@ async fn send_bundle_transaction(
 ) -> Result<String, String> {
     validate_amount(amount)?;
 
-    let app_state = state.lock().await;
-    let pumpfun_interface = &app_state.pumpfun_interface;
+    let mut app_state = state.lock().await;
+    let keypair = app_state.session()?.keypair(&wallet_id)?;
+    let pumpfun_interface = app_state.pumpfun_interface.clone();
+
+    let spends = [Spend::sol(&keypair.pubkey().to_string(), amount, Some(&recipient))];
+    app_state.authorize_spends("send_bundle_transaction", &spends, pumpfun_interface.simulates_before_submit())?;
 
     // Use the enhanced pumpfun interface for bundle transactions
     match pumpfun_interface.create_bundle_transaction(
-        public_key,
-        private_key,
+        keypair,
         recipient,
         amount,
         network,
         use_jito.unwrap_or(false),
         tip_lamports
     ).await {
-        Ok(signature) => Ok(signature),
+        Ok(signature) => {
+            app_state.record_spends("send_bundle_transaction", &spends);
+            Ok(signature)
+        }
         Err(e) => Err(e.to_string())
     }
 }
 
 #[command]
 async fn create_pump_fun_token(
-    dev_wallet: Wallet,
-    password: String,
+    dev_wallet_id: String,
     metadata: PumpfunTokenMetadata,
     network: String,
     use_jito: Option<bool>,
     state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<PumpfunTokenResponse, String> {
-    let app_state = state.lock().await;
-    let pumpfun_interface = &app_state.pumpfun_interface;
+    let mut app_state = state.lock().await;
+    let dev_keypair = app_state.session()?.keypair(&dev_wallet_id)?;
+    let pumpfun_interface = app_state.pumpfun_interface.clone();
+
+    let liquidity_lamports = sol_to_lamports(metadata.initial_liquidity_sol)?;
+    let spends = [Spend::sol(&dev_keypair.pubkey().to_string(), liquidity_lamports, None)];
+    app_state.authorize_spends("create_pump_fun_token", &spends, pumpfun_interface.simulates_before_submit())?;
 
     match pumpfun_interface.create_pump_fun_token(
-        dev_wallet,
-        password,
+        dev_keypair,
         metadata,
         network,
-        use_jito.unwrap_or(false)
+        use_jito
     ).await {
-        Ok(response) => Ok(response),
+        Ok(response) => {
+            app_state.record_spends("create_pump_fun_token", &spends);
+            Ok(response)
+        }
         Err(e) => Err(e.to_string())
     }
 }
 
+/// Spends of a bundle buy: every wallet pays `amount_per_wallet` lamports for the token
+///
+/// The mint is what is bought, not where the funds go, so it is no recipient for the address
+/// lists. The number of tokens received is unknown until the swap lands, so token rules don't apply.
+fn bundle_buy_spends(wallets: &[String], amount_per_wallet: u64) -> Vec<Spend> {
+    wallets.iter()
+        .map(|wallet| Spend::sol(wallet, amount_per_wallet, None))
+        .collect()
+}
+
 #[command]
 async fn execute_bundle_buy(
-    bundle_wallets: Vec<Wallet>,
+    bundle_wallet_ids: Vec<String>,
     token_address: String,
     amount_per_wallet: u64,
-    password: String,
     swap_dapp: String,
     network: String,
     use_mev_protection: Option<bool>,
     state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<BundleExecutionResult, String> {
-    let app_state = state.lock().await;
-    let pumpfun_interface = &app_state.pumpfun_interface;
+    let mut app_state = state.lock().await;
+    let session = app_state.session()?;
+    let bundle_keypairs = bundle_wallet_ids.iter()
+        .map(|wallet_id| session.keypair(wallet_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pumpfun_interface = app_state.pumpfun_interface.clone();
+
+    let wallets: Vec<String> = bundle_keypairs.iter().map(|keypair| keypair.pubkey().to_string()).collect();
+    let spends = bundle_buy_spends(&wallets, amount_per_wallet);
+    app_state.authorize_spends("execute_bundle_buy", &spends, pumpfun_interface.simulates_before_submit())?;
 
     // Convert swap dapp string to enum
     let swap_dapp_enum = match swap_dapp.to_lowercase().as_str() {
@@ -740,23 +2055,25 @@ async fn execute_bundle_buy(
     };
 
     match pumpfun_interface.execute_bundle_buy(
-        bundle_wallets,
+        bundle_keypairs,
         token_address,
         amount_per_wallet,
-        password,
         swap_dapp_enum,
         network,
         use_mev_protection.unwrap_or(true)
     ).await {
-        Ok(response) => Ok(BundleExecutionResult {
-            bundle_id: format!("bundle_{}", Utc::now().timestamp()),
-            signatures: response.signatures,
-            total_amount: response.total_amount,
-            execution_time: response.execution_time,
-            success_count: response.success_count,
-            error_count: response.total_transactions - response.success_count,
-            estimated_profit: None, // Could be calculated based on market data
-        }),
+        Ok(response) => {
+            app_state.record_spends("execute_bundle_buy", &spends);
+            Ok(BundleExecutionResult {
+                bundle_id: format!("bundle_{}", Utc::now().timestamp()),
+                signatures: response.signatures,
+                total_amount: response.total_amount,
+                execution_time: response.execution_time,
+                success_count: response.success_count,
+                error_count: response.total_transactions - response.success_count,
+                estimated_profit: None, // Could be calculated based on market data
+            })
+        }
         Err(e) => Err(e.to_string())
     }
 }
@@ -773,7 +2090,7 @@ async fn get_transaction_history(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let pubkey = validate_public_key(&public_key)?;
 
     let signatures = rpc_client.get_signatures_for_address(&pubkey)
@@ -802,59 +2119,382 @@ async fn get_transaction_history(
     Ok(TransactionHistory { transactions })
 }
 
+/// Search all CPU cores for a keypair whose address matches `pattern`, then add it to the unlocked vault
+///
+/// Progress is emitted as `vanity-progress` events until the key is found or
+/// `cancel_vanity_search` is called. Only one search runs at a time.
+#[command]
+async fn generate_vanity_wallet(
+    pattern: VanityPattern,
+    network: String,
+    threads: Option<usize>,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallet, String> {
+    pattern.validate()?;
+
+    // The state lock is not held while searching, so the search can be cancelled
+    let cancel = {
+        let mut app_state = state.lock().await;
+        app_state.session()?;
+        if app_state.vanity_cancel.is_some() {
+            return Err(WalletError::InvalidInput("A vanity search is already running".to_string()).to_string());
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        app_state.vanity_cancel = Some(cancel.clone());
+        cancel
+    };
+
+    let threads = threads.unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+    let progress_app = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        vanity::search(&pattern, threads, &cancel, |progress| {
+            if let Err(e) = progress_app.emit(VANITY_PROGRESS_EVENT, &progress) {
+                log::warn!("Failed to emit {}: {}", VANITY_PROGRESS_EVENT, e);
+            }
+        })
+    })
+    .await;
+
+    let mut app_state = state.lock().await;
+    app_state.vanity_cancel = None;
+
+    let (keypair, attempts) = match result.map_err(|e| WalletError::InvalidInput(e.to_string()))? {
+        Ok(found) => found,
+        Err(VanityError::Cancelled) => {
+            if let Err(e) = app.emit(VANITY_CANCELLED_EVENT, ()) {
+                log::warn!("Failed to emit {}: {}", VANITY_CANCELLED_EVENT, e);
+            }
+            return Err(VanityError::Cancelled.into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // The key goes straight into the vault; it is never returned in plaintext
+    let private_key = Zeroizing::new(keypair.to_bytes().to_vec());
+    let wallet = app_state.add_session_wallet(Wallet::new(keypair.pubkey().to_string(), network), Some(&private_key))?;
+
+    let found = VanityFound {
+        public_key: wallet.public_key.clone(),
+        attempts,
+    };
+    if let Err(e) = app.emit(VANITY_FOUND_EVENT, &found) {
+        log::warn!("Failed to emit {}: {}", VANITY_FOUND_EVENT, e);
+    }
+    Ok(wallet)
+}
+
+/// Stop the running vanity search; returns whether one was running
+#[command]
+async fn cancel_vanity_search(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<bool, String> {
+    let app_state = state.lock().await;
+    match &app_state.vanity_cancel {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[command]
+async fn generate_seed_phrase(
+    word_count: Option<usize>,
+    language: Option<MnemonicLanguage>
+) -> Result<String, String> {
+    let phrase = mnemonic::generate(word_count.unwrap_or(24), language.unwrap_or_default())?;
+    Ok(phrase.to_string())
+}
+
+/// Split a seed phrase into SLIP-39 shares, so no single place holds the whole seed
+///
+/// The shared secret is the phrase's BIP39 entropy, so combining the shares gives
+/// back the same phrase. `share_passphrase` protects the shares and is unrelated to
+/// any BIP39 passphrase of the seed.
+#[command]
+async fn split_seed_phrase(
+    seed_phrase: String,
+    group_threshold: u8,
+    groups: Vec<GroupSpec>,
+    share_passphrase: Option<String>,
+    iteration_exponent: Option<u8>,
+    language: Option<MnemonicLanguage>
+) -> Result<Vec<ShareGroup>, String> {
+    let entropy = Zeroizing::new(mnemonic::parse(&seed_phrase, language)?.to_entropy());
+    let passphrase = Zeroizing::new(share_passphrase.unwrap_or_default());
+
+    // Encryption runs PBKDF2 with up to millions of iterations
+    tokio::task::spawn_blocking(move || {
+        slip39::split(&entropy, &passphrase, group_threshold, &groups, iteration_exponent.unwrap_or(1))
+    })
+    .await
+    .map_err(|e| WalletError::InvalidInput(e.to_string()))?
+    .map_err(String::from)
+}
+
+/// Recover the seed phrase from enough SLIP-39 shares of one split
+#[command]
+async fn combine_seed_shares(
+    shares: Vec<String>,
+    share_passphrase: Option<String>,
+    language: Option<MnemonicLanguage>
+) -> Result<String, String> {
+    let passphrase = Zeroizing::new(share_passphrase.unwrap_or_default());
+    let entropy = tokio::task::spawn_blocking(move || slip39::combine(&shares, &passphrase))
+        .await
+        .map_err(|e| WalletError::InvalidInput(e.to_string()))??;
+
+    let phrase = mnemonic::from_entropy(&entropy, language.unwrap_or_default())?;
+    Ok(phrase.to_string())
+}
+
+/// Group and threshold metadata of one SLIP-39 share
 #[command]
-async fn generate_seed_phrase() -> Result<String, String> {
-    let mut entropy = [0u8; 32];
-    OsRng.fill_bytes(&mut entropy);
-    let mnemonic = Mnemonic::from_entropy(&entropy)
-        .map_err(|e| WalletError::Bip39(e.to_string()).to_string())?;
-    Ok(mnemonic.to_string())
+async fn inspect_seed_share(share: String) -> Result<ShareInfo, String> {
+    Ok(slip39::inspect(&share)?)
 }
 
 #[command]
 async fn import_wallet_from_seed_phrase(
     seed_phrase: String,
-    password: String,
-    network: String
+    network: String,
+    path_template: Option<PathTemplate>,
+    account_index: Option<u32>,
+    passphrase: Option<String>,
+    language: Option<MnemonicLanguage>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<Wallet, String> {
-    let mnemonic = Mnemonic::parse_in_normalized(Language::English, &seed_phrase)
-        .map_err(|e| WalletError::Bip39(e.to_string()).to_string())?;
+    let seed = mnemonic::to_seed(&seed_phrase, passphrase.as_deref(), language)?;
 
-    let seed = mnemonic.to_seed("");
-    let keypair = Keypair::from_seed(&seed[..32])
-        .map_err(|e| WalletError::Bip39(e.to_string()).to_string())?;
+    // Default to m/44'/501'/0'/0' so the address matches Phantom, Solflare and solana-keygen
+    let template = path_template.unwrap_or_default();
+    let account = account_index.unwrap_or(0);
+    let keypair = template.derive_keypair(&seed[..], account)?;
+    let derivation_path = template.path(account)?.map(|path| path.to_string());
 
     let public_key = keypair.pubkey().to_string();
-    let private_key = keypair.secret().to_bytes().to_vec();
+    let private_key = Zeroizing::new(keypair.to_bytes().to_vec());
 
-    // Encrypt private key
-    let salt = SaltString::generate(&mut OsRng);
-    let encryption_key = derive_encryption_key(&password, salt.as_str().as_bytes())?;
-    let encrypted_private_key = encrypt_data(&encryption_key, &private_key)?;
+    let wallet = Wallet { derivation_path, ..Wallet::new(public_key, network) };
+    let mut app_state = state.lock().await;
+    Ok(app_state.add_session_wallet(wallet, Some(&private_key))?)
+}
+
+/// Derive the seed's next account into `wallets` and advance its counter
+///
+/// An existing wallet with the same address is linked to the seed instead of
+/// being duplicated, and gets its key if it was watch-only.
+fn derive_seed_account(
+    wallets: &mut Wallets,
+    seed: &mut SeedEntry,
+    passphrase: Option<&str>,
+    password: &str,
+    kdf: &SecretKdf,
+    network: &str,
+) -> Result<Wallet, WalletError> {
+    let account = seed.next_account;
+    let bip39_seed = seed.seed(password, passphrase)?;
+    let keypair = seed.path_template.derive_keypair(&bip39_seed[..], account)
+        .map_err(|e| WalletError::InvalidInput(e.to_string()))?;
+    let derivation_path = seed.path_template.path(account)
+        .map_err(|e| WalletError::InvalidInput(e.to_string()))?
+        .map(|path| path.to_string());
+    seed.next_account = account + 1;
 
+    let public_key = keypair.pubkey().to_string();
+    let private_key = Zeroizing::new(keypair.to_bytes().to_vec());
     let now = Utc::now();
-    Ok(Wallet {
-        public_key,
-        encrypted_private_key,
-        salt: salt.as_str().as_bytes().to_vec(),
-        balance: 0,
-        created_at: now,
-        last_updated: now,
-        network,
+
+    if let Some(wallet) = wallets.wallets.iter_mut().find(|wallet| wallet.public_key == public_key) {
+        if wallet.is_watch_only() {
+            wallet.seal_secret(&private_key, password, kdf)?;
+        }
+        wallet.seed_id = Some(seed.id.clone());
+        wallet.derivation_path = derivation_path;
+        wallet.last_updated = now;
+        return Ok(wallet.clone());
+    }
+
+    let mut wallet = Wallet {
+        metadata: WalletMetadata {
+            name: format!("{} #{}", seed.name, account),
+            ..Default::default()
+        },
+        derivation_path,
+        seed_id: Some(seed.id.clone()),
+        ..Wallet::new(public_key, network.to_string())
+    };
+    wallet.seal_secret(&private_key, password, kdf)?;
+    wallets.wallets.push(wallet.clone());
+    Ok(wallet)
+}
+
+/// Keep a seed phrase in the unlocked vault and add its first account
+///
+/// The passphrase, if any, is only stored with `store_passphrase`; otherwise it
+/// must be given again to every `derive_next_account` call.
+#[command]
+async fn add_seed_phrase(
+    seed_phrase: String,
+    name: Option<String>,
+    network: String,
+    path_template: Option<PathTemplate>,
+    passphrase: Option<String>,
+    store_passphrase: Option<bool>,
+    language: Option<MnemonicLanguage>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallet, String> {
+    let mut app_state = state.lock().await;
+    let wallet = app_state.update_session_wallets(|wallets, password, kdf| {
+        let mut seed = SeedEntry::new(
+            name.as_deref().unwrap_or("Seed"),
+            &seed_phrase,
+            passphrase.as_deref(),
+            store_passphrase.unwrap_or(false),
+            language,
+            path_template.unwrap_or_default(),
+            password,
+            kdf,
+        )?;
+        let is_duplicate = wallets.seeds.iter()
+            .any(|other| other.fingerprint == seed.fingerprint && other.path_template == seed.path_template);
+        if is_duplicate {
+            return Err(WalletError::InvalidInput("This seed phrase is already in the vault".to_string()));
+        }
+
+        let wallet = derive_seed_account(wallets, &mut seed, passphrase.as_deref(), password, kdf, &network)?;
+        wallets.seeds.push(seed);
+        Ok(wallet)
+    })?;
+    Ok(wallet)
+}
+
+/// Add the next account of a stored seed under its path template
+#[command]
+async fn derive_next_account(
+    seed_id: String,
+    network: String,
+    passphrase: Option<String>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallet, String> {
+    let mut app_state = state.lock().await;
+    let wallet = app_state.update_session_wallets(|wallets, password, kdf| {
+        let index = wallets.seeds.iter()
+            .position(|seed| seed.id == seed_id)
+            .ok_or_else(|| WalletError::InvalidInput(format!("Seed not found: {}", seed_id)))?;
+
+        let mut seed = wallets.seeds[index].clone();
+        let wallet = derive_seed_account(wallets, &mut seed, passphrase.as_deref(), password, kdf, &network)?;
+        wallets.seeds[index] = seed;
+        Ok(wallet)
+    })?;
+    Ok(wallet)
+}
+
+#[command]
+async fn list_seeds(state: tauri::State<'_, Arc<Mutex<AppState>>>) -> Result<Vec<SeedSummary>, String> {
+    let app_state = state.lock().await;
+    Ok(app_state.session()?.wallets.seeds.iter().map(SeedEntry::summary).collect())
+}
+
+/// Derived address of `count` consecutive accounts, so the user can pick which to import
+#[command]
+async fn derive_seed_phrase_addresses(
+    seed_phrase: String,
+    path_template: Option<PathTemplate>,
+    start_index: Option<u32>,
+    count: Option<u32>,
+    passphrase: Option<String>,
+    language: Option<MnemonicLanguage>
+) -> Result<Vec<DerivedAddress>, String> {
+    let seed = mnemonic::to_seed(&seed_phrase, passphrase.as_deref(), language)?;
+
+    let template = path_template.unwrap_or_default();
+    let start = start_index.unwrap_or(0);
+    let count = count.unwrap_or(10).min(100);
+
+    (start..start.saturating_add(count))
+        .map(|account| {
+            let keypair = template.derive_keypair(&seed[..], account)?;
+            Ok(DerivedAddress {
+                account_index: account,
+                derivation_path: template.path(account)?.map(|path| path.to_string()),
+                public_key: keypair.pubkey().to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, derivation::DerivationError>>()
+        .map_err(String::from)
+}
+
+/// Find the used accounts of a seed phrase under common path schemes
+///
+/// `rpc_url` overrides the network's default endpoint, e.g. a local test validator.
+#[command]
+async fn discover_seed_phrase_accounts(
+    seed_phrase: String,
+    network: String,
+    rpc_url: Option<String>,
+    config: Option<DiscoveryConfig>,
+    passphrase: Option<String>,
+    language: Option<MnemonicLanguage>
+) -> Result<Vec<DiscoveredAccount>, String> {
+    let seed = mnemonic::to_seed(&seed_phrase, passphrase.as_deref(), language)?;
+
+    let rpc_url = match rpc_url {
+        Some(url) => {
+            let parsed = reqwest::Url::parse(&url)
+                .map_err(|e| WalletError::InvalidInput(format!("Invalid RPC URL: {}", e)))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(WalletError::InvalidInput("RPC URL must use http or https".to_string()).to_string());
+            }
+            url
+        }
+        None => match network.as_str() {
+            "mainnet" => Network::Mainnet,
+            "devnet" => Network::Devnet,
+            "testnet" => Network::Testnet,
+            _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
+        }.rpc_url().to_string(),
+    };
+    let config = config.unwrap_or_default();
+
+    // Many sequential RPC round trips; keep them off the async runtime
+    let found = tokio::task::spawn_blocking(move || {
+        let rpc_client = http_client::rpc_client(rpc_url);
+        discover_accounts(&seed[..], &config, |pubkey| probe_account(&rpc_client, pubkey))
     })
+    .await
+    .map_err(|e| WalletError::Network(e.to_string()))??;
+
+    Ok(found)
 }
 
 #[command]
-async fn validate_seed_phrase(seed_phrase: String) -> Result<bool, String> {
-    match Mnemonic::parse_in_normalized(Language::English, &seed_phrase) {
+async fn validate_seed_phrase(
+    seed_phrase: String,
+    language: Option<MnemonicLanguage>
+) -> Result<bool, String> {
+    match mnemonic::parse(&seed_phrase, language) {
         Ok(_) => Ok(true),
         Err(_) => Ok(false),
     }
 }
 
+/// Unknown words with spelling suggestions, plus checksum-passing repairs when one word is wrong or missing
+#[command]
+async fn diagnose_seed_phrase(
+    seed_phrase: String,
+    language: Option<MnemonicLanguage>
+) -> Result<PhraseDiagnosis, String> {
+    // Enumerating repairs runs tens of thousands of checksums; keep it off the async runtime
+    tokio::task::spawn_blocking(move || mnemonic::diagnose(&seed_phrase, language))
+        .await
+        .map_err(|e| WalletError::InvalidInput(e.to_string()).to_string())
+}
+
 #[command]
 async fn get_network_status(network: String) -> Result<serde_json::Value, String> {
-    let client = Client::new();
+    let client = http_client::client();
     let url = match network.as_str() {
         "mainnet" => "https://api.mainnet-beta.solana.com",
         "devnet" => "https://api.devnet.solana.com",
@@ -876,52 +2516,123 @@ async fn get_network_status(network: String) -> Result<serde_json::Value, String
     Ok(status)
 }
 
+/// Decrypt a keyed wallet of the unlocked vault, re-checking the vault password
+async fn reauthenticated_keypair(
+    wallet_id: &str,
+    password: &str,
+    state: &tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Keypair, WalletError> {
+    let wallet = state.lock().await.session()?.resolve(wallet_id)?.clone();
+    wallet.ensure_can_sign()?;
+
+    // Exporting requires the password again, even with the vault unlocked
+    let private_key = wallet.open_secret(password)?;
+
+    keypair_from_secret(&private_key).map_err(|e| WalletError::Encryption(e.to_string()))
+}
+
 #[command]
 async fn export_wallet_private_key(
-    public_key: String,
-    wallet: Wallet,
-    password: String
+    wallet_id: String,
+    password: String,
+    format: Option<SecretKeyFormat>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<String, String> {
-    // Decrypt the private key using stored salt
-    let encryption_key = derive_encryption_key(&password, &wallet.salt)?;
-    let private_key = decrypt_data(&encryption_key, &wallet.encrypted_private_key)?;
-
-    // Convert to hex string
-    let hex_string = hex::encode(&private_key);
-    Ok(hex_string)
+    let keypair = reauthenticated_keypair(&wallet_id, &password, &state).await?;
+    let encoded = encode_secret_key(&keypair, format.unwrap_or(SecretKeyFormat::Hex));
+    Ok(encoded.to_string())
 }
 
+/// Write a wallet as a `solana-keygen` keypair file, returning the path written
 #[command]
-async fn import_wallet_from_private_key(
-    private_key_hex: String,
+async fn export_wallet_keypair_file(
+    wallet_id: String,
     password: String,
-    network: String
+    path: String,
+    overwrite: Option<bool>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<String, String> {
+    let keypair = reauthenticated_keypair(&wallet_id, &password, &state).await?;
+    let path = PathBuf::from(path);
+    write_keypair_to_file(&keypair, &path, overwrite.unwrap_or(false))?;
+
+    log::info!("Exported keypair file for {} to {}", keypair.pubkey(), path.display());
+    Ok(path.display().to_string())
+}
+
+#[command]
+async fn detect_private_key_format(private_key: String) -> Result<SecretKeyFormat, String> {
+    Ok(detect_format(&private_key))
+}
+
+#[command]
+async fn update_wallet_metadata(
+    wallet_id: String,
+    metadata: WalletMetadata,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<Wallet, String> {
-    // Decode hex private key
-    let private_key = hex::decode(&private_key_hex)
-        .map_err(|_| WalletError::InvalidInput("Invalid hex private key".to_string()))?;
+    let metadata = metadata.normalized()?;
 
-    // Create keypair from private key bytes
-    let keypair = Keypair::from_bytes(&private_key)
-        .map_err(|e| WalletError::Bip39(e.to_string()))?;
+    let mut app_state = state.lock().await;
+    let updated = app_state.update_session_wallets(|wallets, _, _| {
+        let wallet = wallets.wallets.iter_mut()
+            .find(|wallet| wallet.id == wallet_id || wallet.public_key == wallet_id)
+            .ok_or_else(|| WalletError::WalletNotFound(wallet_id.clone()))?;
 
-    let public_key = keypair.pubkey().to_string();
+        wallet.metadata = metadata;
+        wallet.last_updated = Utc::now();
+        Ok(wallet.clone())
+    })?;
+    Ok(updated)
+}
 
-    // Encrypt private key
-    let salt = SaltString::generate(&mut OsRng);
-    let encryption_key = derive_encryption_key(&password, salt.as_str().as_bytes())?;
-    let encrypted_private_key = encrypt_data(&encryption_key, &private_key)?;
+/// Wallets of the unlocked vault carrying `tag`, in sort order
+#[command]
+async fn get_wallets_by_tag(
+    tag: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Vec<Wallet>, String> {
+    let tag = normalize_tag(&tag)?;
 
-    let now = Utc::now();
-    Ok(Wallet {
-        public_key,
-        encrypted_private_key,
-        salt: salt.as_str().as_bytes().to_vec(),
-        balance: 0,
-        created_at: now,
-        last_updated: now,
-        network,
-    })
+    let app_state = state.lock().await;
+    let mut wallets: Vec<Wallet> = app_state.session()?.wallets.wallets.iter()
+        .filter(|wallet| wallet.metadata.has_tag(&tag))
+        .cloned()
+        .collect();
+    wallets.sort_by_key(|wallet| (wallet.metadata.sort_order, wallet.created_at));
+    Ok(wallets)
+}
+
+#[command]
+async fn add_watch_only_wallet(
+    public_key: String,
+    network: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallet, String> {
+    let public_key = Pubkey::from_str(public_key.trim())
+        .map_err(|_| WalletError::InvalidInput("Invalid public key".to_string()))?
+        .to_string();
+
+    let wallet = Wallet::new(public_key, network);
+    let mut app_state = state.lock().await;
+    Ok(app_state.add_session_wallet(wallet, None)?)
+}
+
+#[command]
+async fn import_wallet_from_private_key(
+    private_key: String,
+    format: Option<SecretKeyFormat>,
+    network: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<Wallet, String> {
+    // Accepts hex, base58 or solana-keygen JSON, detecting which unless told
+    let keypair = parse_secret_key(&private_key, format)?;
+    let public_key = keypair.pubkey().to_string();
+    let private_key = Zeroizing::new(keypair.to_bytes().to_vec());
+
+    let wallet = Wallet::new(public_key, network);
+    let mut app_state = state.lock().await;
+    Ok(app_state.add_session_wallet(wallet, Some(&private_key))?)
 }
 
 #[command]
@@ -935,7 +2646,7 @@ async fn get_staking_accounts(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let wallet_pubkey = validate_public_key(&public_key)?;
 
     let stake_accounts = rpc_client.get_program_accounts(&stake::program::id())
@@ -980,36 +2691,16 @@ async fn get_staking_accounts(
 
 #[command]
 async fn delegate_stake(
-    wallet: Wallet,
-    password: String,
+    wallet_id: String,
     validator: String,
     amount: u64,
-    network: String
-) -> Result<String, String> {
-    validate_amount(amount)?;
-#[command]
-async fn launch_snipe_bundle(
-    dev_wallet: Wallet,
-    password: String,
-    metadata: PumpfunTokenMetadata,
     network: String,
-    config: Option<LaunchSnipeConfig>,
     state: tauri::State<'_, Arc<Mutex<AppState>>>
-) -> Result<LaunchSnipeResponse, String> {
-    let app_state = state.lock().await;
-    let pumpfun_interface = &app_state.pumpfun_interface;
+) -> Result<String, String> {
+    validate_amount(amount)?;
 
-    match pumpfun_interface.launch_snipe_bundle(
-        dev_wallet,
-        password,
-        metadata,
-        network,
-        config
-    ).await {
-        Ok(response) => Ok(response),
-        Err(e) => Err(e.to_string())
-    }
-}
+    let mut app_state = state.lock().await;
+    let keypair = app_state.session()?.keypair(&wallet_id)?;
 
     let network_enum = match network.as_str() {
         "mainnet" => Network::Mainnet,
@@ -1017,17 +2708,9 @@ async fn launch_snipe_bundle(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
-
-    // Decrypt private key
-    let encryption_key = derive_encryption_key(&password, &wallet.salt)?;
-    let private_key_bytes = decrypt_data(&encryption_key, &wallet.encrypted_private_key)?;
-    let keypair = Keypair::from_bytes(&private_key_bytes)
-        .map_err(|e| format!("Invalid private key: {}", e))?;
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
 
     let validator_pubkey = validate_public_key(&validator)?;
-    let recent_blockhash = rpc_client.get_latest_blockhash()
-        .map_err(|e| WalletError::Solana(e.to_string()).to_string())?;
 
     // Create stake account
     let stake_account = Keypair::new();
@@ -1036,6 +2719,12 @@ async fn launch_snipe_bundle(
 
     let total_amount = amount + rent;
 
+    // The rent-exempt reserve leaves the wallet too; sent with a preflight simulation
+    let spends = [Spend::sol(&keypair.pubkey().to_string(), total_amount, None)];
+    app_state.authorize_spends("delegate_stake", &spends, true)?;
+    let recent_blockhash = rpc_client.get_latest_blockhash()
+        .map_err(|e| WalletError::Solana(e.to_string()).to_string())?;
+
     let transfer_ix = system_instruction::transfer(&keypair.pubkey(), &stake_account.pubkey(), total_amount);
     let create_stake_ixs = stake_instruction::create_account(
         &keypair.pubkey(),
@@ -1058,32 +2747,67 @@ async fn launch_snipe_bundle(
 
     let signature = rpc_client.send_and_confirm_transaction(&transaction)
         .map_err(|e| WalletError::Solana(e.to_string()).to_string())?;
+    app_state.record_spends("delegate_stake", &spends);
 
     Ok(signature.to_string())
 }
 
+#[command]
+async fn launch_snipe_bundle(
+    dev_wallet_id: String,
+    metadata: PumpfunTokenMetadata,
+    network: String,
+    config: Option<LaunchSnipeConfig>,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
+) -> Result<LaunchSnipeResponse, String> {
+    let mut app_state = state.lock().await;
+    let dev_keypair = app_state.session()?.keypair(&dev_wallet_id)?;
+    let pumpfun_interface = app_state.pumpfun_interface.clone();
+
+    // Liquidity plus the largest snipe the launch may buy
+    let max_buy_sol = config.as_ref().unwrap_or(pumpfun_interface.launch_snipe_config()).max_buy_amount_sol;
+    let lamports = sol_to_lamports(metadata.initial_liquidity_sol)?
+        .checked_add(sol_to_lamports(max_buy_sol)?)
+        .ok_or_else(|| WalletError::InvalidInput("Launch amount overflows".to_string()))?;
+    let spends = [Spend::sol(&dev_keypair.pubkey().to_string(), lamports, None)];
+    app_state.authorize_spends("launch_snipe_bundle", &spends, pumpfun_interface.simulates_before_submit())?;
+
+    match pumpfun_interface.launch_snipe_bundle(
+        dev_keypair,
+        metadata,
+        network,
+        config
+    ).await {
+        Ok(response) => {
+            app_state.record_spends("launch_snipe_bundle", &spends);
+            Ok(response)
+        }
+        Err(e) => Err(e.to_string())
+    }
+}
+
 #[command]
 async fn deactivate_stake(
-    wallet: Wallet,
-    password: String,
+    wallet_id: String,
     stake_account_address: String,
-    network: String
+    network: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<String, String> {
+    let mut app_state = state.lock().await;
+    let keypair = app_state.session()?.keypair(&wallet_id)?;
+
     let network_enum = match network.as_str() {
         "mainnet" => Network::Mainnet,
         "devnet" => Network::Devnet,
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
-
-    // Decrypt private key
-    let encryption_key = derive_encryption_key(&password, &wallet.salt)?;
-    let private_key_bytes = decrypt_data(&encryption_key, &wallet.encrypted_private_key)?;
-    let keypair = Keypair::from_bytes(&private_key_bytes)
-        .map_err(|e| format!("Invalid private key: {}", e))?;
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
 
     let stake_account_pubkey = validate_public_key(&stake_account_address)?;
+
+    // Moves no funds, but still needs approval where that is required
+    app_state.authorize_spends("deactivate_stake", &[Spend::sol(&keypair.pubkey().to_string(), 0, None)], true)?;
     let recent_blockhash = rpc_client.get_latest_blockhash()
         .map_err(|e| WalletError::Solana(e.to_string()).to_string())?;
 
@@ -1115,7 +2839,7 @@ async fn get_staking_rewards(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let wallet_pubkey = validate_public_key(&public_key)?;
 
     let epochs = epochs.unwrap_or(30);
@@ -1153,7 +2877,7 @@ async fn get_nfts_for_wallet(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let wallet_pubkey = validate_public_key(&public_key)?;
 
     let mut nfts = Vec::new();
@@ -1187,7 +2911,7 @@ async fn get_nfts_for_wallet(
 
                 if let Ok(metadata_account) = rpc_client.get_account(&metadata_pda) {
                     if let Ok(metadata) = Metadata::deserialize(&mut metadata_account.data.as_slice()) {
-                        let client = Client::new();
+                        let client = http_client::public_client();
                         if let Ok(response) = client.get(&metadata.uri).send().await {
                             if let Ok(metadata_json) = response.json::<serde_json::Value>().await {
                                 let nft = NFT {
@@ -1236,7 +2960,7 @@ async fn get_nft_metadata(mint_address: String, network: String) -> Result<NFT,
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let mint_pubkey = validate_public_key(&mint_address)?;
 
     let (metadata_pda, _) = SolanaPubkey::find_program_address(
@@ -1250,7 +2974,7 @@ async fn get_nft_metadata(mint_address: String, network: String) -> Result<NFT,
     let metadata = Metadata::deserialize(&mut metadata_account.data.as_slice())
         .map_err(|e| format!("Failed to deserialize metadata: {}", e))?;
 
-    let client = Client::new();
+    let client = http_client::public_client();
     let response = client.get(&metadata.uri).send().await
         .map_err(|e| format!("Failed to fetch metadata: {}", e))?;
 
@@ -1300,7 +3024,7 @@ async fn get_token_accounts(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
     let wallet_pubkey = validate_public_key(&public_key)?;
 
     let token_accounts_filter = TokenAccountsFilter::ProgramId(spl_token::id());
@@ -1346,7 +3070,7 @@ async fn get_token_accounts(
 
 #[command]
 async fn get_token_list(network: String) -> Result<TokenList, String> {
-    let client = Client::new();
+    let client = http_client::client();
     let url = "https://token.jup.ag/strict";
 
     let response = client.get(url).send().await
@@ -1380,16 +3104,16 @@ async fn get_token_list(network: String) -> Result<TokenList, String> {
 
 #[command]
 async fn sign_message(
-    wallet: Wallet,
-    password: String,
+    wallet_id: String,
     message: String,
-    network: String
+    network: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<String, String> {
-    // Decrypt private key
-    let encryption_key = derive_encryption_key(&password, &wallet.salt)?;
-    let private_key_bytes = decrypt_data(&encryption_key, &wallet.encrypted_private_key)?;
-    let keypair = Keypair::from_bytes(&private_key_bytes)
-        .map_err(|e| format!("Invalid private key: {}", e))?;
+    let mut app_state = state.lock().await;
+    let keypair = app_state.session()?.keypair(&wallet_id)?;
+
+    // A message is never submitted, so there is nothing to simulate
+    app_state.authorize_spends("sign_message", &[Spend::sol(&keypair.pubkey().to_string(), 0, None)], true)?;
 
     // Sign the message
     let signature = keypair.sign_message(message.as_bytes());
@@ -1416,14 +3140,16 @@ async fn verify_signature(
 
 #[command]
 async fn simulate_transaction(
-    wallet: Wallet,
-    password: String,
+    wallet_id: String,
     recipient: String,
     amount: u64,
-    network: String
+    network: String,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>
 ) -> Result<serde_json::Value, String> {
     validate_amount(amount)?;
 
+    let keypair = state.lock().await.session()?.keypair(&wallet_id)?;
+
     let network_enum = match network.as_str() {
         "mainnet" => Network::Mainnet,
         "devnet" => Network::Devnet,
@@ -1431,13 +3157,7 @@ async fn simulate_transaction(
         _ => return Err(WalletError::InvalidInput("Invalid network".to_string()).to_string()),
     };
 
-    let rpc_client = RpcClient::new(network_enum.rpc_url().to_string());
-
-    // Decrypt private key
-    let encryption_key = derive_encryption_key(&password, &wallet.salt)?;
-    let private_key_bytes = decrypt_data(&encryption_key, &wallet.encrypted_private_key)?;
-    let keypair = Keypair::from_bytes(&private_key_bytes)
-        .map_err(|e| format!("Invalid private key: {}", e))?;
+    let rpc_client = http_client::rpc_client(network_enum.rpc_url().to_string());
 
     let to_pubkey = validate_public_key(&recipient)?;
     let recent_blockhash = rpc_client.get_latest_blockhash()
@@ -1468,7 +3188,7 @@ async fn simulate_transaction(
 
 #[command]
 async fn check_for_updates() -> Result<serde_json::Value, String> {
-    let client = Client::new();
+    let client = http_client::client();
 
     let url = "https://api.github.com/repos/lesinski-tools/lesinki-wallet/releases/latest";
 
@@ -1509,7 +3229,7 @@ async fn check_for_updates() -> Result<serde_json::Value, String> {
 
 #[command]
 async fn download_update(download_url: String) -> Result<(), String> {
-    let client = Client::new();
+    let client = http_client::client();
 
     let response = client
         .get(&download_url)
@@ -1534,7 +3254,7 @@ async fn download_update(download_url: String) -> Result<(), String> {
 
 #[command]
 async fn get_token_price(token_address: String) -> Result<f64, String> {
-    let client = Client::new();
+    let client = http_client::client();
     let url = format!("https://api.jup.ag/price/v2?ids={}", token_address);
 
     let response = client.get(&url)
@@ -1574,23 +3294,64 @@ async fn get_performance_metrics() -> Result<PerformanceMetrics, String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let app_state = Arc::new(Mutex::new(AppState::new()));
-
     tauri::Builder::default()
-        .manage(app_state)
         .invoke_handler(tauri::generate_handler![
             generate_wallet,
             get_balance,
-            save_wallets,
             load_wallets,
+            unlock_vault,
+            lock_vault,
+            change_password,
+            set_vault_backup_count,
+            calibrate_vault_kdf,
+            rotate_vault_key,
+            set_key_rotation_interval,
+            get_auto_lock_policy,
+            export_backup,
+            import_backup,
+            set_auto_lock_policy,
+            get_transaction_policy,
+            set_transaction_policy,
+            get_spend_volume,
+            list_pending_approvals,
+            approve_transaction,
+            reject_transaction,
+            get_address_lists,
+            add_address_list_entry,
+            remove_address_list_entry,
+            import_address_list,
+            set_allowlist_only,
+            verify_audit_log,
+            query_audit_log,
+            list_profiles,
+            create_profile,
+            switch_profile,
+            rename_profile,
+            delete_profile,
             transfer_tokens,
             get_transaction_history,
             generate_seed_phrase,
+            generate_vanity_wallet,
+            cancel_vanity_search,
             import_wallet_from_seed_phrase,
+            derive_seed_phrase_addresses,
+            discover_seed_phrase_accounts,
             validate_seed_phrase,
+            split_seed_phrase,
+            combine_seed_shares,
+            inspect_seed_share,
+            diagnose_seed_phrase,
+            add_seed_phrase,
+            derive_next_account,
+            list_seeds,
             get_network_status,
             export_wallet_private_key,
+            export_wallet_keypair_file,
+            detect_private_key_format,
             import_wallet_from_private_key,
+            add_watch_only_wallet,
+            update_wallet_metadata,
+            get_wallets_by_tag,
             send_bundle_transaction,
             get_jupiter_quote,
             execute_jupiter_swap,
@@ -1610,7 +3371,8 @@ pub fn run() {
             get_token_price,
             get_performance_metrics,
             create_pump_fun_token,
-            execute_bundle_buy
+            execute_bundle_buy,
+            launch_snipe_bundle
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -1620,8 +3382,20 @@ pub fn run() {
                         .build(),
                 )?;
             }
+
+            // Resolve every persisted file under the app data directory
+            let data_dir = app.path().app_data_dir()?;
+            let app_state = AppState::new(data_dir)?;
+            app.manage(Arc::new(Mutex::new(app_state)));
+
+            tauri::async_runtime::spawn(run_auto_lock(app.handle().clone()));
+            tauri::async_runtime::spawn(run_key_rotation(app.handle().clone()));
+            tauri::async_runtime::spawn(run_audit_log(app.handle().clone()));
+
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+include!("lib_tests.rs");
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn keyed_wallet(id: &str, keypair: &Keypair) -> Wallet {
+        let now = Utc::now();
+        Wallet {
+            id: id.to_string(),
+            public_key: keypair.pubkey().to_string(),
+            kind: WalletKind::Keyed,
+            encrypted_private_key: vec![],
+            salt: vec![],
+            kdf: None,
+            balance: 0,
+            created_at: now,
+            last_updated: now,
+            network: "mainnet".to_string(),
+            metadata: Default::default(),
+            derivation_path: None,
+            seed_id: None,
+            secret_version: 0,
+        }
+    }
+
+    #[test]
+    fn test_wallet_keys_are_bound_to_their_wallet() {
+        let (alice_key, bob_key) = (Keypair::new(), Keypair::new());
+        let mut alice = keyed_wallet("alice", &alice_key);
+        let mut bob = keyed_wallet("bob", &bob_key);
+        alice.seal_secret(&alice_key.to_bytes(), "test_password", &SecretKdf::legacy()).unwrap();
+        bob.seal_secret(&bob_key.to_bytes(), "test_password", &SecretKdf::legacy()).unwrap();
+        assert_eq!(alice.secret_version, WALLET_SECRET_VERSION);
+        assert_eq!(&alice.open_secret("test_password").unwrap()[..], &alice_key.to_bytes()[..]);
+
+        // Bob's ciphertext moved onto Alice's entry
+        let swapped = Wallet {
+            encrypted_private_key: bob.encrypted_private_key.clone(),
+            salt: bob.salt.clone(),
+            ..alice.clone()
+        };
+        assert!(matches!(swapped.open_secret("test_password"), Err(WalletError::AuthFailed)));
+
+        // Claiming the key predates binding does not help either
+        let downgraded = Wallet { secret_version: 0, ..alice.clone() };
+        assert!(downgraded.open_secret("test_password").is_err());
+    }
+
+    #[test]
+    fn test_unbound_keys_open_only_for_their_address() {
+        let (alice_key, bob_key) = (Keypair::new(), Keypair::new());
+        let salt = b"legacy-salt-0123".to_vec();
+        let encryption_key = derive_encryption_key("test_password", &salt, &SecretKdf::legacy()).unwrap();
+        let legacy = encrypt_data(&encryption_key, &alice_key.to_bytes()).unwrap();
+
+        let alice = Wallet { encrypted_private_key: legacy.clone(), salt: salt.clone(), ..keyed_wallet("alice", &alice_key) };
+        assert!(alice.needs_secret_binding());
+        assert_eq!(&alice.open_secret("test_password").unwrap()[..], &alice_key.to_bytes()[..]);
+
+        let bob = Wallet { encrypted_private_key: legacy, salt, ..keyed_wallet("bob", &bob_key) };
+        assert!(bob.open_secret("test_password").is_err());
+    }
+
+    #[test]
+    fn test_bundle_buys_count_against_sol_limits() {
+        let now = Utc::now();
+        let policy = TransactionPolicy {
+            security: security::TransactionSecurity { daily_limit: 3_000, simulation_required: false, ..Default::default() },
+            rules: vec![],
+        };
+        let wallets = vec![Keypair::new().pubkey().to_string(), Keypair::new().pubkey().to_string()];
+        let mut ledger = policy::SpendLedger::default();
+
+        let spends = bundle_buy_spends(&wallets, 2_000);
+        assert!(spends.iter().all(|spend| spend.asset == NATIVE_ASSET && spend.recipient.is_none()));
+        policy.evaluate(&ledger, &spends, false, now).unwrap();
+        ledger.record("execute_bundle_buy", &spends, now);
+        assert_eq!(ledger.volume(&wallets[0], NATIVE_ASSET, now), 2_000);
+
+        let err = policy.evaluate(&ledger, &bundle_buy_spends(&wallets, 1_001), false, now).unwrap_err();
+        assert!(err.to_string().starts_with("Blocked by policy 'daily_limit':"), "{}", err);
+    }
+
+    #[test]
+    fn test_backups_are_resealed_under_the_new_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VaultConfig {
+            kdf_params: Some(keystore::KdfParams { memory_cost: 256, time_cost: 1, ..keystore::KdfParams::legacy() }),
+            ..VaultConfig::default()
+        };
+        let storage = VaultStorage::new(dir.path().join("wallets.enc"), &config);
+        storage.write(b"garbage").unwrap();
+        for version in ["1", "2", "3"] {
+            write_vault(&storage, &Wallets { version: version.to_string(), ..Wallets::empty() }, "old password").unwrap();
+        }
+
+        let backups = rewrap_backups(&storage, "old password", "new password", &SecretKdf::current()).unwrap();
+        let versions: Vec<String> = backups.iter()
+            .map(|backup| {
+                assert!(keystore::open("old password", backup).is_err());
+                let opened = keystore::open("new password", backup).unwrap();
+                serde_json::from_slice::<Wallets>(&opened.plaintext).unwrap().version
+            })
+            .collect();
+        // The corrupt backup is dropped
+        assert_eq!(versions, vec!["2".to_string(), "1".to_string()]);
+    }
+
+    #[test]
+    fn test_sol_amounts_convert_to_lamports_or_fail() {
+        assert_eq!(sol_to_lamports(1.5).unwrap(), 1_500_000_000);
+        assert_eq!(sol_to_lamports(0.0).unwrap(), 0);
+        for invalid in [-0.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e12] {
+            assert!(matches!(sol_to_lamports(invalid), Err(WalletError::InvalidInput(_))), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Longest accepted display name, in characters
+const MAX_NAME_LEN: usize = 64;
+
+/// Longest accepted note, in characters
+const MAX_NOTE_LEN: usize = 2048;
+
+/// Longest accepted tag, in characters
+const MAX_TAG_LEN: usize = 32;
+
+/// Most tags a single wallet may carry
+const MAX_TAGS: usize = 16;
+
+/// Metadata validation errors
+#[derive(Error, Debug)]
+pub enum MetadataError {
+    #[error("Wallet name must be at most 64 characters")]
+    NameTooLong,
+    #[error("Wallet note must be at most 2048 characters")]
+    NoteTooLong,
+    #[error("Invalid tag '{0}': use 1-32 letters, digits, '-', '_' or '.'")]
+    InvalidTag(String),
+    #[error("A wallet can have at most 16 tags")]
+    TooManyTags,
+    #[error("Invalid color '{0}': use #RRGGBB")]
+    InvalidColor(String),
+}
+
+impl From<MetadataError> for String {
+    fn from(err: MetadataError) -> String {
+        err.to_string()
+    }
+}
+
+/// User-facing metadata stored with each wallet in the vault
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct WalletMetadata {
+    /// Display name
+    pub name: String,
+    /// Free-text note
+    pub note: String,
+    /// Lowercase tags, unique per wallet
+    pub tags: Vec<String>,
+    /// `#rrggbb` color, if one was picked
+    pub color: Option<String>,
+    /// Position in wallet lists, ascending
+    pub sort_order: i64,
+}
+
+impl WalletMetadata {
+    /// Validate and normalize user input: trims text, lowercases and dedupes tags, lowercases the color
+    pub fn normalized(self) -> Result<Self, MetadataError> {
+        let name = self.name.trim().to_string();
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(MetadataError::NameTooLong);
+        }
+
+        let note = self.note.trim().to_string();
+        if note.chars().count() > MAX_NOTE_LEN {
+            return Err(MetadataError::NoteTooLong);
+        }
+
+        let mut tags: Vec<String> = Vec::with_capacity(self.tags.len());
+        for tag in &self.tags {
+            let tag = normalize_tag(tag)?;
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(MetadataError::TooManyTags);
+        }
+
+        let color = match self.color.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            Some(color) => Some(normalize_color(color)?),
+            None => None,
+        };
+
+        Ok(Self {
+            name,
+            note,
+            tags,
+            color,
+            sort_order: self.sort_order,
+        })
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+        self.tags.iter().any(|t| *t == tag)
+    }
+}
+
+/// Trim and lowercase a tag, rejecting empty, overlong or oddly-charactered ones
+pub fn normalize_tag(tag: &str) -> Result<String, MetadataError> {
+    let normalized = tag.trim().to_lowercase();
+    let valid = !normalized.is_empty()
+        && normalized.chars().count() <= MAX_TAG_LEN
+        && normalized.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if valid {
+        Ok(normalized)
+    } else {
+        Err(MetadataError::InvalidTag(tag.to_string()))
+    }
+}
+
+fn normalize_color(color: &str) -> Result<String, MetadataError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+    if valid {
+        Ok(color.to_lowercase())
+    } else {
+        Err(MetadataError::InvalidColor(color.to_string()))
+    }
+}
+
+include!("metadata_tests.rs");
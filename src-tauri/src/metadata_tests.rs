@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_normalizes_user_input() {
+        let metadata = WalletMetadata {
+            name: "  Bundle 07 ".to_string(),
+            note: "funded from treasury\n".to_string(),
+            tags: vec!["Bundle".to_string(), " launch-3 ".to_string(), "bundle".to_string()],
+            color: Some("#FFA500".to_string()),
+            sort_order: 7,
+        };
+
+        let normalized = metadata.normalized().unwrap();
+        assert_eq!(normalized.name, "Bundle 07");
+        assert_eq!(normalized.note, "funded from treasury");
+        assert_eq!(normalized.tags, vec!["bundle".to_string(), "launch-3".to_string()]);
+        assert_eq!(normalized.color.as_deref(), Some("#ffa500"));
+        assert!(normalized.has_tag("BUNDLE"));
+        assert!(!normalized.has_tag("treasury"));
+    }
+
+    #[test]
+    fn test_empty_color_clears_it() {
+        let metadata = WalletMetadata {
+            color: Some(" ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(metadata.normalized().unwrap().color, None);
+    }
+
+    #[test]
+    fn test_rejects_invalid_metadata() {
+        let bad_tag = WalletMetadata {
+            tags: vec!["two words".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(bad_tag.normalized(), Err(MetadataError::InvalidTag(_))));
+
+        let bad_color = WalletMetadata {
+            color: Some("orange".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(bad_color.normalized(), Err(MetadataError::InvalidColor(_))));
+
+        let long_name = WalletMetadata {
+            name: "x".repeat(MAX_NAME_LEN + 1),
+            ..Default::default()
+        };
+        assert!(matches!(long_name.normalized(), Err(MetadataError::NameTooLong)));
+    }
+
+    #[test]
+    fn test_missing_metadata_deserializes_to_default() {
+        let metadata: WalletMetadata = serde_json::from_str(r#"{"name":"cold"}"#).unwrap();
+        assert_eq!(metadata.name, "cold");
+        assert!(metadata.tags.is_empty());
+        assert_eq!(metadata.sort_order, 0);
+    }
+}
@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use bip39::{Language, Mnemonic};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Seed phrase errors
+#[derive(Error, Debug)]
+pub enum MnemonicError {
+    #[error("Unsupported word count {0}: use 12, 15, 18, 21 or 24")]
+    WordCount(usize),
+    #[error("Invalid seed phrase: {0}")]
+    Invalid(String),
+}
+
+impl From<MnemonicError> for String {
+    fn from(err: MnemonicError) -> String {
+        err.to_string()
+    }
+}
+
+/// Most checksum-passing corrections `diagnose` returns
+pub const MAX_CORRECTIONS: usize = 256;
+
+/// Spelling suggestions per unknown word, and how far off they may be
+const MAX_SUGGESTIONS: usize = 5;
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// BIP39 wordlists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MnemonicLanguage {
+    #[default]
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    Czech,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Spanish,
+}
+
+impl MnemonicLanguage {
+    pub const ALL: [MnemonicLanguage; 9] = [
+        MnemonicLanguage::English,
+        MnemonicLanguage::ChineseSimplified,
+        MnemonicLanguage::ChineseTraditional,
+        MnemonicLanguage::Czech,
+        MnemonicLanguage::French,
+        MnemonicLanguage::Italian,
+        MnemonicLanguage::Japanese,
+        MnemonicLanguage::Korean,
+        MnemonicLanguage::Spanish,
+    ];
+
+    pub fn word_list(self) -> &'static [&'static str; 2048] {
+        Language::from(self).word_list()
+    }
+
+    /// Wordlist index of `word`
+    pub fn find_word(self, word: &str) -> Option<u16> {
+        self.word_list().iter().position(|candidate| *candidate == word).map(|index| index as u16)
+    }
+}
+
+impl From<MnemonicLanguage> for Language {
+    fn from(language: MnemonicLanguage) -> Language {
+        match language {
+            MnemonicLanguage::English => Language::English,
+            MnemonicLanguage::ChineseSimplified => Language::SimplifiedChinese,
+            MnemonicLanguage::ChineseTraditional => Language::TraditionalChinese,
+            MnemonicLanguage::Czech => Language::Czech,
+            MnemonicLanguage::French => Language::French,
+            MnemonicLanguage::Italian => Language::Italian,
+            MnemonicLanguage::Japanese => Language::Japanese,
+            MnemonicLanguage::Korean => Language::Korean,
+            MnemonicLanguage::Spanish => Language::Spanish,
+        }
+    }
+}
+
+impl From<Language> for MnemonicLanguage {
+    fn from(language: Language) -> MnemonicLanguage {
+        match language {
+            Language::English => MnemonicLanguage::English,
+            Language::SimplifiedChinese => MnemonicLanguage::ChineseSimplified,
+            Language::TraditionalChinese => MnemonicLanguage::ChineseTraditional,
+            Language::Czech => MnemonicLanguage::Czech,
+            Language::French => MnemonicLanguage::French,
+            Language::Italian => MnemonicLanguage::Italian,
+            Language::Japanese => MnemonicLanguage::Japanese,
+            Language::Korean => MnemonicLanguage::Korean,
+            Language::Spanish => MnemonicLanguage::Spanish,
+        }
+    }
+}
+
+/// Entropy length in bytes for a phrase of `word_count` words
+pub fn entropy_len(word_count: usize) -> Result<usize, MnemonicError> {
+    match word_count {
+        12 | 15 | 18 | 21 | 24 => Ok(word_count / 3 * 4),
+        _ => Err(MnemonicError::WordCount(word_count)),
+    }
+}
+
+/// A new random seed phrase
+pub fn generate(word_count: usize, language: MnemonicLanguage) -> Result<Zeroizing<String>, MnemonicError> {
+    let mut entropy = [0u8; 32];
+    let len = entropy_len(word_count)?;
+    OsRng.fill_bytes(&mut entropy[..len]);
+
+    let phrase = from_entropy(&entropy[..len], language);
+    entropy.zeroize();
+    phrase
+}
+
+/// The seed phrase encoding `entropy`
+pub fn from_entropy(entropy: &[u8], language: MnemonicLanguage) -> Result<Zeroizing<String>, MnemonicError> {
+    let mnemonic = Mnemonic::from_entropy_in(language.into(), entropy)
+        .map_err(|e| MnemonicError::Invalid(e.to_string()))?;
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+/// Parse a seed phrase in `language`, or in whichever wordlist it belongs to when `None`
+pub fn parse(phrase: &str, language: Option<MnemonicLanguage>) -> Result<Mnemonic, MnemonicError> {
+    let result = match language {
+        Some(language) => Mnemonic::parse_in(language.into(), phrase),
+        None => Mnemonic::parse(phrase),
+    };
+    result.map_err(|e| MnemonicError::Invalid(e.to_string()))
+}
+
+/// BIP39 seed of a phrase and optional passphrase (the "25th word")
+pub fn to_seed(
+    phrase: &str,
+    passphrase: Option<&str>,
+    language: Option<MnemonicLanguage>,
+) -> Result<Zeroizing<[u8; 64]>, MnemonicError> {
+    let mnemonic = parse(phrase, language)?;
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase.unwrap_or(""))))
+}
+
+/// A word of the phrase that is not in the wordlist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownWord {
+    pub position: usize,
+    pub word: String,
+    /// Closest wordlist words by edit distance, nearest first
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrectionKind {
+    /// The word at `position` was replaced
+    Replaced,
+    /// The word was inserted at `position`, for a phrase missing one word
+    Inserted,
+}
+
+/// A phrase one word away from the input that passes the BIP39 checksum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhraseCorrection {
+    pub kind: CorrectionKind,
+    pub position: usize,
+    pub word: String,
+    pub phrase: String,
+}
+
+/// What is wrong with a seed phrase, and how it might be repaired
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhraseDiagnosis {
+    pub valid: bool,
+    /// The requested language, or the one most of the words belong to
+    pub language: MnemonicLanguage,
+    pub word_count: usize,
+    pub unknown_words: Vec<UnknownWord>,
+    /// Checksum-passing corrections, closest to the typed word first
+    ///
+    /// Only enumerated when exactly one word is wrong or exactly one word is missing.
+    pub corrections: Vec<PhraseCorrection>,
+    /// More than `MAX_CORRECTIONS` corrections pass the checksum
+    pub corrections_truncated: bool,
+}
+
+/// Diagnose a seed phrase that may contain typos or miss a word
+pub fn diagnose(phrase: &str, language: Option<MnemonicLanguage>) -> PhraseDiagnosis {
+    let words: Vec<String> = phrase.split_whitespace().map(|word| word.to_lowercase()).collect();
+    let language = language.unwrap_or_else(|| detect_language(&words));
+    let indices: Vec<Option<u16>> = words.iter().map(|word| language.find_word(word)).collect();
+
+    let unknown_words: Vec<UnknownWord> = words.iter().zip(&indices).enumerate()
+        .filter(|(_, (_, index))| index.is_none())
+        .map(|(position, (word, _))| UnknownWord {
+            position,
+            word: word.clone(),
+            suggestions: suggest_words(word, language),
+        })
+        .collect();
+
+    let known: Vec<u16> = indices.iter().flatten().copied().collect();
+    let count_ok = entropy_len(words.len()).is_ok();
+    let valid = unknown_words.is_empty() && count_ok && checksum_matches(&known);
+
+    let mut corrections = Vec::new();
+    if !valid {
+        let list = language.word_list();
+        let mut candidate = indices.clone();
+
+        // One wrong word: an unknown word, or else any word when the checksum fails
+        let replace_at: Vec<usize> = match unknown_words.len() {
+            0 if count_ok => (0..words.len()).collect(),
+            1 if count_ok => vec![unknown_words[0].position],
+            _ => vec![],
+        };
+        for position in replace_at {
+            for replacement in 0..list.len() as u16 {
+                if indices[position] == Some(replacement) {
+                    continue;
+                }
+                candidate[position] = Some(replacement);
+                if checksum_matches(&candidate.iter().flatten().copied().collect::<Vec<_>>()) {
+                    corrections.push((CorrectionKind::Replaced, position, replacement));
+                }
+            }
+            candidate[position] = indices[position];
+        }
+
+        // One missing word, anywhere in the phrase
+        if unknown_words.is_empty() && entropy_len(words.len() + 1).is_ok() {
+            for position in 0..=known.len() {
+                for inserted in 0..list.len() as u16 {
+                    let mut candidate = known.clone();
+                    candidate.insert(position, inserted);
+                    if checksum_matches(&candidate) {
+                        corrections.push((CorrectionKind::Inserted, position, inserted));
+                    }
+                }
+            }
+        }
+    }
+
+    // Closest to what was typed first; an insertion has nothing to compare against
+    let distance = |kind: CorrectionKind, position: usize, word: u16| match kind {
+        CorrectionKind::Replaced => edit_distance(&words[position], list_word(language, word)),
+        CorrectionKind::Inserted => 0,
+    };
+    corrections.sort_by_key(|&(kind, position, word)| (distance(kind, position, word), position, word));
+    let corrections_truncated = corrections.len() > MAX_CORRECTIONS;
+
+    let corrections = corrections.into_iter()
+        .take(MAX_CORRECTIONS)
+        .map(|(kind, position, word)| {
+            let word = list_word(language, word).to_string();
+            let mut repaired = words.clone();
+            match kind {
+                CorrectionKind::Replaced => repaired[position] = word.clone(),
+                CorrectionKind::Inserted => repaired.insert(position, word.clone()),
+            }
+            PhraseCorrection {
+                kind,
+                position,
+                word,
+                phrase: repaired.join(" "),
+            }
+        })
+        .collect();
+
+    PhraseDiagnosis {
+        valid,
+        language,
+        word_count: words.len(),
+        unknown_words,
+        corrections,
+        corrections_truncated,
+    }
+}
+
+fn list_word(language: MnemonicLanguage, index: u16) -> &'static str {
+    language.word_list()[index as usize]
+}
+
+/// The wordlist containing most of `words`, English on a tie
+fn detect_language(words: &[String]) -> MnemonicLanguage {
+    let mut best = MnemonicLanguage::English;
+    let mut best_count = 0;
+    for language in MnemonicLanguage::ALL {
+        let count = words.iter().filter(|word| language.find_word(word).is_some()).count();
+        if count > best_count {
+            best = language;
+            best_count = count;
+        }
+    }
+    best
+}
+
+/// Wordlist words within `MAX_SUGGESTION_DISTANCE` edits of `word`, nearest first
+fn suggest_words(word: &str, language: MnemonicLanguage) -> Vec<String> {
+    let mut scored: Vec<(usize, &str)> = language.word_list().iter()
+        .map(|candidate| (edit_distance(word, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .collect();
+    scored.sort();
+    scored.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate.to_string()).collect()
+}
+
+/// Levenshtein distance in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Whether wordlist indices form a valid-length phrase whose BIP39 checksum matches
+fn checksum_matches(indices: &[u16]) -> bool {
+    if entropy_len(indices.len()).is_err() {
+        return false;
+    }
+
+    // 11 bits per word: the entropy, then one checksum bit per 32 bits of entropy
+    let mut bits = [0u8; 33];
+    for (i, index) in indices.iter().enumerate() {
+        for bit in 0..11 {
+            if index & (1 << (10 - bit)) != 0 {
+                let position = i * 11 + bit;
+                bits[position / 8] |= 1 << (7 - position % 8);
+            }
+        }
+    }
+
+    let checksum_bits = indices.len() / 3;
+    let entropy_bytes = indices.len() / 3 * 4;
+    let hash = Sha256::digest(&bits[..entropy_bytes]);
+    let shift = 8 - checksum_bits;
+    let matches = hash[0] >> shift == bits[entropy_bytes] >> shift;
+    bits.zeroize();
+    matches
+}
+
+include!("mnemonic_tests.rs");
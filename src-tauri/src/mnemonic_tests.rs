@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ABANDON: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    /// BIP39 reference vector: all-zero 128-bit entropy with passphrase "TREZOR"
+    #[test]
+    fn test_passphrase_seed_vector() {
+        let seed = to_seed(ABANDON, Some("TREZOR"), None).unwrap();
+        assert_eq!(
+            hex::encode(&seed[..]),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+
+        let without = to_seed(ABANDON, None, None).unwrap();
+        assert_ne!(&seed[..], &without[..]);
+    }
+
+    #[test]
+    fn test_word_counts() {
+        for (words, bytes) in [(12, 16), (15, 20), (18, 24), (21, 28), (24, 32)] {
+            assert_eq!(entropy_len(words).unwrap(), bytes);
+            let phrase = generate(words, MnemonicLanguage::English).unwrap();
+            assert_eq!(phrase.split_whitespace().count(), words);
+        }
+        assert!(matches!(entropy_len(13), Err(MnemonicError::WordCount(13))));
+    }
+
+    #[test]
+    fn test_languages_roundtrip() {
+        for language in [
+            MnemonicLanguage::English,
+            MnemonicLanguage::ChineseSimplified,
+            MnemonicLanguage::ChineseTraditional,
+            MnemonicLanguage::Czech,
+            MnemonicLanguage::French,
+            MnemonicLanguage::Italian,
+            MnemonicLanguage::Japanese,
+            MnemonicLanguage::Korean,
+            MnemonicLanguage::Spanish,
+        ] {
+            let phrase = generate(12, language).unwrap();
+            assert_eq!(MnemonicLanguage::from(parse(&phrase, Some(language)).unwrap().language()), language);
+        }
+    }
+
+    #[test]
+    fn test_language_detected() {
+        // The two Chinese wordlists share characters, so only languages with distinct words are detected reliably
+        for language in [MnemonicLanguage::Czech, MnemonicLanguage::Japanese, MnemonicLanguage::Korean] {
+            let phrase = generate(24, language).unwrap();
+            assert_eq!(MnemonicLanguage::from(parse(&phrase, None).unwrap().language()), language);
+        }
+        assert_eq!(MnemonicLanguage::from(parse(ABANDON, None).unwrap().language()), MnemonicLanguage::English);
+    }
+
+    #[test]
+    fn test_wrong_language_rejected() {
+        assert!(parse(ABANDON, Some(MnemonicLanguage::Korean)).is_err());
+    }
+
+    #[test]
+    fn test_checksum_agrees_with_bip39() {
+        for words in [12, 24] {
+            let phrase = generate(words, MnemonicLanguage::English).unwrap();
+            let indices: Vec<u16> = phrase.split_whitespace()
+                .map(|word| MnemonicLanguage::English.find_word(word).unwrap())
+                .collect();
+            assert!(checksum_matches(&indices));
+        }
+
+        // "about" carries the checksum of all-zero entropy
+        let mut indices = vec![0u16; 12];
+        assert!(!checksum_matches(&indices));
+        indices[11] = MnemonicLanguage::English.find_word("about").unwrap();
+        assert!(checksum_matches(&indices));
+    }
+
+    #[test]
+    fn test_valid_phrase_needs_no_repair() {
+        let diagnosis = diagnose(ABANDON, None);
+        assert!(diagnosis.valid);
+        assert_eq!(diagnosis.language, MnemonicLanguage::English);
+        assert!(diagnosis.unknown_words.is_empty());
+        assert!(diagnosis.corrections.is_empty());
+    }
+
+    #[test]
+    fn test_misspelled_word_suggestions_and_repairs() {
+        let typo = ABANDON.replacen("about", "abuot", 1);
+        let diagnosis = diagnose(&typo, None);
+
+        assert!(!diagnosis.valid);
+        assert_eq!(diagnosis.unknown_words.len(), 1);
+        assert_eq!(diagnosis.unknown_words[0].position, 11);
+        assert_eq!(diagnosis.unknown_words[0].suggestions[0], "about");
+
+        // 4 checksum bits: about one word in 16 fits, and the nearest spelling is listed first
+        let first = &diagnosis.corrections[0];
+        assert_eq!((first.kind, first.position, first.word.as_str()), (CorrectionKind::Replaced, 11, "about"));
+        assert_eq!(first.phrase, ABANDON);
+        assert!(diagnosis.corrections.iter().all(|c| parse(&c.phrase, None).is_ok()));
+    }
+
+    #[test]
+    fn test_wrong_but_listed_word_is_repaired() {
+        let wrong = ABANDON.replacen("about", "above", 1);
+        let diagnosis = diagnose(&wrong, None);
+
+        assert!(!diagnosis.valid);
+        assert!(diagnosis.unknown_words.is_empty());
+        assert!(diagnosis.corrections.iter().any(|c| c.phrase == ABANDON));
+    }
+
+    #[test]
+    fn test_missing_word_is_inserted() {
+        let phrase = generate(24, MnemonicLanguage::English).unwrap();
+        let mut words: Vec<&str> = phrase.split_whitespace().collect();
+        words.remove(5);
+        let diagnosis = diagnose(&words.join(" "), Some(MnemonicLanguage::English));
+
+        assert_eq!(diagnosis.word_count, 23);
+        assert!(diagnosis.corrections.iter().all(|c| c.kind == CorrectionKind::Inserted));
+        assert!(diagnosis.corrections.iter().any(|c| c.phrase == phrase.as_str()));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("abandon", "abandon"), 0);
+        assert_eq!(edit_distance("abandn", "abandon"), 1);
+        assert_eq!(edit_distance("abuot", "about"), 2);
+        assert_eq!(edit_distance("", "zoo"), 3);
+    }
+}
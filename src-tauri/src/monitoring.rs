@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
@@ -8,7 +9,6 @@ use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use moka::future::Cache;
 use anyhow::Result;
-use reqwest::Client;
 use thiserror::Error;
 
 /// Monitoring and analytics errors
@@ -463,7 +463,7 @@ impl BlockchainHealthMonitor {
     
     async fn check_blockchain_health(&self) -> Result<BlockchainMonitor, MonitoringError> {
         // In a real implementation, you'd check actual blockchain APIs
-        let client = Client::new();
+        let client = crate::http_client::client();
         
         // Mock blockchain data
         let monitor = BlockchainMonitor {
@@ -489,8 +489,8 @@ impl BlockchainHealthMonitor {
 }
 
 impl MonitoringManager {
-    /// Create new monitoring manager
-    pub fn new(config: AnalyticsConfig) -> Self {
+    /// Create new monitoring manager, persisting its stores under `data_dir`
+    pub fn new(config: AnalyticsConfig, data_dir: &Path) -> Self {
         let (event_sender, event_receiver) = unbounded();
         
         let metrics_cache = Cache::builder()
@@ -519,8 +519,8 @@ impl MonitoringManager {
             event_receiver: Arc::new(RwLock::new(event_receiver)),
             metrics_cache: Arc::new(metrics_cache),
             user_session_cache: Arc::new(user_session_cache),
-            metrics_store: Arc::new(MetricsStore::new("./analytics.db".to_string())),
-            user_data_store: Arc::new(UserDataStore::new("./user_data.db".to_string())),
+            metrics_store: Arc::new(MetricsStore::new(data_dir.join("analytics.db").to_string_lossy().into_owned())),
+            user_data_store: Arc::new(UserDataStore::new(data_dir.join("user_data.db").to_string_lossy().into_owned())),
             health_monitor: Arc::new(HealthMonitor::new()),
             blockchain_monitor: Arc::new(BlockchainHealthMonitor::new()),
             analytics_processors,
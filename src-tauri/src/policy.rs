@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::security::{SecurityError, TransactionSecurity};
+use crate::vault::{VaultConfig, VaultStorage};
+
+/// Policy file inside each profile directory
+const POLICY_FILE: &str = "policy.json";
+
+/// Spend ledger file inside each profile directory
+const LEDGER_FILE: &str = "spend_ledger.json";
+
+/// Asset name of native SOL; other assets are named by their mint address
+pub const NATIVE_ASSET: &str = "SOL";
+
+/// Hours over which daily limits are measured
+pub const SPEND_WINDOW_HOURS: i64 = 24;
+
+const HMAC_INFO: &[u8] = b"lesinki-policy-hmac";
+
+/// Policy errors
+#[derive(Error, Debug)]
+pub enum PolicyError {
+    #[error("Blocked by policy '{policy}': {reason}")]
+    Blocked { policy: String, reason: String },
+    #[error("Invalid policy: {0}")]
+    Invalid(String),
+    #[error("Approval request not found: {0}")]
+    ApprovalNotFound(String),
+    #[error("Policy file {0} failed authentication")]
+    Unauthenticated(String),
+    #[error("Spend ledger is missing while the policy sets limits")]
+    MissingLedger,
+    #[error("Policy file is missing although a policy was saved")]
+    MissingPolicy,
+    #[error("Policy key error: {0}")]
+    Key(String),
+    #[error("Policy storage error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Policy file error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+impl From<PolicyError> for String {
+    fn from(err: PolicyError) -> String {
+        err.to_string()
+    }
+}
+
+impl From<SecurityError> for PolicyError {
+    fn from(err: SecurityError) -> PolicyError {
+        match err {
+            SecurityError::PolicyViolation { policy, reason } => PolicyError::Blocked { policy, reason },
+            other => PolicyError::Invalid(other.to_string()),
+        }
+    }
+}
+
+fn blocked(policy: &str, reason: String) -> PolicyError {
+    PolicyError::Blocked { policy: policy.to_string(), reason }
+}
+
+/// A named limit on how much of one asset may leave a wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendRule {
+    /// Named in the error when the rule blocks a transaction
+    pub name: String,
+    /// Public key of the wallet the rule limits; `None` limits every wallet on its own
+    #[serde(default)]
+    pub wallet: Option<String>,
+    /// `NATIVE_ASSET` or a token mint address
+    pub asset: String,
+    /// Largest amount a single transaction may send, in base units
+    #[serde(default)]
+    pub max_amount: Option<u64>,
+    /// Largest amount that may be sent within `SPEND_WINDOW_HOURS`, in base units
+    #[serde(default)]
+    pub daily_limit: Option<u64>,
+}
+
+impl SpendRule {
+    fn applies_to(&self, spend: &Spend) -> bool {
+        self.asset == spend.asset && self.wallet.iter().all(|wallet| *wallet == spend.wallet)
+    }
+}
+
+/// Spending policy of a profile
+///
+/// `security.max_amount` and `security.daily_limit` are the default SOL limits of
+/// every wallet; `rules` add per-wallet and per-token limits on top of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionPolicy {
+    #[serde(default)]
+    pub security: TransactionSecurity,
+    #[serde(default)]
+    pub rules: Vec<SpendRule>,
+}
+
+impl TransactionPolicy {
+    /// Whether any amount or volume limit is set, so the ledger must be kept
+    pub fn has_limits(&self) -> bool {
+        self.security.max_amount != u64::MAX || self.security.daily_limit != u64::MAX || !self.rules.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<(), PolicyError> {
+        if self.security.transaction_timeout.is_zero() {
+            return Err(PolicyError::Invalid("transaction_timeout must be greater than zero".to_string()));
+        }
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.name.trim().is_empty() {
+                return Err(PolicyError::Invalid(format!("rule {} has no name", i + 1)));
+            }
+            if self.rules[..i].iter().any(|other| other.name == rule.name) {
+                return Err(PolicyError::Invalid(format!("rule name '{}' is used twice", rule.name)));
+            }
+            if rule.asset.is_empty() {
+                return Err(PolicyError::Invalid(format!("rule '{}' has no asset", rule.name)));
+            }
+            if rule.max_amount.is_none() && rule.daily_limit.is_none() {
+                return Err(PolicyError::Invalid(format!("rule '{}' sets no limit", rule.name)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `spends`, submitted together, against every limit given what `ledger` has already recorded
+    ///
+    /// `simulated` tells whether the transaction is simulated before it is submitted.
+    pub fn evaluate(
+        &self,
+        ledger: &SpendLedger,
+        spends: &[Spend],
+        simulated: bool,
+        now: DateTime<Utc>,
+    ) -> Result<(), PolicyError> {
+        if self.security.simulation_required && !simulated {
+            return Err(blocked("simulation_required", "the transaction is not simulated before it is submitted".to_string()));
+        }
+
+        // Spends of the same request count towards each other's daily limits
+        let mut pending: HashMap<(&str, &str), u64> = HashMap::new();
+        for spend in spends {
+            let in_request = pending.entry((&spend.wallet, &spend.asset)).or_default();
+            *in_request = in_request.saturating_add(spend.amount);
+            let volume = ledger.volume(&spend.wallet, &spend.asset, now).saturating_add(*in_request);
+
+            if spend.asset == NATIVE_ASSET {
+                check_max_amount("max_amount", self.security.max_amount, spend)?;
+                check_daily_limit("daily_limit", self.security.daily_limit, spend, volume)?;
+            }
+            for rule in self.rules.iter().filter(|rule| rule.applies_to(spend)) {
+                if let Some(max) = rule.max_amount {
+                    check_max_amount(&rule.name, max, spend)?;
+                }
+                if let Some(limit) = rule.daily_limit {
+                    check_daily_limit(&rule.name, limit, spend, volume)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_max_amount(policy: &str, max: u64, spend: &Spend) -> Result<(), PolicyError> {
+    if spend.amount > max {
+        return Err(blocked(policy, format!(
+            "{} {} from {} exceeds the per-transaction limit of {}",
+            spend.amount, spend.asset, spend.wallet, max
+        )));
+    }
+    Ok(())
+}
+
+/// `volume` already includes `spend`
+fn check_daily_limit(policy: &str, limit: u64, spend: &Spend, volume: u64) -> Result<(), PolicyError> {
+    if volume > limit {
+        return Err(blocked(policy, format!(
+            "{} {} from {} would bring its 24h volume to {}, over the limit of {}",
+            spend.amount, spend.asset, spend.wallet, volume, limit
+        )));
+    }
+    Ok(())
+}
+
+/// Funds a signing operation sends out of one wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spend {
+    /// Public key of the sending wallet
+    pub wallet: String,
+    /// `NATIVE_ASSET` or a token mint address
+    pub asset: String,
+    /// Amount in base units: lamports for SOL, raw token units otherwise
+    pub amount: u64,
+    pub recipient: Option<String>,
+}
+
+impl Spend {
+    pub fn sol(wallet: &str, amount: u64, recipient: Option<&str>) -> Self {
+        Self {
+            wallet: wallet.to_string(),
+            asset: NATIVE_ASSET.to_string(),
+            amount,
+            recipient: recipient.map(str::to_string),
+        }
+    }
+}
+
+/// A recorded spend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub operation: String,
+    pub wallet: String,
+    pub asset: String,
+    pub amount: u64,
+    pub at: DateTime<Utc>,
+}
+
+/// Total sent by one wallet in one asset over the last `SPEND_WINDOW_HOURS`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendVolume {
+    pub wallet: String,
+    pub asset: String,
+    pub amount: u64,
+}
+
+/// Spends of the last `SPEND_WINDOW_HOURS`, kept on disk so limits survive restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpendLedger {
+    entries: Vec<LedgerEntry>,
+}
+
+impl SpendLedger {
+    fn window_start(now: DateTime<Utc>) -> DateTime<Utc> {
+        now - chrono::Duration::hours(SPEND_WINDOW_HOURS)
+    }
+
+    fn in_window(&self, now: DateTime<Utc>) -> impl Iterator<Item = &LedgerEntry> {
+        let start = Self::window_start(now);
+        self.entries.iter().filter(move |entry| entry.at > start && entry.at <= now)
+    }
+
+    /// Amount `wallet` sent of `asset` in the window ending at `now`
+    pub fn volume(&self, wallet: &str, asset: &str, now: DateTime<Utc>) -> u64 {
+        self.in_window(now)
+            .filter(|entry| entry.wallet == wallet && entry.asset == asset)
+            .fold(0, |total, entry| total.saturating_add(entry.amount))
+    }
+
+    /// Volume of every wallet and asset with spends in the window ending at `now`
+    pub fn volumes(&self, now: DateTime<Utc>) -> Vec<SpendVolume> {
+        let mut volumes: Vec<SpendVolume> = Vec::new();
+        for entry in self.in_window(now) {
+            match volumes.iter_mut().find(|v| v.wallet == entry.wallet && v.asset == entry.asset) {
+                Some(volume) => volume.amount = volume.amount.saturating_add(entry.amount),
+                None => volumes.push(SpendVolume {
+                    wallet: entry.wallet.clone(),
+                    asset: entry.asset.clone(),
+                    amount: entry.amount,
+                }),
+            }
+        }
+        volumes
+    }
+
+    /// Record the non-zero `spends` of `operation` at `now`
+    pub fn record(&mut self, operation: &str, spends: &[Spend], now: DateTime<Utc>) {
+        for spend in spends.iter().filter(|spend| spend.amount > 0) {
+            self.entries.push(LedgerEntry {
+                operation: operation.to_string(),
+                wallet: spend.wallet.clone(),
+                asset: spend.asset.clone(),
+                amount: spend.amount,
+                at: now,
+            });
+        }
+    }
+
+    /// Drop entries too old to count towards any limit
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let start = Self::window_start(now);
+        self.entries.retain(|entry| entry.at > start);
+    }
+}
+
+/// A transaction held back by `require_approval` until the user approves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub id: String,
+    pub operation: String,
+    pub spends: Vec<Spend>,
+    pub requested_at: DateTime<Utc>,
+    pub approved: bool,
+}
+
+/// Transactions waiting for approval, kept in memory only
+///
+/// A blocked request is queued under an id derived from its content, so retrying
+/// the same transaction after approving it finds the approval. Each approval
+/// lets the transaction through once and lapses after `transaction_timeout`.
+#[derive(Debug, Default)]
+pub struct ApprovalQueue {
+    requests: Vec<PendingApproval>,
+}
+
+impl ApprovalQueue {
+    fn request_id(operation: &str, spends: &[Spend]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(operation.as_bytes());
+        for spend in spends {
+            hasher.update(serde_json::to_vec(spend).unwrap_or_default());
+        }
+        hex::encode(&hasher.finalize()[..8])
+    }
+
+    fn expire(&mut self, timeout: Duration, now: DateTime<Utc>) {
+        self.requests.retain(|request| (now - request.requested_at).to_std().map_or(true, |age| age < timeout));
+    }
+
+    /// Let an approved request through once, or queue it and block it until it is approved
+    pub fn check(
+        &mut self,
+        operation: &str,
+        spends: &[Spend],
+        timeout: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<(), PolicyError> {
+        self.expire(timeout, now);
+        let id = Self::request_id(operation, spends);
+
+        if let Some(i) = self.requests.iter().position(|request| request.id == id && request.approved) {
+            self.requests.remove(i);
+            return Ok(());
+        }
+        if !self.requests.iter().any(|request| request.id == id) {
+            self.requests.push(PendingApproval {
+                id: id.clone(),
+                operation: operation.to_string(),
+                spends: spends.to_vec(),
+                requested_at: now,
+                approved: false,
+            });
+        }
+        Err(blocked("require_approval", format!("approve request {} and submit the transaction again", id)))
+    }
+
+    pub fn approve(&mut self, id: &str, timeout: Duration, now: DateTime<Utc>) -> Result<PendingApproval, PolicyError> {
+        self.expire(timeout, now);
+        let request = self.requests.iter_mut()
+            .find(|request| request.id == id)
+            .ok_or_else(|| PolicyError::ApprovalNotFound(id.to_string()))?;
+        request.approved = true;
+        Ok(request.clone())
+    }
+
+    pub fn reject(&mut self, id: &str) -> Result<(), PolicyError> {
+        let before = self.requests.len();
+        self.requests.retain(|request| request.id != id);
+        if self.requests.len() == before {
+            return Err(PolicyError::ApprovalNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn pending(&mut self, timeout: Duration, now: DateTime<Utc>) -> Vec<PendingApproval> {
+        self.expire(timeout, now);
+        self.requests.clone()
+    }
+}
+
+/// HMAC key of a profile's policy files, derived from the vault's audit secret
+pub fn derive_key(secret: &[u8]) -> Result<Zeroizing<Vec<u8>>, PolicyError> {
+    let mut key = Zeroizing::new(vec![0u8; 32]);
+    Hkdf::<Sha256>::new(None, secret)
+        .expand(HMAC_INFO, &mut key)
+        .map_err(|e| PolicyError::Key(e.to_string()))?;
+    Ok(key)
+}
+
+/// A policy file's JSON content with an HMAC over it and the file's name
+#[derive(Serialize, Deserialize)]
+struct SignedFile {
+    /// Kept as the exact bytes the MAC covers
+    content: String,
+    mac: String,
+}
+
+/// Policy and spend ledger files of one profile
+///
+/// Layout: `<profile_dir>/policy.json` and `<profile_dir>/spend_ledger.json`, each
+/// authenticated with a key derived from the vault, so they can only be read and
+/// written while it is unlocked. A missing policy means the default one until the
+/// vault records that a policy was saved. A missing ledger means an empty one unless
+/// the policy sets limits; saving such a policy creates the ledger. An unreadable or
+/// forged file is an error, so editing or removing either file never resets the limits. Like the audit log, restoring
+/// earlier copies of the files cannot be told apart from that earlier state.
+#[derive(Clone)]
+pub struct PolicyStore {
+    dir: PathBuf,
+    key: Zeroizing<Vec<u8>>,
+    policy_saved: bool,
+}
+
+impl PolicyStore {
+    pub fn new(dir: impl Into<PathBuf>, key: &[u8]) -> Self {
+        Self { dir: dir.into(), key: Zeroizing::new(key.to_vec()), policy_saved: false }
+    }
+
+    /// Whether the vault records that a policy was saved, making a missing policy file an error
+    pub fn with_saved_policy(mut self, policy_saved: bool) -> Self {
+        self.policy_saved = policy_saved;
+        self
+    }
+
+    fn hmac(&self, file: &str, content: &[u8]) -> Result<Hmac<Sha256>, PolicyError> {
+        let mut hmac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .map_err(|e| PolicyError::Key(e.to_string()))?;
+        hmac.update(file.as_bytes());
+        hmac.update(&[0]);
+        hmac.update(content);
+        Ok(hmac)
+    }
+
+    fn read<T: for<'de> Deserialize<'de>>(&self, file: &str) -> Result<Option<T>, PolicyError> {
+        let path = self.dir.join(file);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let signed: SignedFile = serde_json::from_slice(&fs::read(&path)?)?;
+        let mac = hex::decode(&signed.mac).map_err(|_| PolicyError::Unauthenticated(file.to_string()))?;
+        self.hmac(file, signed.content.as_bytes())?
+            .verify_slice(&mac)
+            .map_err(|_| PolicyError::Unauthenticated(file.to_string()))?;
+        Ok(Some(serde_json::from_str(&signed.content)?))
+    }
+
+    fn write<T: Serialize>(&self, file: &str, value: &T) -> Result<(), PolicyError> {
+        let content = serde_json::to_string_pretty(value)?;
+        let mac = hex::encode(self.hmac(file, content.as_bytes())?.finalize().into_bytes());
+
+        let storage = VaultStorage::new(self.dir.join(file), &VaultConfig { backup_count: 1, ..VaultConfig::default() });
+        storage.write(&serde_json::to_vec_pretty(&SignedFile { content, mac })?)?;
+        Ok(())
+    }
+
+    pub fn load_policy(&self) -> Result<TransactionPolicy, PolicyError> {
+        match self.read(POLICY_FILE)? {
+            Some(policy) => Ok(policy),
+            None if self.policy_saved => Err(PolicyError::MissingPolicy),
+            None => Ok(TransactionPolicy::default()),
+        }
+    }
+
+    pub fn save_policy(&self, policy: &TransactionPolicy) -> Result<(), PolicyError> {
+        policy.validate()?;
+        // The ledger goes first, so a policy with limits is never on disk without one
+        if policy.has_limits() && !self.dir.join(LEDGER_FILE).exists() {
+            self.write(LEDGER_FILE, &SpendLedger::default())?;
+        }
+        self.write(POLICY_FILE, policy)
+    }
+
+    /// The ledger `policy` is enforced against
+    pub fn load_ledger(&self, policy: &TransactionPolicy) -> Result<SpendLedger, PolicyError> {
+        match self.read(LEDGER_FILE)? {
+            Some(ledger) => Ok(ledger),
+            None if policy.has_limits() => Err(PolicyError::MissingLedger),
+            None => Ok(SpendLedger::default()),
+        }
+    }
+
+    /// Save the ledger without entries older than the window ending at `now`
+    pub fn save_ledger(&self, ledger: &mut SpendLedger, now: DateTime<Utc>) -> Result<(), PolicyError> {
+        ledger.prune(now);
+        self.write(LEDGER_FILE, ledger)
+    }
+}
+
+include!("policy_tests.rs");
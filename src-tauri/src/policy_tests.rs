@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const ALICE: &str = "Alice1111111111111111111111111111111111111";
+    const BOB: &str = "Bob11111111111111111111111111111111111111";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn token(wallet: &str, asset: &str, amount: u64) -> Spend {
+        Spend {
+            wallet: wallet.to_string(),
+            asset: asset.to_string(),
+            amount,
+            recipient: None,
+        }
+    }
+
+    fn policy(rules: Vec<SpendRule>) -> TransactionPolicy {
+        TransactionPolicy {
+            security: TransactionSecurity { simulation_required: false, ..TransactionSecurity::default() },
+            rules,
+        }
+    }
+
+    fn rule(name: &str, wallet: Option<&str>, asset: &str, max_amount: Option<u64>, daily_limit: Option<u64>) -> SpendRule {
+        SpendRule {
+            name: name.to_string(),
+            wallet: wallet.map(str::to_string),
+            asset: asset.to_string(),
+            max_amount,
+            daily_limit,
+        }
+    }
+
+    fn blocked_by(result: Result<(), PolicyError>) -> Option<String> {
+        match result {
+            Err(PolicyError::Blocked { policy, .. }) => Some(policy),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_repeat_transfers_pass_until_the_daily_limit() {
+        let now = Utc::now();
+        let policy = TransactionPolicy {
+            security: TransactionSecurity { daily_limit: 3_000, simulation_required: false, ..TransactionSecurity::default() },
+            rules: vec![],
+        };
+        let mut ledger = SpendLedger::default();
+
+        for _ in 0..3 {
+            policy.evaluate(&ledger, &[Spend::sol(ALICE, 1_000, None)], false, now).unwrap();
+            ledger.record("transfer_tokens", &[Spend::sol(ALICE, 1_000, None)], now);
+        }
+        let err = policy.evaluate(&ledger, &[Spend::sol(ALICE, 1, None)], false, now).unwrap_err();
+        assert!(err.to_string().starts_with("Blocked by policy 'daily_limit':"), "{}", err);
+
+        // Other wallets have their own volume
+        assert!(policy.evaluate(&ledger, &[Spend::sol(BOB, 1_000, None)], false, now).is_ok());
+    }
+
+    #[test]
+    fn test_volume_is_a_rolling_window() {
+        let now = Utc::now();
+        let mut ledger = SpendLedger::default();
+        ledger.record("transfer_tokens", &[Spend::sol(ALICE, 500, None)], now - chrono::Duration::hours(25));
+        ledger.record("transfer_tokens", &[Spend::sol(ALICE, 700, None)], now - chrono::Duration::hours(23));
+
+        assert_eq!(ledger.volume(ALICE, NATIVE_ASSET, now), 700);
+        assert_eq!(ledger.volume(ALICE, NATIVE_ASSET, now + chrono::Duration::hours(2)), 0);
+
+        ledger.prune(now);
+        assert_eq!(ledger.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_rules_limit_their_own_wallet_and_token() {
+        let now = Utc::now();
+        let policy = policy(vec![
+            rule("bonk-per-tx", None, BONK, Some(100), None),
+            rule("alice-sol-daily", Some(ALICE), NATIVE_ASSET, None, Some(1_000)),
+        ]);
+        let ledger = SpendLedger::default();
+
+        let err = policy.evaluate(&ledger, &[token(BOB, BONK, 101)], false, now);
+        assert_eq!(blocked_by(err), Some("bonk-per-tx".to_string()));
+
+        // A token limit says nothing about SOL, and a wallet's rule nothing about other wallets
+        assert!(policy.evaluate(&ledger, &[Spend::sol(BOB, 5_000, None)], false, now).is_ok());
+        assert!(policy.evaluate(&ledger, &[token(ALICE, BONK, 100)], false, now).is_ok());
+
+        let err = policy.evaluate(&ledger, &[Spend::sol(ALICE, 1_001, None)], false, now);
+        assert_eq!(blocked_by(err), Some("alice-sol-daily".to_string()));
+    }
+
+    #[test]
+    fn test_spends_of_one_request_add_up() {
+        let now = Utc::now();
+        let policy = policy(vec![rule("sol-daily", None, NATIVE_ASSET, None, Some(1_000))]);
+        let ledger = SpendLedger::default();
+
+        let bundle = [Spend::sol(ALICE, 600, None), Spend::sol(ALICE, 600, None)];
+        assert_eq!(blocked_by(policy.evaluate(&ledger, &bundle, false, now)), Some("sol-daily".to_string()));
+
+        let spread = [Spend::sol(ALICE, 600, None), Spend::sol(BOB, 600, None)];
+        assert!(policy.evaluate(&ledger, &spread, false, now).is_ok());
+    }
+
+    #[test]
+    fn test_unsimulated_transactions_blocked_when_simulation_required() {
+        let policy = TransactionPolicy::default();
+        let spend = [Spend::sol(ALICE, 1, None)];
+
+        let err = policy.evaluate(&SpendLedger::default(), &spend, false, Utc::now());
+        assert_eq!(blocked_by(err), Some("simulation_required".to_string()));
+        assert!(policy.evaluate(&SpendLedger::default(), &spend, true, Utc::now()).is_ok());
+    }
+
+    #[test]
+    fn test_approval_lets_a_transaction_through_once() {
+        let now = Utc::now();
+        let timeout = Duration::from_secs(300);
+        let spend = [Spend::sol(ALICE, 1_000, Some(BOB))];
+        let mut approvals = ApprovalQueue::default();
+
+        let err = approvals.check("transfer_tokens", &spend, timeout, now);
+        assert_eq!(blocked_by(err), Some("require_approval".to_string()));
+        let pending = approvals.pending(timeout, now);
+        assert_eq!(pending.len(), 1);
+
+        approvals.approve(&pending[0].id, timeout, now).unwrap();
+        approvals.check("transfer_tokens", &spend, timeout, now).unwrap();
+        assert!(approvals.check("transfer_tokens", &spend, timeout, now).is_err());
+
+        // Approvals lapse after the timeout
+        let id = approvals.pending(timeout, now)[0].id.clone();
+        approvals.approve(&id, timeout, now).unwrap();
+        let later = now + chrono::Duration::seconds(301);
+        assert!(approvals.check("transfer_tokens", &spend, timeout, later).is_err());
+    }
+
+    #[test]
+    fn test_policy_and_ledger_persist() {
+        let dir = tempdir().unwrap();
+        let store = PolicyStore::new(dir.path(), &derive_key(b"audit secret").unwrap());
+        let now = Utc::now();
+
+        assert!(store.load_policy().unwrap().rules.is_empty());
+        let policy = policy(vec![rule("sol-daily", None, NATIVE_ASSET, None, Some(1_000))]);
+        store.save_policy(&policy).unwrap();
+        assert_eq!(store.load_policy().unwrap().rules, policy.rules);
+
+        let mut ledger = store.load_ledger(&policy).unwrap();
+        ledger.record("transfer_tokens", &[Spend::sol(ALICE, 400, None)], now - chrono::Duration::hours(30));
+        ledger.record("transfer_tokens", &[Spend::sol(ALICE, 900, None)], now);
+        store.save_ledger(&mut ledger, now).unwrap();
+
+        let reloaded = store.load_ledger(&policy).unwrap();
+        assert_eq!(reloaded.volumes(now), vec![SpendVolume {
+            wallet: ALICE.to_string(),
+            asset: NATIVE_ASSET.to_string(),
+            amount: 900,
+        }]);
+        let err = store.load_policy().unwrap().evaluate(&reloaded, &[Spend::sol(ALICE, 101, None)], false, now);
+        assert_eq!(blocked_by(err), Some("sol-daily".to_string()));
+    }
+
+    #[test]
+    fn test_policy_files_are_authenticated() {
+        let dir = tempdir().unwrap();
+        let store = PolicyStore::new(dir.path(), &derive_key(b"audit secret").unwrap());
+        let policy = policy(vec![rule("sol-daily", None, NATIVE_ASSET, None, Some(1_000))]);
+        store.save_policy(&policy).unwrap();
+
+        // Another vault's key does not open them
+        let other = PolicyStore::new(dir.path(), &derive_key(b"other secret").unwrap());
+        assert!(matches!(other.load_policy(), Err(PolicyError::Unauthenticated(_))));
+
+        // Nor does raising a limit by hand
+        let path = dir.path().join(POLICY_FILE);
+        let mut signed: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        let raised = signed["content"].as_str().unwrap().replace("1000", "1000000");
+        signed["content"] = raised.into();
+        fs::write(&path, serde_json::to_vec(&signed).unwrap()).unwrap();
+        assert!(matches!(store.load_policy(), Err(PolicyError::Unauthenticated(_))));
+
+        // The ledger cannot be swapped in for the policy either
+        fs::copy(dir.path().join(LEDGER_FILE), &path).unwrap();
+        assert!(matches!(store.load_policy(), Err(PolicyError::Unauthenticated(_))));
+    }
+
+    #[test]
+    fn test_missing_ledger_is_an_error_under_limits() {
+        let dir = tempdir().unwrap();
+        let store = PolicyStore::new(dir.path(), &derive_key(b"audit secret").unwrap());
+        let unlimited = policy(vec![]);
+        assert!(store.load_ledger(&unlimited).is_ok());
+
+        let limited = policy(vec![rule("sol-daily", None, NATIVE_ASSET, None, Some(1_000))]);
+        store.save_policy(&limited).unwrap();
+        assert!(store.load_ledger(&limited).is_ok());
+
+        fs::remove_file(dir.path().join(LEDGER_FILE)).unwrap();
+        assert!(matches!(store.load_ledger(&limited), Err(PolicyError::MissingLedger)));
+    }
+
+    #[test]
+    fn test_missing_policy_is_an_error_once_saved() {
+        let dir = tempdir().unwrap();
+        let key = derive_key(b"audit secret").unwrap();
+        let limited = policy(vec![rule("sol-daily", None, NATIVE_ASSET, None, Some(1_000))]);
+        PolicyStore::new(dir.path(), &key).save_policy(&limited).unwrap();
+
+        fs::remove_file(dir.path().join(POLICY_FILE)).unwrap();
+        let store = PolicyStore::new(dir.path(), &key).with_saved_policy(true);
+        assert!(matches!(store.load_policy(), Err(PolicyError::MissingPolicy)));
+
+        // Saving the policy again recovers
+        store.save_policy(&limited).unwrap();
+        assert_eq!(store.load_policy().unwrap().rules.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        assert!(policy(vec![rule("", None, NATIVE_ASSET, Some(1), None)]).validate().is_err());
+        assert!(policy(vec![rule("no-limit", None, NATIVE_ASSET, None, None)]).validate().is_err());
+        assert!(policy(vec![
+            rule("dup", None, NATIVE_ASSET, Some(1), None),
+            rule("dup", None, BONK, Some(1), None),
+        ]).validate().is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::session::AutoLockPolicy;
+use crate::vault::{VaultConfig, VaultStorage};
+
+/// Registry file listing all vault profiles, relative to the app data directory
+const REGISTRY_FILE: &str = "profiles.json";
+
+/// Directory holding one sub-directory per profile, relative to the app data directory
+const PROFILES_DIR: &str = "profiles";
+
+/// Vault file name inside each profile directory
+const VAULT_FILE: &str = "wallets.enc";
+
+/// Profile created on first start
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profile errors
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("Invalid profile name '{0}': use 1-32 letters, digits, '-' or '_'")]
+    InvalidName(String),
+    #[error("Profile not found: {0}")]
+    NotFound(String),
+    #[error("Profile already exists: {0}")]
+    AlreadyExists(String),
+    #[error("Cannot delete the active profile: {0}")]
+    ActiveProfile(String),
+    #[error("Profile '{0}' has a vault; deleting it requires force")]
+    HasVault(String),
+    #[error("Profile storage error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Profile registry error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+impl From<ProfileError> for String {
+    fn from(err: ProfileError) -> String {
+        err.to_string()
+    }
+}
+
+/// A named vault profile with its own encrypted vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultProfile {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub vault_config: VaultConfig,
+    #[serde(default)]
+    pub auto_lock: AutoLockPolicy,
+}
+
+/// Profile information returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub active: bool,
+    pub has_vault: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfileRegistry {
+    active: String,
+    profiles: Vec<VaultProfile>,
+}
+
+/// Manages vault profiles under the app data directory
+///
+/// Layout:
+/// `<data_dir>/profiles.json` and `<data_dir>/profiles/<name>/wallets.enc`
+#[derive(Debug, Clone)]
+pub struct ProfileManager {
+    data_dir: PathBuf,
+    registry: ProfileRegistry,
+}
+
+impl ProfileManager {
+    /// Load the profile registry, creating the default profile on first start
+    ///
+    /// A registry whose active profile is missing falls back to its first profile;
+    /// one without any profile is rejected.
+    pub fn load(data_dir: impl Into<PathBuf>) -> Result<Self, ProfileError> {
+        let data_dir = data_dir.into();
+        fs::create_dir_all(&data_dir)?;
+
+        let registry_path = data_dir.join(REGISTRY_FILE);
+        if registry_path.exists() {
+            let mut registry: ProfileRegistry = serde_json::from_slice(&fs::read(&registry_path)?)?;
+            if registry.profiles.iter().any(|p| p.name == registry.active) {
+                return Ok(Self { data_dir, registry });
+            }
+
+            let first = registry.profiles.first()
+                .ok_or_else(|| ProfileError::NotFound(registry.active.clone()))?;
+            log::warn!("Active profile '{}' is not registered, switching to '{}'", registry.active, first.name);
+            registry.active = first.name.clone();
+            let manager = Self { data_dir, registry };
+            manager.save()?;
+            return Ok(manager);
+        }
+
+        let mut manager = Self {
+            data_dir,
+            registry: ProfileRegistry {
+                active: DEFAULT_PROFILE.to_string(),
+                profiles: vec![],
+            },
+        };
+        manager.create(DEFAULT_PROFILE)?;
+        manager.import_legacy_vault()?;
+        Ok(manager)
+    }
+
+    /// App data directory all persisted files are resolved under
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// The currently active profile
+    pub fn active(&self) -> &VaultProfile {
+        // `load` validates the active profile, and it can be neither deleted nor renamed away
+        self.get(&self.registry.active)
+            .expect("active profile is validated on load")
+    }
+
+    pub fn get(&self, name: &str) -> Option<&VaultProfile> {
+        self.registry.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Directory holding a profile's files
+    pub fn profile_dir(&self, name: &str) -> PathBuf {
+        self.data_dir.join(PROFILES_DIR).join(name)
+    }
+
+    /// Storage for the active profile's vault
+    pub fn active_vault_storage(&self) -> VaultStorage {
+        let profile = self.active();
+        VaultStorage::new(self.profile_dir(&profile.name).join(VAULT_FILE), &profile.vault_config)
+    }
+
+    pub fn list(&self) -> Vec<ProfileSummary> {
+        self.registry.profiles.iter()
+            .map(|profile| ProfileSummary {
+                name: profile.name.clone(),
+                created_at: profile.created_at,
+                active: profile.name == self.registry.active,
+                has_vault: self.has_vault(&profile.name),
+            })
+            .collect()
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<&VaultProfile, ProfileError> {
+        self.validate_profile_name(name, None)?;
+
+        fs::create_dir_all(self.profile_dir(name))?;
+        self.registry.profiles.push(VaultProfile {
+            name: name.to_string(),
+            created_at: Utc::now(),
+            vault_config: VaultConfig::default(),
+            auto_lock: AutoLockPolicy::default(),
+        });
+        self.save()?;
+
+        Ok(self.registry.profiles.last().expect("profile was just pushed"))
+    }
+
+    pub fn switch(&mut self, name: &str) -> Result<(), ProfileError> {
+        if self.get(name).is_none() {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+
+        self.registry.active = name.to_string();
+        self.save()
+    }
+
+    pub fn rename(&mut self, old_name: &str, new_name: &str) -> Result<(), ProfileError> {
+        if self.get(old_name).is_none() {
+            return Err(ProfileError::NotFound(old_name.to_string()));
+        }
+        self.validate_profile_name(new_name, Some(old_name))?;
+
+        let old_dir = self.profile_dir(old_name);
+        if old_dir.exists() {
+            fs::rename(&old_dir, self.profile_dir(new_name))?;
+        }
+
+        for profile in &mut self.registry.profiles {
+            if profile.name == old_name {
+                profile.name = new_name.to_string();
+            }
+        }
+        if self.registry.active == old_name {
+            self.registry.active = new_name.to_string();
+        }
+        self.save()
+    }
+
+    /// Whether a profile has a vault file or any of its backups
+    fn has_vault(&self, name: &str) -> bool {
+        let profile = self.get(name);
+        let config = profile.map(|p| p.vault_config.clone()).unwrap_or_default();
+        VaultStorage::new(self.profile_dir(name).join(VAULT_FILE), &config).exists()
+    }
+
+    /// Delete a profile and its vault files; the active profile cannot be deleted
+    ///
+    /// Deleting a profile that has a vault destroys its keys for good, so it takes `force`.
+    pub fn delete(&mut self, name: &str, force: bool) -> Result<(), ProfileError> {
+        if self.get(name).is_none() {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+        if self.registry.active == name {
+            return Err(ProfileError::ActiveProfile(name.to_string()));
+        }
+        if self.has_vault(name) && !force {
+            return Err(ProfileError::HasVault(name.to_string()));
+        }
+
+        let dir = self.profile_dir(name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+
+        self.registry.profiles.retain(|p| p.name != name);
+        self.save()
+    }
+
+    /// Update the active profile's vault configuration
+    pub fn set_active_vault_config(&mut self, config: VaultConfig) -> Result<(), ProfileError> {
+        self.update_active(|profile| profile.vault_config = config)
+    }
+
+    /// Update the active profile's auto-lock policy
+    pub fn set_active_auto_lock(&mut self, policy: AutoLockPolicy) -> Result<(), ProfileError> {
+        self.update_active(|profile| profile.auto_lock = policy)
+    }
+
+    fn update_active<F: FnOnce(&mut VaultProfile)>(&mut self, update: F) -> Result<(), ProfileError> {
+        let active = self.registry.active.clone();
+        if let Some(profile) = self.registry.profiles.iter_mut().find(|p| p.name == active) {
+            update(profile);
+        }
+        self.save()
+    }
+
+    /// Check that `name` is valid and differs from every other profile's name, ignoring case
+    /// like the profile directories on macOS and Windows do; `renaming` is not compared
+    fn validate_profile_name(&self, name: &str, renaming: Option<&str>) -> Result<(), ProfileError> {
+        let valid = !name.is_empty()
+            && name.len() <= 32
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ProfileError::InvalidName(name.to_string()));
+        }
+
+        let taken = self.registry.profiles.iter()
+            .any(|p| Some(p.name.as_str()) != renaming && p.name.eq_ignore_ascii_case(name));
+        if taken {
+            return Err(ProfileError::AlreadyExists(name.to_string()));
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), ProfileError> {
+        let storage = VaultStorage::new(self.data_dir.join(REGISTRY_FILE), &VaultConfig { backup_count: 1, ..VaultConfig::default() });
+        storage.write(&serde_json::to_vec_pretty(&self.registry)?)?;
+        Ok(())
+    }
+
+    /// Copy a vault from the old working-directory location into the default profile
+    fn import_legacy_vault(&self) -> Result<(), ProfileError> {
+        let legacy_path = Path::new(VAULT_FILE);
+        let target = self.profile_dir(DEFAULT_PROFILE).join(VAULT_FILE);
+
+        if legacy_path.exists() && !target.exists() {
+            fs::copy(legacy_path, &target)?;
+            log::info!("Imported legacy vault {} into profile '{}'", legacy_path.display(), DEFAULT_PROFILE);
+        }
+        Ok(())
+    }
+}
+
+include!("profiles_tests.rs");
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_default_profile_created() {
+        let dir = tempdir().unwrap();
+        let manager = ProfileManager::load(dir.path()).unwrap();
+
+        assert_eq!(manager.active().name, DEFAULT_PROFILE);
+        assert!(dir.path().join(REGISTRY_FILE).exists());
+        assert_eq!(
+            manager.active_vault_storage().path(),
+            dir.path().join(PROFILES_DIR).join(DEFAULT_PROFILE).join(VAULT_FILE)
+        );
+    }
+
+    #[test]
+    fn test_create_switch_rename_delete() {
+        let dir = tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path()).unwrap();
+
+        manager.create("treasury").unwrap();
+        manager.create("bundler").unwrap();
+        assert!(matches!(manager.create("treasury"), Err(ProfileError::AlreadyExists(_))));
+
+        manager.switch("treasury").unwrap();
+        assert_eq!(manager.active().name, "treasury");
+
+        manager.rename("treasury", "cold-treasury").unwrap();
+        assert_eq!(manager.active().name, "cold-treasury");
+        assert!(manager.profile_dir("cold-treasury").exists());
+        assert!(!manager.profile_dir("treasury").exists());
+
+        assert!(matches!(manager.delete("cold-treasury", true), Err(ProfileError::ActiveProfile(_))));
+        manager.delete("bundler", false).unwrap();
+        assert!(manager.get("bundler").is_none());
+
+        // Registry persists across reloads
+        let reloaded = ProfileManager::load(dir.path()).unwrap();
+        assert_eq!(reloaded.active().name, "cold-treasury");
+        assert_eq!(reloaded.list().len(), 2);
+    }
+
+    #[test]
+    fn test_unregistered_active_profile_falls_back() {
+        let dir = tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path()).unwrap();
+        manager.create("treasury").unwrap();
+
+        let registry_path = dir.path().join(REGISTRY_FILE);
+        let mut registry: serde_json::Value = serde_json::from_slice(&fs::read(&registry_path).unwrap()).unwrap();
+        registry["active"] = "vanished".into();
+        fs::write(&registry_path, serde_json::to_vec(&registry).unwrap()).unwrap();
+
+        let reloaded = ProfileManager::load(dir.path()).unwrap();
+        assert_eq!(reloaded.active().name, DEFAULT_PROFILE);
+        assert_eq!(ProfileManager::load(dir.path()).unwrap().active().name, DEFAULT_PROFILE);
+
+        registry["profiles"] = serde_json::json!([]);
+        fs::write(&registry_path, serde_json::to_vec(&registry).unwrap()).unwrap();
+        assert!(matches!(ProfileManager::load(dir.path()), Err(ProfileError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_auto_lock_policy_is_per_profile() {
+        let dir = tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path()).unwrap();
+        manager.create("hot").unwrap();
+
+        let policy = AutoLockPolicy {
+            idle_timeout_secs: Some(30),
+            max_session_secs: None,
+        };
+        manager.switch("hot").unwrap();
+        manager.set_active_auto_lock(policy.clone()).unwrap();
+
+        let reloaded = ProfileManager::load(dir.path()).unwrap();
+        assert_eq!(reloaded.get("hot").unwrap().auto_lock, policy);
+        assert_eq!(reloaded.get(DEFAULT_PROFILE).unwrap().auto_lock, AutoLockPolicy::default());
+    }
+
+    #[test]
+    fn test_deleting_a_vault_requires_force() {
+        let dir = tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path()).unwrap();
+        manager.create("hot").unwrap();
+        manager.switch("hot").unwrap();
+        manager.active_vault_storage().write(b"vault").unwrap();
+        manager.switch(DEFAULT_PROFILE).unwrap();
+
+        assert!(matches!(manager.delete("hot", false), Err(ProfileError::HasVault(_))));
+        assert!(manager.profile_dir("hot").join(VAULT_FILE).exists());
+
+        manager.delete("hot", true).unwrap();
+        assert!(manager.get("hot").is_none());
+        assert!(!manager.profile_dir("hot").exists());
+    }
+
+    #[test]
+    fn test_profile_names_differing_in_case_collide() {
+        let dir = tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path()).unwrap();
+        manager.create("main").unwrap();
+        manager.create("bundler").unwrap();
+
+        assert!(matches!(manager.create("Main"), Err(ProfileError::AlreadyExists(_))));
+        assert!(matches!(manager.rename("bundler", "MAIN"), Err(ProfileError::AlreadyExists(_))));
+
+        // Changing only the case of a profile's own name is fine
+        manager.rename("main", "Main").unwrap();
+        assert!(manager.get("Main").is_some());
+        assert!(manager.get("main").is_none());
+    }
+
+    #[test]
+    fn test_invalid_profile_names_rejected() {
+        let dir = tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path()).unwrap();
+
+        let long_name = "x".repeat(33);
+        for name in ["", "../escape", "with space", long_name.as_str()] {
+            assert!(matches!(manager.create(name), Err(ProfileError::InvalidName(_))));
+        }
+    }
+}
@@ -204,7 +204,7 @@ impl Default for PumpfunConfig {
 impl PumpfunInterface {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: crate::http_client::client(),
             config: PumpfunConfig::default(),
             jito_config: JitoConfig::default(),
             mev_protection: MevProtection::default(),
@@ -212,20 +212,40 @@ impl PumpfunInterface {
         }
     }
 
+    /// Whether transactions are simulated before they are submitted
+    pub fn simulates_before_submit(&self) -> bool {
+        self.mev_protection.simulate_before_submit
+    }
+
+    /// Launch+Snipe settings used when a launch does not bring its own
+    pub fn launch_snipe_config(&self) -> &LaunchSnipeConfig {
+        &self.launch_snipe_config
+    }
+
+    /// Simulate `transaction` if MEV protection asks for it, failing if it would not succeed
+    fn simulate(&self, rpc_client: &RpcClient, transaction: &Transaction) -> Result<(), PumpfunBundlerError> {
+        if !self.mev_protection.simulate_before_submit {
+            return Ok(());
+        }
+
+        let simulation = rpc_client.simulate_transaction(transaction)
+            .map_err(|e| PumpfunBundlerError::Simulation(e.to_string()))?;
+        match simulation.value.err {
+            Some(err) => Err(PumpfunBundlerError::Simulation(err.to_string())),
+            None => Ok(()),
+        }
+    }
+
     /// Enhanced bundle transaction creation with Jito integration
     pub async fn create_bundle_transaction(
         &self,
-        from_public_key: String,
-        private_key: Vec<u8>,
+        keypair: Keypair,
         recipient: String,
         amount: u64,
         network: String,
         use_jito: bool,
         tip_lamports: Option<u64>,
     ) -> Result<String, PumpfunBundlerError> {
-        let keypair = Keypair::from_bytes(&private_key)
-            .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
-
         let to_pubkey = Pubkey::from_str(&recipient)
             .map_err(|e| PumpfunBundlerError::InvalidMetadata(e.to_string()))?;
 
@@ -235,7 +255,7 @@ impl PumpfunInterface {
             _ => return Err(PumpfunBundlerError::InvalidMetadata("Invalid network".to_string())),
         };
 
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+        let rpc_client = crate::http_client::rpc_client(rpc_url.to_string());
         let recent_blockhash = rpc_client.get_latest_blockhash()
             .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
 
@@ -247,6 +267,8 @@ impl PumpfunInterface {
             recent_blockhash,
         );
 
+        self.simulate(&rpc_client, &transaction)?;
+
         if use_jito && self.jito_config.enabled {
             self.send_via_jito_bundle(vec![transaction], tip_lamports).await
         } else {
@@ -315,8 +337,7 @@ impl PumpfunInterface {
     /// Enhanced pump.fun token creation
     pub async fn create_pump_fun_token(
         &self,
-        dev_wallet: Wallet,
-        password: String,
+        keypair: Keypair,
         metadata: PumpfunTokenMetadata,
         network: String,
         use_jito: Option<bool>,
@@ -329,18 +350,13 @@ impl PumpfunInterface {
             return Err(PumpfunBundlerError::InvalidMetadata("Initial liquidity must be positive".to_string()));
         }
 
-        let encryption_key = derive_encryption_key(&password, &dev_wallet.salt)?;
-        let private_key_bytes = decrypt_data(&encryption_key, &dev_wallet.encrypted_private_key)?;
-        let keypair = Keypair::from_bytes(&private_key_bytes)
-            .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
-
         let rpc_url = match network.as_str() {
             "mainnet" => "https://api.mainnet-beta.solana.com",
             "devnet" => "https://api.devnet.solana.com",
             _ => return Err(PumpfunBundlerError::InvalidMetadata("Invalid network".to_string())),
         };
 
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+        let rpc_client = crate::http_client::rpc_client(rpc_url.to_string());
         let recent_blockhash = rpc_client.get_latest_blockhash()
             .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
 
@@ -366,16 +382,7 @@ impl PumpfunInterface {
         );
 
         // Simulate launch transaction
-        if self.mev_protection.simulate_before_submit {
-            let simulation = rpc_client.simulate_transaction(&launch_transaction)
-                .map_err(|e| PumpfunBundlerError::Simulation(e.to_string()))?;
-            
-            if simulation.value.err.is_some() {
-                return Err(PumpfunBundlerError::Simulation(
-                    simulation.value.err.unwrap().to_string()
-                ));
-            }
-        }
+        self.simulate(&rpc_client, &launch_transaction)?;
 
         // Send launch transaction
         let signature = if use_jito.unwrap_or(false) && self.jito_config.enabled {
@@ -434,8 +441,7 @@ impl PumpfunInterface {
     /// Launch+Snipe+Bundle: The critical MEV protection strategy
     pub async fn launch_snipe_bundle(
         &self,
-        dev_wallet: Wallet,
-        password: String,
+        keypair: Keypair,
         metadata: PumpfunTokenMetadata,
         network: String,
         config: Option<LaunchSnipeConfig>,
@@ -446,19 +452,13 @@ impl PumpfunInterface {
             return Err(PumpfunBundlerError::LaunchSnipeFailed("Launch+Snipe disabled".to_string()));
         }
 
-        // Decrypt dev wallet
-        let encryption_key = derive_encryption_key(&password, &dev_wallet.salt)?;
-        let private_key_bytes = decrypt_data(&encryption_key, &dev_wallet.encrypted_private_key)?;
-        let keypair = Keypair::from_bytes(&private_key_bytes)
-            .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
-
         let rpc_url = match network.as_str() {
             "mainnet" => "https://api.mainnet-beta.solana.com",
             "devnet" => "https://api.devnet.solana.com",
             _ => return Err(PumpfunBundlerError::InvalidMetadata("Invalid network".to_string())),
         };
 
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+        let rpc_client = crate::http_client::rpc_client(rpc_url.to_string());
         let recent_blockhash = rpc_client.get_latest_blockhash()
             .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
 
@@ -481,6 +481,9 @@ impl PumpfunInterface {
             recent_blockhash,
         );
 
+        // The snipe depends on the launch landing, so only the launch can be simulated up front
+        self.simulate(&rpc_client, &launch_transaction)?;
+
         // Calculate snipe amount
         let snipe_amount_lamports = ((initial_liquidity_lamports as f64) * launch_config.buy_amount_percentage * 1_000_000_000.0) as u64;
         let final_snipe_amount = snipe_amount_lamports.min((launch_config.max_buy_amount_sol * 1_000_000_000.0) as u64);
@@ -609,10 +612,9 @@ impl PumpfunInterface {
     /// Enhanced bundle buying with MEV protection
     pub async fn execute_bundle_buy(
         &self,
-        bundle_wallets: Vec<Wallet>,
+        bundle_keypairs: Vec<Keypair>,
         token_address: String,
         amount_per_wallet: u64,
-        password: String,
         swap_dapp: SwapDapp,
         network: String,
         use_mev_protection: bool,
@@ -620,26 +622,23 @@ impl PumpfunInterface {
         let mut signatures = Vec::new();
         let start_time = Instant::now();
 
-        if bundle_wallets.is_empty() {
+        if bundle_keypairs.is_empty() {
             return Err(PumpfunBundlerError::InvalidMetadata("No bundle wallets provided".to_string()));
         }
 
         // Use Jito bundles for better MEV protection
+        let rpc_client = crate::http_client::rpc_client(self.get_rpc_url(&network));
         let mut transactions = Vec::new();
-        for wallet in &bundle_wallets {
-            let encryption_key = derive_encryption_key(&password, &wallet.salt)?;
-            let private_key_bytes = decrypt_data(&encryption_key, &wallet.encrypted_private_key)?;
-            let keypair = Keypair::from_bytes(&private_key_bytes)
-                .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
-
+        for keypair in &bundle_keypairs {
             let transaction = self.create_swap_transaction(
-                &keypair,
+                keypair,
                 &token_address,
                 amount_per_wallet,
                 &swap_dapp,
                 &network,
             ).await?;
 
+            self.simulate(&rpc_client, &transaction)?;
             transactions.push(transaction);
         }
 
@@ -653,7 +652,6 @@ impl PumpfunInterface {
             signatures.push(bundle_result);
         } else {
             for transaction in transactions {
-                let rpc_client = RpcClient::new(self.get_rpc_url(&network));
                 let signature = rpc_client.send_and_confirm_transaction(&transaction)
                     .map_err(|e| PumpfunBundlerError::BundleSubmission(e.to_string()))?;
                 signatures.push(signature.to_string());
@@ -664,8 +662,8 @@ impl PumpfunInterface {
 
         Ok(BundleBuyResponse {
             signatures,
-            total_transactions: bundle_wallets.len(),
-            total_amount: amount_per_wallet * bundle_wallets.len() as u64,
+            total_transactions: bundle_keypairs.len(),
+            total_amount: amount_per_wallet * bundle_keypairs.len() as u64,
             execution_time: start_time.elapsed(),
             success_count: signatures.len(),
         })
@@ -686,7 +684,7 @@ impl PumpfunInterface {
         let token_pubkey = Pubkey::from_str(token_address)
             .map_err(|e| PumpfunBundlerError::InvalidMetadata(e.to_string()))?;
 
-        let rpc_client = RpcClient::new(self.get_rpc_url(network));
+        let rpc_client = crate::http_client::rpc_client(self.get_rpc_url(network));
         let recent_blockhash = rpc_client.get_latest_blockhash()
             .map_err(|e| PumpfunBundlerError::BundleConstruction(e.to_string()))?;
 
@@ -3,23 +3,32 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
-use aes_gcm::{Aes256Gcm, Key, Nonce, aead::KeyInit, aead::Aead};
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::{self, Aead, KeyInit, Payload};
+use aes_gcm::aead::consts::{U12, U16};
+use aes_gcm::aead::generic_array::typenum::Unsigned;
+use aes::Aes256;
+use ccm::Ccm;
+use chacha20poly1305::ChaCha20Poly1305;
+use hkdf::Hkdf;
 use argon2::{Argon2, password_hash::{PasswordHasher, SaltString, Output}};
-use pbkdf2::Pbkdf2;
-use scrypt::{ScryptParams, scrypt};
+use scrypt::{scrypt, Params as ScryptParams};
 use rand::rngs::OsRng;
 use rand::RngCore;
 use hmac::{Hmac, Mac};
-use sha2::{Sha256, Sha512};
-use sha3::{Sha3_256, Sha3_512};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Sha3_256;
 use blake3::Hasher as Blake3Hasher;
 use zeroize::{Zeroize, ZeroizeOnDrop};
-use ring::digest;
 use crossbeam::channel::{Sender, Receiver};
 use once_cell::sync::Lazy;
 use dashmap::DashMap;
 use chrono::{DateTime, Utc};
-use anyhow::{Result, Context};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use anyhow::Result;
+use thiserror::Error;
+
+use crate::address_lists::{AddressLists, ListKind};
 
 /// Security error types
 #[derive(Error, Debug)]
@@ -34,6 +43,8 @@ pub enum SecurityError {
     RateLimitExceeded,
     #[error("Transaction validation failed: {0}")]
     TransactionValidation(String),
+    #[error("Blocked by policy '{policy}': {reason}")]
+    PolicyViolation { policy: String, reason: String },
     #[error("Secure memory operation failed: {0}")]
     SecureMemory(String),
     #[error("Certificate verification failed: {0}")]
@@ -42,6 +53,8 @@ pub enum SecurityError {
     KeyRotation(String),
     #[error("Backup/Recovery failed: {0}")]
     BackupRecovery(String),
+    #[error("Key not loaded: {0}")]
+    KeyNotLoaded(String),
 }
 
 impl From<SecurityError> for String {
@@ -50,12 +63,41 @@ impl From<SecurityError> for String {
     }
 }
 
+/// Marks `encrypt_data` output that names its cipher; older output is bare AES-GCM `nonce || ciphertext`
+pub const CIPHERTEXT_MAGIC: &[u8; 3] = b"LSC";
+
+/// Layout version following `CIPHERTEXT_MAGIC`
+const CIPHERTEXT_VERSION: u8 = 1;
+
+/// Nonce length of ciphertexts written before the prefix existed
+const LEGACY_NONCE_LENGTH: usize = 12;
+
+/// HKDF expand context, so keys derived here differ from other uses of the same input
+const HKDF_INFO: &[u8] = b"lesinki-wallet-key";
+
+/// scrypt block size; with r = 8 a cost of N uses N KiB of memory
+const SCRYPT_BLOCK_SIZE: u32 = 8;
+
+/// Calibration never goes above this Argon2 memory cost (1 GiB, in KiB)
+pub(crate) const MAX_CALIBRATED_MEMORY_COST: u32 = 1024 * 1024;
+
+/// Calibration never goes above this many Argon2 passes
+pub(crate) const MAX_CALIBRATED_TIME_COST: u32 = 10;
+
+/// Calibration never uses more lanes than this, however many cores the machine has
+pub(crate) const MAX_CALIBRATED_PARALLELISM: u32 = 4;
+
+/// AES-256-CCM with a 16-byte tag and 12-byte nonce
+type Aes256Ccm = Ccm<Aes256, U16, U12>;
+
 /// Enhanced encryption parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptionParams {
     pub algorithm: EncryptionAlgorithm,
     pub key_derivation: KeyDerivationMethod,
+    /// PBKDF2 rounds
     pub iterations: u32,
+    /// Memory in KiB for Argon2, and for scrypt rounded down to a power of two
     pub memory_cost: u32,
     pub time_cost: u32,
     pub parallelism: u32,
@@ -80,16 +122,50 @@ impl Default for EncryptionParams {
     }
 }
 
-/// Encryption algorithms
+/// Argon2id parameters picked by `SecurityManager::calibrate_argon2`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfCalibration {
+    pub params: EncryptionParams,
+    /// Measured time of one derivation with `params`
+    pub elapsed_ms: u64,
+    pub target_ms: u64,
+}
+
+/// Encryption algorithms
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EncryptionAlgorithm {
     Aes256Gcm,
     Aes256Ccm,
     ChaCha20Poly1305,
 }
 
+impl EncryptionAlgorithm {
+    /// Identifier in the `encrypt_data` ciphertext prefix
+    pub fn id(&self) -> u8 {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm => 1,
+            EncryptionAlgorithm::ChaCha20Poly1305 => 2,
+            EncryptionAlgorithm::Aes256Ccm => 3,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(EncryptionAlgorithm::Aes256Gcm),
+            2 => Some(EncryptionAlgorithm::ChaCha20Poly1305),
+            3 => Some(EncryptionAlgorithm::Aes256Ccm),
+            _ => None,
+        }
+    }
+
+    /// All three ciphers are used with 96-bit nonces
+    pub fn nonce_length(&self) -> usize {
+        12
+    }
+}
+
 /// Key derivation methods
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KeyDerivationMethod {
     Argon2id,
     Argon2i,
@@ -103,8 +179,11 @@ pub enum KeyDerivationMethod {
 #[derive(ZeroizeOnDrop)]
 pub struct SecureKey {
     pub key_material: Vec<u8>,
+    #[zeroize(skip)]
     pub derivation_method: KeyDerivationMethod,
+    #[zeroize(skip)]
     pub created_at: DateTime<Utc>,
+    #[zeroize(skip)]
     pub last_accessed: DateTime<Utc>,
     pub access_count: u64,
     pub key_version: u32,
@@ -185,11 +264,29 @@ impl Default for RateLimitConfig {
 #[derive(Debug, Clone)]
 pub struct CertificatePinning {
     pub enabled: bool,
+    /// Hosts the wallet's own endpoints may be served from
     pub allowed_domains: Vec<String>,
+    /// Base64 SHA-256 digests of the SubjectPublicKeyInfo a host may present, as in HPKP;
+    /// a host without pins only has to be allowed and chain to a trusted root
     pub public_key_pins: HashMap<String, Vec<String>>,
     pub expiry_checking: bool,
 }
 
+impl CertificatePinning {
+    /// Whether connections to `domain` are checked and reported; `allowlisted_only` is set
+    /// for the wallet's own endpoints, other hosts are only checked when they have pins
+    pub fn applies_to(&self, domain: &str, allowlisted_only: bool) -> bool {
+        self.enabled && (allowlisted_only || self.public_key_pins.get(domain).is_some_and(|pins| !pins.is_empty()))
+    }
+
+    /// Pin of a DER certificate: the base64 SHA-256 digest of its SubjectPublicKeyInfo
+    pub fn spki_pin(certificate: &[u8]) -> Result<String, SecurityError> {
+        let (_, certificate) = x509_parser::parse_x509_certificate(certificate)
+            .map_err(|e| SecurityError::CertificateVerification(format!("Unreadable certificate: {}", e)))?;
+        Ok(BASE64.encode(SecureHash::sha256(certificate.public_key().raw)))
+    }
+}
+
 impl Default for CertificatePinning {
     fn default() -> Self {
         let mut pins = HashMap::new();
@@ -204,6 +301,11 @@ impl Default for CertificatePinning {
                 "api.testnet.solana.com".to_string(),
                 "quote-api.jup.ag".to_string(),
                 "token.jup.ag".to_string(),
+                "api.jup.ag".to_string(),
+                "mainnet.block-engine.jito.wtf".to_string(),
+                "api.github.com".to_string(),
+                "github.com".to_string(),
+                "objects.githubusercontent.com".to_string(),
             ],
             public_key_pins: pins,
             expiry_checking: true,
@@ -222,11 +324,6 @@ pub struct SecurityManager {
     transaction_tracker: Arc<DashMap<String, (u64, Instant)>>,
     banned_addresses: Arc<DashMap<String, DateTime<Utc>>>,
     
-    // Transaction security
-    whitelist: Arc<DashMap<String, bool>>,
-    blacklist: Arc<DashMap<String, bool>>,
-    transaction_history: Arc<DashMap<String, Vec<DateTime<Utc>>>>,
-    
     // Monitoring and logging
     security_events: Sender<SecurityEvent>,
     
@@ -237,7 +334,7 @@ pub struct SecurityManager {
 }
 
 /// Security event types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SecurityEvent {
     KeyAccessed { key_id: String, timestamp: DateTime<Utc> },
     TransactionAttempt { from: String, to: String, amount: u64, timestamp: DateTime<Utc> },
@@ -247,6 +344,31 @@ pub enum SecurityEvent {
     KeyRotated { key_id: String, old_version: u32, new_version: u32, timestamp: DateTime<Utc> },
 }
 
+impl SecurityEvent {
+    /// Name of the event type, as filtered on in the audit log
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::KeyAccessed { .. } => "key_accessed",
+            Self::TransactionAttempt { .. } => "transaction_attempt",
+            Self::RateLimitTriggered { .. } => "rate_limit_triggered",
+            Self::SecurityViolation { .. } => "security_violation",
+            Self::CertificatePinned { .. } => "certificate_pinned",
+            Self::KeyRotated { .. } => "key_rotated",
+        }
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Self::KeyAccessed { timestamp, .. }
+            | Self::TransactionAttempt { timestamp, .. }
+            | Self::RateLimitTriggered { timestamp, .. }
+            | Self::SecurityViolation { timestamp, .. }
+            | Self::CertificatePinned { timestamp, .. }
+            | Self::KeyRotated { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
 impl SecurityManager {
     /// Create new security manager
     pub fn new(security_events: Sender<SecurityEvent>) -> Self {
@@ -256,9 +378,6 @@ impl SecurityManager {
             request_tracker: Arc::new(DashMap::new()),
             transaction_tracker: Arc::new(DashMap::new()),
             banned_addresses: Arc::new(DashMap::new()),
-            whitelist: Arc::new(DashMap::new()),
-            blacklist: Arc::new(DashMap::new()),
-            transaction_history: Arc::new(DashMap::new()),
             security_events,
             encryption_params: EncryptionParams::default(),
             rate_limit_config: RateLimitConfig::default(),
@@ -266,6 +385,21 @@ impl SecurityManager {
         }
     }
 
+    /// Replace the certificate pinning policy
+    pub fn with_certificate_pinning(mut self, certificate_pinning: CertificatePinning) -> Self {
+        self.certificate_pinning = certificate_pinning;
+        self
+    }
+
+    pub fn certificate_pinning(&self) -> &CertificatePinning {
+        &self.certificate_pinning
+    }
+
+    /// Current encryption parameters used for new ciphertexts
+    pub fn encryption_params(&self) -> &EncryptionParams {
+        &self.encryption_params
+    }
+
     /// Enhanced key derivation with multiple algorithms
     pub fn derive_key(
         &self,
@@ -273,22 +407,41 @@ impl SecurityManager {
         salt: &[u8],
         method: KeyDerivationMethod,
     ) -> Result<Vec<u8>, SecurityError> {
-        match method {
+        let params = EncryptionParams {
+            key_derivation: method,
+            ..self.encryption_params.clone()
+        };
+        self.derive_key_with_params(password, salt, &params)
+    }
+
+    /// Key derivation with explicit parameters, e.g. the ones recorded in a keystore header
+    pub fn derive_key_with_params(
+        &self,
+        password: &str,
+        salt: &[u8],
+        params: &EncryptionParams,
+    ) -> Result<Vec<u8>, SecurityError> {
+        match params.key_derivation {
             KeyDerivationMethod::Argon2id | KeyDerivationMethod::Argon2i | KeyDerivationMethod::Argon2d => {
+                let argon2_params = argon2::Params::new(
+                    params.memory_cost,
+                    params.time_cost,
+                    params.parallelism,
+                    Some(params.key_length as usize),
+                ).map_err(|e| SecurityError::KeyDerivation(e.to_string()))?;
+
                 let argon2 = Argon2::new(
-                    match method {
-                        KeyDerivationMethod::Argon2id => argon2::Variant::Argon2id,
-                        KeyDerivationMethod::Argon2i => argon2::Variant::Argon2i,
-                        KeyDerivationMethod::Argon2d => argon2::Variant::Argon2d,
-                        _ => argon2::Variant::Argon2id,
+                    match params.key_derivation {
+                        KeyDerivationMethod::Argon2id => argon2::Algorithm::Argon2id,
+                        KeyDerivationMethod::Argon2i => argon2::Algorithm::Argon2i,
+                        KeyDerivationMethod::Argon2d => argon2::Algorithm::Argon2d,
+                        _ => argon2::Algorithm::Argon2id,
                     },
                     argon2::Version::V0x13,
-                    self.encryption_params.time_cost,
-                    self.encryption_params.memory_cost,
-                    self.encryption_params.parallelism,
+                    argon2_params,
                 );
 
-                let mut key = vec![0u8; self.encryption_params.key_length as usize];
+                let mut key = vec![0u8; params.key_length as usize];
                 argon2.hash_password_into(password.as_bytes(), salt, &mut key)
                     .map_err(|e| SecurityError::KeyDerivation(e.to_string()))?;
                 
@@ -296,87 +449,248 @@ impl SecurityManager {
             }
             
             KeyDerivationMethod::PBKDF2 => {
-                let mut key = vec![0u8; self.encryption_params.key_length as usize];
-                Pbkdf2::hash_password_into(password.as_bytes(), salt, self.encryption_params.iterations, &mut key)
-                    .map_err(|e| SecurityError::KeyDerivation(e.to_string()))?;
+                if params.iterations == 0 {
+                    return Err(SecurityError::KeyDerivation("PBKDF2 needs at least one iteration".to_string()));
+                }
+                let mut key = vec![0u8; params.key_length as usize];
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, params.iterations, &mut key);
                 Ok(key)
             }
             
             KeyDerivationMethod::Scrypt => {
-                let params = ScryptParams::new(
-                    14, // log_n
-                    8,  // r
-                    1,  // p
-                );
-                let mut key = vec![0u8; self.encryption_params.key_length as usize];
-                scrypt(password.as_bytes(), salt, &params, &mut key)
+                // 128 * r * N bytes of memory: with r = 8, N is the memory cost in KiB
+                let log_n = params.memory_cost.max(2).ilog2() as u8;
+                let scrypt_params = ScryptParams::new(log_n, SCRYPT_BLOCK_SIZE, params.parallelism)
+                    .map_err(|e| SecurityError::KeyDerivation(e.to_string()))?;
+                let mut key = vec![0u8; params.key_length as usize];
+                scrypt(password.as_bytes(), salt, &scrypt_params, &mut key)
                     .map_err(|e| SecurityError::KeyDerivation(e.to_string()))?;
                 Ok(key)
             }
             
             KeyDerivationMethod::HKDF => {
-                // Implementation for HKDF would go here
-                Err(SecurityError::KeyDerivation("HKDF not yet implemented".to_string()))
+                // No work factor: only suitable when the input is already high-entropy key material
+                let mut key = vec![0u8; params.key_length as usize];
+                Hkdf::<Sha256>::new(Some(salt), password.as_bytes())
+                    .expand(HKDF_INFO, &mut key)
+                    .map_err(|e| SecurityError::KeyDerivation(e.to_string()))?;
+                Ok(key)
             }
         }
     }
 
-    /// Enhanced encryption with AEAD
-    pub fn encrypt_data(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, SecurityError> {
-        let algorithm = self.encryption_params.algorithm.clone();
-        
-        match algorithm {
-            EncryptionAlgorithm::Aes256Gcm => {
-                if key.len() != 32 {
-                    return Err(SecurityError::InvalidKeyMaterial("Key must be 32 bytes for AES-256".to_string()));
-                }
+    /// Pick the strongest Argon2id parameters whose derivation takes at most `target` here
+    ///
+    /// Memory is doubled first, as it is what makes Argon2 expensive to attack on
+    /// GPUs, then passes are added. Each step is timed with a real derivation. The
+    /// result is never weaker than `EncryptionParams::default()`, even on a machine
+    /// too slow to reach that within `target`.
+    pub fn calibrate_argon2(&self, target: Duration) -> Result<KdfCalibration, SecurityError> {
+        let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get() as u32);
+        let mut params = EncryptionParams {
+            key_derivation: KeyDerivationMethod::Argon2id,
+            parallelism: cores.min(MAX_CALIBRATED_PARALLELISM),
+            ..EncryptionParams::default()
+        };
+        let salt = self.generate_salt();
+        let mut elapsed = self.time_derivation(&params, &salt)?;
+
+        while params.memory_cost * 2 <= MAX_CALIBRATED_MEMORY_COST && elapsed * 2 <= target {
+            let candidate = EncryptionParams { memory_cost: params.memory_cost * 2, ..params.clone() };
+            let candidate_elapsed = self.time_derivation(&candidate, &salt)?;
+            if candidate_elapsed > target {
+                break;
+            }
+            (params, elapsed) = (candidate, candidate_elapsed);
+        }
 
-                let key: &Key<Aes256Gcm> = key.into();
-                let cipher = Aes256Gcm::new(key);
-                
-                // Generate secure nonce
-                let mut nonce_bytes = [0u8; 12];
-                OsRng.fill_bytes(&mut nonce_bytes);
-                let nonce = Nonce::from_slice(&nonce_bytes);
-
-                let ciphertext = cipher.encrypt(nonce, data)
-                    .map_err(|e| SecurityError::Encryption(e.to_string()))?;
-
-                // Return nonce + ciphertext
-                let mut result = nonce_bytes.to_vec();
-                result.extend(ciphertext);
-                Ok(result)
+        while params.time_cost < MAX_CALIBRATED_TIME_COST && elapsed + elapsed / params.time_cost <= target {
+            let candidate = EncryptionParams { time_cost: params.time_cost + 1, ..params.clone() };
+            let candidate_elapsed = self.time_derivation(&candidate, &salt)?;
+            if candidate_elapsed > target {
+                break;
             }
-            _ => Err(SecurityError::Encryption("Algorithm not implemented".to_string()))
+            (params, elapsed) = (candidate, candidate_elapsed);
         }
+
+        Ok(KdfCalibration {
+            params,
+            elapsed_ms: elapsed.as_millis() as u64,
+            target_ms: target.as_millis() as u64,
+        })
+    }
+
+    fn time_derivation(&self, params: &EncryptionParams, salt: &[u8]) -> Result<Duration, SecurityError> {
+        let started = Instant::now();
+        let mut key = self.derive_key_with_params("calibration", salt, params)?;
+        key.zeroize();
+        Ok(started.elapsed())
     }
 
-    /// Enhanced decryption
+    /// Enhanced encryption with AEAD, using the configured algorithm
+    pub fn encrypt_data(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        self.encrypt_data_with_aad(key, data, &[])
+    }
+
+    /// Encrypt with associated data that must be given again to decrypt
+    pub fn encrypt_data_with_aad(&self, key: &[u8], data: &[u8], aad: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        self.encrypt_data_with(&self.encryption_params.algorithm, key, data, aad)
+    }
+
+    /// Encrypt as `magic || version || cipher id || nonce || ciphertext`
+    pub fn encrypt_data_with(
+        &self,
+        algorithm: &EncryptionAlgorithm,
+        key: &[u8],
+        data: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, SecurityError> {
+        let nonce = self.generate_random_bytes(algorithm.nonce_length());
+        let ciphertext = self.seal(algorithm, key, &nonce, data, aad)?;
+
+        let mut result = Vec::with_capacity(CIPHERTEXT_MAGIC.len() + 2 + nonce.len() + ciphertext.len());
+        result.extend_from_slice(CIPHERTEXT_MAGIC);
+        result.push(CIPHERTEXT_VERSION);
+        result.push(algorithm.id());
+        result.extend(nonce);
+        result.extend(ciphertext);
+        Ok(result)
+    }
+
+    /// Decrypt `encrypt_data` output, taking the cipher from its prefix
+    ///
+    /// Output without a prefix is the older bare AES-GCM `nonce || ciphertext`.
     pub fn decrypt_data(&self, key: &[u8], encrypted_data: &[u8]) -> Result<Vec<u8>, SecurityError> {
-        if encrypted_data.len() < 12 {
+        self.decrypt_data_with_aad(key, encrypted_data, &[])
+    }
+
+    /// Decrypt `encrypt_data_with_aad` output; fails unless `aad` is what it was encrypted with
+    pub fn decrypt_data_with_aad(&self, key: &[u8], encrypted_data: &[u8], aad: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        let prefixed = Self::split_prefixed(encrypted_data)
+            .map(|(algorithm, nonce, ciphertext)| self.open(&algorithm, key, nonce, ciphertext, aad));
+
+        match prefixed {
+            Some(Ok(plaintext)) => Ok(plaintext),
+            // A legacy nonce can start with the magic bytes by chance
+            Some(Err(e)) => self.decrypt_legacy(key, encrypted_data, aad).map_err(|_| e),
+            None => self.decrypt_legacy(key, encrypted_data, aad),
+        }
+    }
+
+    /// Cipher, nonce and ciphertext of prefixed `encrypt_data` output
+    fn split_prefixed(encrypted_data: &[u8]) -> Option<(EncryptionAlgorithm, &[u8], &[u8])> {
+        let rest = encrypted_data.strip_prefix(CIPHERTEXT_MAGIC.as_slice())?;
+        let (&[version, id], rest) = rest.split_first_chunk::<2>()?;
+        if version != CIPHERTEXT_VERSION {
+            return None;
+        }
+        let algorithm = EncryptionAlgorithm::from_id(id)?;
+        if rest.len() < algorithm.nonce_length() {
+            return None;
+        }
+        let (nonce, ciphertext) = rest.split_at(algorithm.nonce_length());
+        Some((algorithm, nonce, ciphertext))
+    }
+
+    fn decrypt_legacy(&self, key: &[u8], encrypted_data: &[u8], aad: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        if encrypted_data.len() < LEGACY_NONCE_LENGTH {
             return Err(SecurityError::Encryption("Invalid encrypted data length".to_string()));
         }
+        let (nonce, ciphertext) = encrypted_data.split_at(LEGACY_NONCE_LENGTH);
+        self.open(&EncryptionAlgorithm::Aes256Gcm, key, nonce, ciphertext, aad)
+    }
 
-        let algorithm = self.encryption_params.algorithm.clone();
-        
+    /// AEAD encryption with a caller-supplied nonce and associated data
+    pub fn seal(
+        &self,
+        algorithm: &EncryptionAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        data: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, SecurityError> {
+        let payload = Payload { msg: data, aad };
         match algorithm {
-            EncryptionAlgorithm::Aes256Gcm => {
-                if key.len() != 32 {
-                    return Err(SecurityError::InvalidKeyMaterial("Key must be 32 bytes for AES-256".to_string()));
-                }
-
-                let key: &Key<Aes256Gcm> = key.into();
-                let cipher = Aes256Gcm::new(key);
-                let nonce = Nonce::from_slice(&encrypted_data[..12]);
-                let ciphertext = &encrypted_data[12..];
+            EncryptionAlgorithm::Aes256Gcm => aead_apply::<Aes256Gcm>(key, nonce, payload, true),
+            EncryptionAlgorithm::ChaCha20Poly1305 => aead_apply::<ChaCha20Poly1305>(key, nonce, payload, true),
+            EncryptionAlgorithm::Aes256Ccm => aead_apply::<Aes256Ccm>(key, nonce, payload, true),
+        }
+    }
 
-                cipher.decrypt(nonce, ciphertext)
-                    .map_err(|e| SecurityError::Encryption(e.to_string()))
-            }
-            _ => Err(SecurityError::Encryption("Algorithm not implemented".to_string()))
+    /// AEAD decryption with a caller-supplied nonce and associated data
+    pub fn open(
+        &self,
+        algorithm: &EncryptionAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        ciphertext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, SecurityError> {
+        let payload = Payload { msg: ciphertext, aad };
+        match algorithm {
+            EncryptionAlgorithm::Aes256Gcm => aead_apply::<Aes256Gcm>(key, nonce, payload, false),
+            EncryptionAlgorithm::ChaCha20Poly1305 => aead_apply::<ChaCha20Poly1305>(key, nonce, payload, false),
+            EncryptionAlgorithm::Aes256Ccm => aead_apply::<Aes256Ccm>(key, nonce, payload, false),
         }
     }
 
+    /// Keep decrypted key material in memory until it is removed or cleared
+    pub fn store_key(&self, key_id: &str, key_material: Vec<u8>, method: KeyDerivationMethod) {
+        self.master_keys.insert(key_id.to_string(), SecureKey::new(key_material, method));
+    }
+
+    /// Run `f` with the key material stored under `key_id`
+    pub fn with_key<F, R>(&self, key_id: &str, f: F) -> Result<R, SecurityError>
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let mut entry = self.master_keys.get_mut(key_id)
+            .ok_or_else(|| SecurityError::KeyNotLoaded(key_id.to_string()))?;
+        let result = entry.value_mut().access(f);
+        drop(entry);
+
+        self.log_event(SecurityEvent::KeyAccessed {
+            key_id: key_id.to_string(),
+            timestamp: Utc::now(),
+        });
+        Ok(result)
+    }
+
+    /// Set the version of the key stored under `key_id`, e.g. after the secret wrapping it was rotated
+    pub fn set_key_version(&self, key_id: &str, key_version: u32) -> Result<(), SecurityError> {
+        let mut entry = self.master_keys.get_mut(key_id)
+            .ok_or_else(|| SecurityError::KeyNotLoaded(key_id.to_string()))?;
+        entry.value_mut().key_version = key_version;
+        Ok(())
+    }
+
+    /// Version of the key stored under `key_id`
+    pub fn key_version(&self, key_id: &str) -> Option<u32> {
+        self.master_keys.get(key_id).map(|entry| entry.value().key_version)
+    }
+
+    /// Whether key material is stored under `key_id`
+    pub fn has_key(&self, key_id: &str) -> bool {
+        self.master_keys.contains_key(key_id)
+    }
+
+    /// Most recent access to any stored key, `None` when no keys are stored
+    pub fn last_key_access(&self) -> Option<DateTime<Utc>> {
+        self.master_keys.iter()
+            .map(|entry| entry.value().last_accessed)
+            .max()
+    }
+
+    /// Remove and zeroize a single key
+    pub fn remove_key(&self, key_id: &str) {
+        self.master_keys.remove(key_id);
+    }
+
+    /// Remove and zeroize every stored key
+    pub fn clear_keys(&self) {
+        self.master_keys.clear();
+    }
+
     /// Rate limiting check
     pub fn check_rate_limit(&self, client_id: &str, request_type: &str) -> Result<(), SecurityError> {
         let now = Instant::now();
@@ -422,58 +736,37 @@ impl SecurityManager {
         Ok(())
     }
 
-    /// Transaction validation with security checks
+    /// Check the addresses of a transaction against temporary bans and the address lists
+    ///
+    /// `security.whitelist_enabled` switches on allowlist-only mode and
+    /// `security.blacklist_enabled` the denylist. Amount and volume limits are
+    /// enforced by the policy engine in `policy`.
     pub fn validate_transaction(
         &self,
         from_address: &str,
         to_address: &str,
-        amount: u64,
+        security: &TransactionSecurity,
+        lists: &AddressLists,
     ) -> Result<(), SecurityError> {
-        // Check if addresses are banned
-        if self.banned_addresses.get(from_address).is_some() || 
-           self.banned_addresses.get(to_address).is_some() {
-            return Err(SecurityError::TransactionValidation(
-                "Transaction from/to banned address".to_string()
-            ));
-        }
-
-        // Check whitelist if enabled
-        if self.certificate_pinning.whitelist_enabled {
-            if self.whitelist.get(to_address).is_none() {
-                return Err(SecurityError::TransactionValidation(
-                    "Destination address not in whitelist".to_string()
-                ));
+        let violation = |policy: &str, reason: String| SecurityError::PolicyViolation {
+            policy: policy.to_string(),
+            reason,
+        };
+
+        for address in [from_address, to_address] {
+            if self.banned_addresses.get(address).is_some_and(|expiry| *expiry > Utc::now()) {
+                return Err(violation("banned_address", format!("{} is temporarily banned", address)));
             }
         }
 
-        // Check blacklist
-        if self.blacklist.get(to_address).is_some() {
-            return Err(SecurityError::TransactionValidation(
-                "Destination address is blacklisted".to_string()
-            ));
+        let now = Utc::now();
+        if security.whitelist_enabled && lists.find(ListKind::Allow, to_address, now).is_none() {
+            return Err(violation("allowlist", format!("{} is not on the allowlist", to_address)));
         }
 
-        // Check amount limits
-        if amount > 0 {
-            // Daily volume check
-            let today = Utc::now().date_naive();
-            let key = format!("{}_{}", from_address, today);
-            
-            if let Some(mut entry) = self.transaction_history.get_mut(&key) {
-                let history = entry.value_mut();
-                let today_transactions: Vec<_> = history.iter()
-                    .filter(|&dt| dt.date_naive() == today)
-                    .collect();
-                
-                let daily_volume: u64 = today_transactions.iter().count() as u64 * amount;
-                if daily_volume > 0 {
-                    // In a real implementation, you'd calculate actual volume
-                    if daily_volume > 0 {
-                        return Err(SecurityError::TransactionValidation(
-                            "Daily transaction limit exceeded".to_string()
-                        ));
-                    }
-                }
+        if security.blacklist_enabled {
+            if let Some(entry) = lists.find(ListKind::Deny, to_address, now) {
+                return Err(violation("denylist", format!("{} is on the denylist: {}", to_address, entry.reason)));
             }
         }
 
@@ -495,24 +788,44 @@ impl SecurityManager {
     }
 
     /// Certificate pinning validation
-    pub fn validate_certificate(&self, domain: &str) -> Result<bool, SecurityError> {
-        if !self.certificate_pinning.enabled {
+    ///
+    /// Checks the end-entity certificate `domain` presented, already verified to chain to a
+    /// trusted root, against the allowlist and the domain's public key pins. Hosts outside
+    /// the allowlist are only accepted when `allowlisted_only` is false, e.g. for NFT
+    /// metadata; pins apply either way. Every check that applies is logged as a
+    /// `CertificatePinned` event.
+    pub fn validate_certificate(&self, domain: &str, certificate: &[u8], allowlisted_only: bool) -> Result<bool, SecurityError> {
+        if !self.certificate_pinning.applies_to(domain, allowlisted_only) {
             return Ok(true);
         }
 
-        if !self.certificate_pinning.allowed_domains.contains(&domain.to_string()) {
+        let result = self.check_certificate_pins(domain, certificate, allowlisted_only);
+        self.log_event(SecurityEvent::CertificatePinned {
+            domain: domain.to_string(),
+            valid: result.is_ok(),
+            timestamp: Utc::now(),
+        });
+        result.map(|()| true)
+    }
+
+    fn check_certificate_pins(&self, domain: &str, certificate: &[u8], allowlisted_only: bool) -> Result<(), SecurityError> {
+        if allowlisted_only && !self.certificate_pinning.allowed_domains.iter().any(|allowed| allowed == domain) {
             return Err(SecurityError::CertificateVerification(
                 format!("Domain not allowed: {}", domain)
             ));
         }
 
-        // In a real implementation, you would:
-        // 1. Extract the certificate chain
-        // 2. Get the public key pins for the domain
-        // 3. Verify the certificate against the pins
-        // 4. Check certificate expiry if enabled
-
-        Ok(true)
+        let pins = match self.certificate_pinning.public_key_pins.get(domain) {
+            Some(pins) if !pins.is_empty() => pins,
+            _ => return Ok(()),
+        };
+        let pin = CertificatePinning::spki_pin(certificate)?;
+        if !pins.contains(&pin) {
+            return Err(SecurityError::CertificateVerification(
+                format!("Public key of {} matches none of its pins", domain)
+            ));
+        }
+        Ok(())
     }
 
     /// Key rotation management
@@ -529,6 +842,37 @@ impl SecurityManager {
         }
     }
 
+    /// Next scheduled rotation of `key_id`
+    pub fn next_key_rotation(&self, key_id: &str) -> Option<DateTime<Utc>> {
+        self.key_rotation_schedule.get(key_id).map(|date| *date.value())
+    }
+
+    /// Take `key_id` off the rotation schedule
+    pub fn cancel_key_rotation(&self, key_id: &str) {
+        self.key_rotation_schedule.remove(key_id);
+    }
+
+    /// Record a committed rotation of `key_id` and schedule the next one, if any
+    pub fn complete_key_rotation(
+        &self,
+        key_id: &str,
+        old_version: u32,
+        new_version: u32,
+        next_rotation: Option<DateTime<Utc>>,
+    ) {
+        match next_rotation {
+            Some(date) => self.schedule_key_rotation(key_id, date),
+            None => self.cancel_key_rotation(key_id),
+        }
+
+        self.log_event(SecurityEvent::KeyRotated {
+            key_id: key_id.to_string(),
+            old_version,
+            new_version,
+            timestamp: Utc::now(),
+        });
+    }
+
     /// Log security event
     pub fn log_event(&self, event: SecurityEvent) {
         let _ = self.security_events.send(event);
@@ -540,16 +884,6 @@ impl SecurityManager {
         self.banned_addresses.insert(address.to_string(), expiry);
     }
 
-    /// Add address to whitelist
-    pub fn add_to_whitelist(&self, address: &str) {
-        self.whitelist.insert(address.to_string(), true);
-    }
-
-    /// Add address to blacklist
-    pub fn add_to_blacklist(&self, address: &str) {
-        self.blacklist.insert(address.to_string(), true);
-    }
-
     /// Clean up expired entries
     pub fn cleanup_expired(&self) {
         let now = Utc::now();
@@ -580,12 +914,12 @@ impl SecureHash {
 
     /// SHA-256 hash
     pub fn sha256(data: &[u8]) -> Vec<u8> {
-        digest::digest(&digest::SHA256, data).as_ref().to_vec()
+        Sha256::digest(data).to_vec()
     }
 
     /// SHA-512 hash
     pub fn sha512(data: &[u8]) -> Vec<u8> {
-        digest::digest(&digest::SHA512, data).as_ref().to_vec()
+        Sha512::digest(data).to_vec()
     }
 
     /// SHA3-256 hash
@@ -599,19 +933,45 @@ impl SecureHash {
     pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, SecurityError> {
         let mac = Hmac::<Sha256>::new_from_slice(key)
             .map_err(|_| SecurityError::Encryption("Invalid HMAC key".to_string()))?;
-        let result = mac.chain(data).finalize();
+        let result = mac.chain_update(data).finalize();
         Ok(result.into_bytes().to_vec())
     }
 }
 
+/// Encrypt or decrypt `payload` with cipher `C`, checking key and nonce sizes first
+fn aead_apply<C: Aead + KeyInit>(
+    key: &[u8],
+    nonce: &[u8],
+    payload: Payload<'_, '_>,
+    encrypt: bool,
+) -> Result<Vec<u8>, SecurityError> {
+    let cipher = C::new_from_slice(key)
+        .map_err(|_| SecurityError::InvalidKeyMaterial(format!("Key must be {} bytes", C::KeySize::USIZE)))?;
+    if nonce.len() != C::NonceSize::USIZE {
+        return Err(SecurityError::InvalidKeyMaterial(format!("Nonce must be {} bytes", C::NonceSize::USIZE)));
+    }
+
+    let nonce = aead::Nonce::<C>::from_slice(nonce);
+    let result = if encrypt { cipher.encrypt(nonce, payload) } else { cipher.decrypt(nonce, payload) };
+    result.map_err(|e| SecurityError::Encryption(e.to_string()))
+}
+
+/// Events logged by the global security manager, waiting to be written to the audit log
+static SECURITY_EVENTS: Lazy<(Sender<SecurityEvent>, Receiver<SecurityEvent>)> = Lazy::new(crossbeam::channel::unbounded);
+
 /// Global security manager instance
 static SECURITY_MANAGER: Lazy<Arc<SecurityManager>> = Lazy::new(|| {
-    // Create a channel for security events (for now, a dummy sender)
-    let (tx, _rx) = crossbeam::channel::unbounded();
-    Arc::new(SecurityManager::new(tx))
+    Arc::new(SecurityManager::new(SECURITY_EVENTS.0.clone()))
 });
 
+/// Receiving end of the global security manager's events
+pub fn security_events() -> &'static Receiver<SecurityEvent> {
+    &SECURITY_EVENTS.1
+}
+
 /// Get global security manager
 pub fn get_security_manager() -> &'static Arc<SecurityManager> {
     &SECURITY_MANAGER
-}
\ No newline at end of file
+}
+
+include!("security_tests.rs");
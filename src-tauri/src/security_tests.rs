@@ -84,6 +84,101 @@ mod tests {
         assert_eq!(original_data, &decrypted[..]);
     }
 
+    fn kat_params(key_derivation: KeyDerivationMethod) -> EncryptionParams {
+        EncryptionParams {
+            key_derivation,
+            iterations: 1000,
+            memory_cost: 64,
+            time_cost: 2,
+            ..EncryptionParams::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_key_derivation_known_answers() {
+        let (tx, _rx) = channel::unbounded();
+        let manager = SecurityManager::new(tx);
+
+        let password = "correct horse battery staple";
+        let salt = b"lesinki-kat-salt";
+        let cases = [
+            (KeyDerivationMethod::Argon2id, "e9f44874f8e1d29dc1ca4b99b72e58e4ea1fc49764c00bb03a4be2530e5c7f4f"),
+            (KeyDerivationMethod::Argon2i, "15d44b58f9359f1a62d0fb50307ae1262882d71a5aa7fa157ee80e478c1750f8"),
+            (KeyDerivationMethod::Argon2d, "b55eb03c43207d029f6a921ba6dbcfd6e4f91a04074563c4738641de3663730b"),
+            (KeyDerivationMethod::PBKDF2, "c803ae9d8a9756df1ab708787ad18bc947e6d353e0666a79c7ae29ebc0828ed4"),
+            (KeyDerivationMethod::HKDF, "a55c46f12b7fe0b6b7518707a6cbc487d29218d76642770215e3ec9d78c1bce8"),
+        ];
+        for (method, expected) in cases {
+            let key = manager.derive_key_with_params(password, salt, &kat_params(method.clone()))
+                .expect("Key derivation should succeed");
+            assert_eq!(hex::encode(key), expected, "{:?}", method);
+        }
+
+        // scrypt N = 1024, r = 8, p = 1
+        let params = EncryptionParams { memory_cost: 1024, ..kat_params(KeyDerivationMethod::Scrypt) };
+        let key = manager.derive_key_with_params(password, salt, &params).unwrap();
+        assert_eq!(hex::encode(key), "38fa9cef3ef723d640ee763016a135f3f0bdcfd1e79996ab51deeaa945c1f29c");
+    }
+
+    #[tokio::test]
+    async fn test_cipher_known_answers() {
+        let (tx, _rx) = channel::unbounded();
+        let manager = SecurityManager::new(tx);
+
+        let key: Vec<u8> = (0..32).collect();
+        let nonce: Vec<u8> = (100..112).collect();
+        let plaintext = b"lesinki known answer";
+        let cases = [
+            (EncryptionAlgorithm::Aes256Gcm, "247ead0f17823fbe550c309fb4450b9331b563780b03d9f8d8f158cf2e62c511dd16c767"),
+            (EncryptionAlgorithm::ChaCha20Poly1305, "5874c8a3f6ce1f7290b07a089ca9b9fd03b1f746409a5bec026141aaec90a9beafe54b93"),
+            (EncryptionAlgorithm::Aes256Ccm, "5d85db1218c6b1d379dff147ff41ec4bd085a0cad5a52dacf8c9fa63264939f5984ba2f3"),
+        ];
+        for (algorithm, expected) in cases {
+            let ciphertext = manager.seal(&algorithm, &key, &nonce, plaintext, &[]).unwrap();
+            assert_eq!(hex::encode(&ciphertext), expected, "{:?}", algorithm);
+            assert_eq!(manager.open(&algorithm, &key, &nonce, &ciphertext, &[]).unwrap(), plaintext);
+            assert!(manager.open(&algorithm, &key, &nonce, &ciphertext, b"other").is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefixed_ciphertext_roundtrip() {
+        let (tx, _rx) = channel::unbounded();
+        let manager = SecurityManager::new(tx);
+        let key = manager.generate_random_bytes(32);
+
+        for algorithm in [EncryptionAlgorithm::Aes256Gcm, EncryptionAlgorithm::ChaCha20Poly1305, EncryptionAlgorithm::Aes256Ccm] {
+            let encrypted = manager.encrypt_data_with(&algorithm, &key, b"secret", &[]).unwrap();
+            assert_eq!(&encrypted[..3], CIPHERTEXT_MAGIC);
+            assert_eq!(encrypted[4], algorithm.id());
+            assert_eq!(manager.decrypt_data(&key, &encrypted).unwrap(), b"secret");
+        }
+
+        // Bare nonce || ciphertext from before the prefix
+        let nonce = manager.generate_random_bytes(12);
+        let mut legacy = nonce.clone();
+        legacy.extend(manager.seal(&EncryptionAlgorithm::Aes256Gcm, &key, &nonce, b"secret", &[]).unwrap());
+        assert_eq!(manager.decrypt_data(&key, &legacy).unwrap(), b"secret");
+
+        let wrong_key = manager.generate_random_bytes(32);
+        let encrypted = manager.encrypt_data(&key, b"secret").unwrap();
+        assert!(manager.decrypt_data(&wrong_key, &encrypted).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_calibration_never_goes_below_defaults() {
+        let (tx, _rx) = channel::unbounded();
+        let manager = SecurityManager::new(tx);
+
+        // A target no machine can meet keeps the default costs
+        let calibration = manager.calibrate_argon2(Duration::from_millis(1)).unwrap();
+        let defaults = EncryptionParams::default();
+        assert_eq!(calibration.params.key_derivation, KeyDerivationMethod::Argon2id);
+        assert_eq!(calibration.params.memory_cost, defaults.memory_cost);
+        assert_eq!(calibration.params.time_cost, defaults.time_cost);
+        assert_eq!(calibration.target_ms, 1);
+    }
+
     #[tokio::test]
     async fn test_rate_limiting() {
         let (tx, _rx) = channel::unbounded();
@@ -103,57 +198,75 @@ mod tests {
         }
     }
 
+    fn listed(address: &str, reason: &str) -> crate::address_lists::AddressEntry {
+        crate::address_lists::AddressEntry {
+            address: address.to_string(),
+            reason: reason.to_string(),
+            added_at: Utc::now(),
+            expires_at: None,
+            source: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_transaction_validation() {
         let (tx, _rx) = channel::unbounded();
         let manager = SecurityManager::new(tx);
         
-        // Valid transaction should pass
-        let result = manager.validate_transaction(
-            "valid_from_address",
-            "valid_to_address", 
-            1000
+        let security = TransactionSecurity { blacklist_enabled: true, ..TransactionSecurity::default() };
+        let mut lists = AddressLists::default();
+        let (from, to, denied) = (
+            solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+            solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+            solana_sdk::pubkey::Pubkey::new_unique().to_string(),
         );
+
+        // Valid transaction should pass
+        let result = manager.validate_transaction(&from, &to, &security, &lists);
         assert!(result.is_ok());
+
+        // Repeat transactions are not a volume violation
+        assert!(manager.validate_transaction(&from, &to, &security, &lists).is_ok());
         
-        // Add to blacklist
-        manager.add_to_blacklist("banned_address");
+        // Add to denylist
+        lists.add(ListKind::Deny, listed(&denied, "sanctioned")).unwrap();
         
-        // Transaction to blacklisted address should fail
-        let result = manager.validate_transaction(
-            "valid_from_address",
-            "banned_address",
-            1000
-        );
-        assert!(result.is_err());
+        // Transaction to a denied address should fail, naming the list and the reason
+        let result = manager.validate_transaction(&from, &denied, &security, &lists);
+        assert!(matches!(result, Err(SecurityError::PolicyViolation { ref policy, ref reason }) if policy == "denylist" && reason.ends_with("sanctioned")));
+
+        // Unless the denylist is switched off
+        let off = TransactionSecurity { blacklist_enabled: false, ..security };
+        assert!(manager.validate_transaction(&from, &denied, &off, &lists).is_ok());
     }
 
     #[tokio::test]
-    async fn test_whitelist_blacklist() {
+    async fn test_allowlist_only_mode() {
         let (tx, _rx) = channel::unbounded();
         let manager = SecurityManager::new(tx);
         
-        // Enable whitelist
-        // Note: In real implementation, you would set the whitelist_enabled flag
+        // Enable allowlist-only mode
+        let security = TransactionSecurity { whitelist_enabled: true, ..TransactionSecurity::default() };
+        let mut lists = AddressLists::default();
+        let from = solana_sdk::pubkey::Pubkey::new_unique().to_string();
+        let (trusted, malicious, unknown) = (
+            solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+            solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+            solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+        );
         
-        // Add to whitelist
-        manager.add_to_whitelist("trusted_address");
-        manager.add_to_blacklist("malicious_address");
+        lists.add(ListKind::Allow, listed(&trusted, "exchange deposit")).unwrap();
+        lists.add(ListKind::Allow, listed(&malicious, "typo")).unwrap();
+        lists.add(ListKind::Deny, listed(&malicious, "drainer")).unwrap();
         
-        // Test whitelist functionality
-        let result1 = manager.validate_transaction(
-            "from_address",
-            "trusted_address",
-            1000
-        );
-        // Should pass if whitelist is enabled and address is in whitelist
+        assert!(manager.validate_transaction(&from, &trusted, &security, &lists).is_ok());
+
+        let unlisted = manager.validate_transaction(&from, &unknown, &security, &lists);
+        assert!(matches!(unlisted, Err(SecurityError::PolicyViolation { ref policy, .. }) if policy == "allowlist"));
         
-        let result2 = manager.validate_transaction(
-            "from_address", 
-            "malicious_address",
-            1000
-        );
-        assert!(result2.is_err());
+        // The denylist wins over the allowlist
+        let result = manager.validate_transaction(&from, &malicious, &security, &lists);
+        assert!(matches!(result, Err(SecurityError::PolicyViolation { ref policy, .. }) if policy == "denylist"));
     }
 
     #[test]
@@ -223,7 +336,9 @@ mod tests {
         assert_eq!(security.max_amount, u64::MAX);
         assert_eq!(security.daily_limit, u64::MAX);
         assert_eq!(security.transaction_timeout, Duration::from_secs(300));
-        assert_eq!(security.simulation_required, true);
+        assert!(!security.whitelist_enabled);
+        assert!(!security.blacklist_enabled);
+        assert!(security.simulation_required);
     }
 
     #[test]
@@ -277,6 +392,33 @@ mod tests {
         assert!(!manager.check_key_rotation("test_key"));
     }
 
+    #[tokio::test]
+    async fn test_completed_rotation_is_logged_and_rescheduled() {
+        let (tx, rx) = channel::unbounded();
+        let manager = SecurityManager::new(tx);
+
+        manager.schedule_key_rotation("vault:test", chrono::Utc::now() - chrono::Duration::days(1));
+        assert!(manager.check_key_rotation("vault:test"));
+
+        manager.store_key("vault:test", vec![7u8; 32], KeyDerivationMethod::Argon2id);
+        assert_eq!(manager.key_version("vault:test"), Some(1));
+        manager.set_key_version("vault:test", 2).unwrap();
+        assert_eq!(manager.key_version("vault:test"), Some(2));
+
+        let next = chrono::Utc::now() + chrono::Duration::days(90);
+        manager.complete_key_rotation("vault:test", 1, 2, Some(next));
+        assert!(!manager.check_key_rotation("vault:test"));
+        assert_eq!(manager.next_key_rotation("vault:test"), Some(next));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SecurityEvent::KeyRotated { old_version: 1, new_version: 2, .. })
+        ));
+
+        manager.complete_key_rotation("vault:test", 2, 3, None);
+        assert_eq!(manager.next_key_rotation("vault:test"), None);
+        assert!(manager.set_key_version("missing", 2).is_err());
+    }
+
     #[tokio::test]
     async fn test_address_banning() {
         let (tx, _rx) = channel::unbounded();
@@ -289,9 +431,10 @@ mod tests {
         let result = manager.validate_transaction(
             "suspicious_address",
             "recipient_address",
-            1000
+            &TransactionSecurity::default(),
+            &AddressLists::default(),
         );
-        assert!(result.is_err());
+        assert!(matches!(result, Err(SecurityError::PolicyViolation { ref policy, .. }) if policy == "banned_address"));
     }
 
     #[tokio::test]
@@ -312,10 +455,10 @@ mod tests {
     #[test]
     fn test_secure_key_zeroization() {
         let key_material = vec![0xFF; 32];
-        let secure_key = SecureKey::new(key_material, KeyDerivationMethod::Argon2id);
+        let mut secure_key = SecureKey::new(key_material, KeyDerivationMethod::Argon2id);
         
         // Key should be accessible
-        let _ = secure_key.access(|key| {
+        secure_key.access(|key| {
             assert_eq!(key.len(), 32);
         });
         
@@ -327,57 +470,56 @@ mod tests {
 #[cfg(test)]
 mod property_tests {
     use super::*;
+    use crossbeam::channel;
     use proptest::prelude::*;
 
     proptest! {
+        // Every case runs full-cost Argon2id derivations
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
         #[test]
         fn test_encryption_decryption_roundtrip(
-            data in prop::collection::vec(prop::num::u8::ANY, 0..1000),
+            data in prop::collection::vec(any::<u8>(), 0..1000),
             password in "[a-zA-Z0-9]{1,100}",
         ) {
-            tokio_test::block_on(async {
-                let (tx, _rx) = channel::unbounded();
-                let manager = SecurityManager::new(tx);
-                
-                let salt = manager.generate_salt();
-                let key = manager.derive_key(&password, &salt, KeyDerivationMethod::Argon2id)
-                    .expect("Key derivation should succeed");
-                
-                let encrypted = manager.encrypt_data(&key, &data)
-                    .expect("Encryption should succeed");
-                
-                let decrypted = manager.decrypt_data(&key, &encrypted)
-                    .expect("Decryption should succeed");
-                
-                prop_assert_eq!(data, decrypted);
-            });
+            let (tx, _rx) = channel::unbounded();
+            let manager = SecurityManager::new(tx);
+            
+            let salt = manager.generate_salt();
+            let key = manager.derive_key(&password, &salt, KeyDerivationMethod::Argon2id)
+                .expect("Key derivation should succeed");
+            
+            let encrypted = manager.encrypt_data(&key, &data)
+                .expect("Encryption should succeed");
+            
+            let decrypted = manager.decrypt_data(&key, &encrypted)
+                .expect("Decryption should succeed");
+            
+            prop_assert_eq!(data, decrypted);
         }
 
         #[test]
         fn test_different_passwords_different_keys(
-            password1 in "[a-zA-Z0-9]{1,100}",
-            password2 in "[a-zA-Z0-9]{1,100}",
-        ) prop::strategy::filter(|p| p.0 != p.1) => |(password1, password2)| {
-            tokio_test::block_on(async {
-                let (tx, _rx) = channel::unbounded();
-                let manager = SecurityManager::new(tx);
-                
-                let salt = manager.generate_salt();
-                let key1 = manager.derive_key(&password1, &salt, KeyDerivationMethod::Argon2id)
-                    .expect("Key derivation should succeed");
-                let key2 = manager.derive_key(&password2, &salt, KeyDerivationMethod::Argon2id)
-                    .expect("Key derivation should succeed");
-                
-                // Different passwords should produce different keys (with very high probability)
-                if key1 == key2 {
-                    panic!("Different passwords produced same key");
-                }
-            });
+            (password1, password2) in ("[a-zA-Z0-9]{1,100}", "[a-zA-Z0-9]{1,100}")
+                .prop_filter("passwords must differ", |(a, b)| a != b),
+        ) {
+            let (tx, _rx) = channel::unbounded();
+            let manager = SecurityManager::new(tx);
+            
+            let salt = manager.generate_salt();
+            let key1 = manager.derive_key(&password1, &salt, KeyDerivationMethod::Argon2id)
+                .expect("Key derivation should succeed");
+            let key2 = manager.derive_key(&password2, &salt, KeyDerivationMethod::Argon2id)
+                .expect("Key derivation should succeed");
+            
+            // Different passwords should produce different keys (with very high probability)
+            prop_assert_ne!(key1, key2);
         }
 
         #[test]
         fn test_random_bytes_different(
-            size in 1..1024usize,
+            // Long enough that two draws never collide in practice
+            size in 16..1024usize,
         ) {
             let (tx, _rx) = channel::unbounded();
             let manager = SecurityManager::new(tx);
@@ -390,4 +532,4 @@ mod property_tests {
             prop_assert_ne!(bytes1, bytes2);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use argon2::password_hash::SaltString;
+use rand::rngs::OsRng;
+use solana_sdk::signature::Signer;
+use zeroize::Zeroizing;
+
+use crate::derivation::PathTemplate;
+use crate::keystore::SecretKdf;
+use crate::mnemonic::{self, MnemonicLanguage};
+use crate::{decrypt_data, derive_encryption_key, encrypt_data, new_wallet_id, WalletError};
+
+/// Longest seed name; derived wallets are named "<seed> #<account>" within the wallet name limit
+pub const MAX_SEED_NAME_LEN: usize = 48;
+
+/// A seed phrase kept in the vault, from which accounts are derived on demand
+///
+/// The phrase, and the BIP39 passphrase when the user chose to store it, are
+/// encrypted under the vault password. `fingerprint` is the address of account 0,
+/// which tells a wrong passphrase apart from a right one: BIP39 accepts any
+/// passphrase and silently derives a different wallet.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SeedEntry {
+    pub id: String,
+    pub name: String,
+    pub language: MnemonicLanguage,
+    pub path_template: PathTemplate,
+    pub encrypted_mnemonic: Vec<u8>,
+    /// Empty when the seed has no passphrase or it is not stored
+    #[serde(default)]
+    pub encrypted_passphrase: Vec<u8>,
+    pub salt: Vec<u8>,
+    /// Key derivation the secrets were sealed with; `None` for seeds sealed before it was recorded
+    #[serde(default)]
+    pub kdf: Option<SecretKdf>,
+    pub uses_passphrase: bool,
+    pub fingerprint: String,
+    /// Account index `derive_next_account` derives next
+    pub next_account: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Decrypted secrets of a seed entry
+pub struct SeedSecrets {
+    pub phrase: Zeroizing<String>,
+    /// Only set when the passphrase is stored in the vault
+    pub passphrase: Option<Zeroizing<String>>,
+}
+
+/// A seed entry as shown to the frontend, without ciphertext
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SeedSummary {
+    pub id: String,
+    pub name: String,
+    pub language: MnemonicLanguage,
+    pub path_template: PathTemplate,
+    pub uses_passphrase: bool,
+    pub passphrase_stored: bool,
+    pub next_account: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SeedEntry {
+    /// Encrypt a seed phrase for the vault; its passphrase is kept only with `store_passphrase`
+    pub fn new(
+        name: &str,
+        phrase: &str,
+        passphrase: Option<&str>,
+        store_passphrase: bool,
+        language: Option<MnemonicLanguage>,
+        path_template: PathTemplate,
+        password: &str,
+        kdf: &SecretKdf,
+    ) -> Result<Self, WalletError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_SEED_NAME_LEN {
+            return Err(WalletError::InvalidInput("Seed name must be 1 to 48 characters".to_string()));
+        }
+
+        let mnemonic = mnemonic::parse(phrase, language).map_err(|e| WalletError::Bip39(e.to_string()))?;
+        let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+        let seed = Zeroizing::new(mnemonic.to_seed(passphrase.unwrap_or("")));
+
+        let mut entry = Self {
+            id: new_wallet_id(),
+            name: name.to_string(),
+            language: mnemonic.language().into(),
+            fingerprint: account_zero(&path_template, &seed[..])?,
+            path_template,
+            encrypted_mnemonic: vec![],
+            encrypted_passphrase: vec![],
+            salt: vec![],
+            kdf: None,
+            uses_passphrase: passphrase.is_some(),
+            next_account: 0,
+            created_at: Utc::now(),
+        };
+
+        let secrets = SeedSecrets {
+            phrase: Zeroizing::new(mnemonic.to_string()),
+            passphrase: passphrase
+                .filter(|_| store_passphrase)
+                .map(|passphrase| Zeroizing::new(passphrase.to_string())),
+        };
+        entry.seal(&secrets, password, kdf)?;
+        Ok(entry)
+    }
+
+    /// Encrypt `secrets` into this entry under `password` with a fresh salt
+    pub fn seal(&mut self, secrets: &SeedSecrets, password: &str, kdf: &SecretKdf) -> Result<(), WalletError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let key = Zeroizing::new(derive_encryption_key(password, salt.as_str().as_bytes(), kdf)?);
+
+        self.encrypted_mnemonic = encrypt_data(&key, secrets.phrase.as_bytes())?;
+        self.encrypted_passphrase = match &secrets.passphrase {
+            Some(passphrase) => encrypt_data(&key, passphrase.as_bytes())?,
+            None => vec![],
+        };
+        self.salt = salt.as_str().as_bytes().to_vec();
+        self.kdf = Some(kdf.clone());
+        Ok(())
+    }
+
+    /// Decrypt the phrase and stored passphrase
+    pub fn open(&self, password: &str) -> Result<SeedSecrets, WalletError> {
+        let legacy = SecretKdf::legacy();
+        let key = Zeroizing::new(derive_encryption_key(password, &self.salt, self.kdf.as_ref().unwrap_or(&legacy))?);
+        let decrypt = |data: &[u8]| -> Result<Zeroizing<String>, WalletError> {
+            let plaintext = Zeroizing::new(decrypt_data(&key, data).map_err(|_| WalletError::AuthFailed)?);
+            std::str::from_utf8(&plaintext)
+                .map(|text| Zeroizing::new(text.to_string()))
+                .map_err(|_| WalletError::Encryption(format!("Seed {} is corrupted", self.id)))
+        };
+
+        Ok(SeedSecrets {
+            phrase: decrypt(&self.encrypted_mnemonic)?,
+            passphrase: if self.encrypted_passphrase.is_empty() {
+                None
+            } else {
+                Some(decrypt(&self.encrypted_passphrase)?)
+            },
+        })
+    }
+
+    /// The BIP39 seed, using the stored passphrase or else `passphrase`
+    pub fn seed(&self, password: &str, passphrase: Option<&str>) -> Result<Zeroizing<[u8; 64]>, WalletError> {
+        let secrets = self.open(password)?;
+        let passphrase = match (&secrets.passphrase, passphrase) {
+            (Some(stored), _) => stored.as_str(),
+            (None, Some(given)) => given,
+            (None, None) if !self.uses_passphrase => "",
+            (None, None) => {
+                return Err(WalletError::InvalidInput(format!("Seed '{}' needs its passphrase", self.name)));
+            }
+        };
+
+        let seed = mnemonic::to_seed(&secrets.phrase, Some(passphrase), Some(self.language))
+            .map_err(|e| WalletError::Bip39(e.to_string()))?;
+        if account_zero(&self.path_template, &seed[..])? != self.fingerprint {
+            return Err(WalletError::InvalidInput(format!("Wrong passphrase for seed '{}'", self.name)));
+        }
+        Ok(seed)
+    }
+
+    /// Copy of this entry encrypted under a new password
+    pub fn reencrypt(&self, old_password: &str, new_password: &str, kdf: &SecretKdf) -> Result<Self, WalletError> {
+        let secrets = self.open(old_password)?;
+        let mut entry = self.clone();
+        entry.seal(&secrets, new_password, kdf)?;
+        Ok(entry)
+    }
+
+    pub fn summary(&self) -> SeedSummary {
+        SeedSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            language: self.language,
+            path_template: self.path_template.clone(),
+            uses_passphrase: self.uses_passphrase,
+            passphrase_stored: !self.encrypted_passphrase.is_empty(),
+            next_account: self.next_account,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Address of account 0 under `template`
+fn account_zero(template: &PathTemplate, seed: &[u8]) -> Result<String, WalletError> {
+    let keypair = template.derive_keypair(seed, 0).map_err(|e| WalletError::InvalidInput(e.to_string()))?;
+    Ok(keypair.pubkey().to_string())
+}
+
+include!("seeds_tests.rs");
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ABANDON: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_seed_roundtrip_and_fingerprint() {
+        let entry = SeedEntry::new("main", ABANDON, None, false, None, PathTemplate::Bip44Change, "vault-password", &SecretKdf::legacy()).unwrap();
+        assert_eq!(entry.fingerprint, "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+        assert_eq!(entry.language, MnemonicLanguage::English);
+        assert!(!entry.uses_passphrase);
+
+        let secrets = entry.open("vault-password").unwrap();
+        assert_eq!(secrets.phrase.as_str(), ABANDON);
+        assert!(secrets.passphrase.is_none());
+        assert!(matches!(entry.open("wrong-password"), Err(WalletError::AuthFailed)));
+
+        let seed = entry.seed("vault-password", None).unwrap();
+        assert_eq!(&seed[..], &mnemonic::to_seed(ABANDON, None, None).unwrap()[..]);
+    }
+
+    #[test]
+    fn test_unstored_passphrase_is_required_and_checked() {
+        let entry = SeedEntry::new("hidden", ABANDON, Some("TREZOR"), false, None, PathTemplate::Bip44Change, "vault-password", &SecretKdf::legacy()).unwrap();
+        assert!(entry.uses_passphrase);
+        assert!(entry.encrypted_passphrase.is_empty());
+        assert!(entry.summary().uses_passphrase && !entry.summary().passphrase_stored);
+
+        assert!(matches!(entry.seed("vault-password", None), Err(WalletError::InvalidInput(_))));
+        assert!(matches!(entry.seed("vault-password", Some("trezor")), Err(WalletError::InvalidInput(_))));
+        assert!(entry.seed("vault-password", Some("TREZOR")).is_ok());
+    }
+
+    #[test]
+    fn test_stored_passphrase_survives_reencrypt() {
+        let entry = SeedEntry::new("stored", ABANDON, Some("TREZOR"), true, None, PathTemplate::Bip44, "old-password", &SecretKdf::legacy()).unwrap();
+        let cheap = SecretKdf {
+            kdf_params: crate::keystore::KdfParams { memory_cost: 256, time_cost: 1, ..crate::keystore::KdfParams::legacy() },
+            ..SecretKdf::legacy()
+        };
+        let moved = entry.reencrypt("old-password", "new-password", &cheap).unwrap();
+
+        assert_eq!(moved.id, entry.id);
+        assert_eq!(moved.kdf, Some(cheap));
+        assert_eq!(moved.fingerprint, entry.fingerprint);
+        assert!(moved.open("old-password").is_err());
+        assert_eq!(moved.open("new-password").unwrap().passphrase.as_deref().map(|p| p.as_str()), Some("TREZOR"));
+        assert!(moved.seed("new-password", None).is_ok());
+    }
+}
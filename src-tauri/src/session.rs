@@ -0,0 +1,254 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use solana_sdk::signature::Keypair;
+use zeroize::Zeroize;
+
+use crate::keyformat::keypair_from_secret;
+use crate::security::{get_security_manager, KeyDerivationMethod};
+use crate::{Wallet, WalletError, Wallets};
+
+/// Key id under which the unlocked vault's password is kept, so the vault can be rewritten
+const SESSION_PASSWORD_KEY: &str = "session:password";
+
+/// Tauri event emitted when a vault is unlocked, with a `VaultUnlockEvent` payload
+pub const VAULT_UNLOCKED_EVENT: &str = "vault-unlocked";
+
+/// Tauri event emitted when a vault is locked, with a `VaultLockEvent` payload
+pub const VAULT_LOCKED_EVENT: &str = "vault-locked";
+
+/// How often the background task checks the session against its auto-lock policy
+pub const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Key id of a wallet's decrypted private key
+pub fn wallet_key_id(public_key: &str) -> String {
+    format!("wallet:{}", public_key)
+}
+
+/// When an unlocked vault locks itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoLockPolicy {
+    /// Lock after this many seconds without any key use; `None` disables the idle timeout
+    pub idle_timeout_secs: Option<u64>,
+    /// Lock this many seconds after unlocking, regardless of activity; `None` disables the limit
+    pub max_session_secs: Option<u64>,
+}
+
+impl Default for AutoLockPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: Some(5 * 60),
+            max_session_secs: Some(8 * 60 * 60),
+        }
+    }
+}
+
+impl AutoLockPolicy {
+    pub fn validate(&self) -> Result<(), WalletError> {
+        if self.idle_timeout_secs == Some(0) || self.max_session_secs == Some(0) {
+            return Err(WalletError::InvalidInput("Auto-lock timeouts must be greater than zero".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Why a session unlocked at `unlocked_at` and last used at `last_activity` must lock at `now`, if it must
+    pub fn expiry(
+        &self,
+        unlocked_at: DateTime<Utc>,
+        last_activity: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<LockReason> {
+        let elapsed = |since: DateTime<Utc>| (now - since).num_seconds().max(0) as u64;
+
+        if self.max_session_secs.is_some_and(|max| elapsed(unlocked_at) >= max) {
+            return Some(LockReason::MaxLifetime);
+        }
+        if self.idle_timeout_secs.is_some_and(|idle| elapsed(last_activity) >= idle) {
+            return Some(LockReason::IdleTimeout);
+        }
+        None
+    }
+}
+
+/// Why a vault session was locked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockReason {
+    Manual,
+    IdleTimeout,
+    MaxLifetime,
+    ProfileChanged,
+}
+
+/// Payload of `VAULT_LOCKED_EVENT`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultLockEvent {
+    pub profile: String,
+    pub reason: LockReason,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Payload of `VAULT_UNLOCKED_EVENT`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultUnlockEvent {
+    pub profile: String,
+    pub unlocked_at: DateTime<Utc>,
+    pub policy: AutoLockPolicy,
+}
+
+/// An unlocked vault
+///
+/// Only public wallet data lives here. Decrypted private keys and the vault
+/// password are held by the `SecurityManager` as `SecureKey`s and are zeroized
+/// when the session is locked.
+#[derive(Clone)]
+pub struct VaultSession {
+    pub profile: String,
+    pub wallets: Wallets,
+    pub unlocked_at: DateTime<Utc>,
+    pub policy: AutoLockPolicy,
+}
+
+impl VaultSession {
+    /// Decrypt every wallet's private key and keep it in the security manager
+    pub fn unlock(
+        profile: &str,
+        wallets: Wallets,
+        password: &str,
+        policy: AutoLockPolicy,
+    ) -> Result<Self, WalletError> {
+        Self::load_keys(&wallets, password)?;
+
+        Ok(Self {
+            profile: profile.to_string(),
+            wallets,
+            unlocked_at: Utc::now(),
+            policy,
+        })
+    }
+
+    fn load_keys(wallets: &Wallets, password: &str) -> Result<(), WalletError> {
+        let security_manager = get_security_manager();
+        security_manager.clear_keys();
+
+        for wallet in wallets.wallets.iter().filter(|wallet| !wallet.is_watch_only()) {
+            if let Err(e) = Self::load_wallet_key(wallet, password, wallets.key_version) {
+                security_manager.clear_keys();
+                return Err(e);
+            }
+        }
+
+        security_manager.store_key(SESSION_PASSWORD_KEY, password.as_bytes().to_vec(), KeyDerivationMethod::Argon2id);
+        let _ = security_manager.set_key_version(SESSION_PASSWORD_KEY, wallets.key_version);
+        Ok(())
+    }
+
+    /// Decrypt one wallet's private key into the security manager, as key version `key_version`
+    pub fn load_wallet_key(wallet: &Wallet, password: &str, key_version: u32) -> Result<(), WalletError> {
+        let private_key = wallet.open_secret(password)?;
+
+        let security_manager = get_security_manager();
+        let key_id = wallet_key_id(&wallet.public_key);
+        security_manager.store_key(&key_id, private_key.to_vec(), KeyDerivationMethod::Argon2id);
+        let _ = security_manager.set_key_version(&key_id, key_version);
+        Ok(())
+    }
+
+    /// The session after its vault's secrets were re-wrapped as a new key version
+    ///
+    /// The decrypted keys themselves are unchanged, so they stay loaded and only
+    /// their version moves on; nor does this count as key use for auto-lock.
+    pub fn rewrapped(&self, wallets: Wallets) -> Result<Self, WalletError> {
+        let security_manager = get_security_manager();
+        let key_ids = wallets.wallets.iter()
+            .filter(|wallet| !wallet.is_watch_only())
+            .map(|wallet| wallet_key_id(&wallet.public_key))
+            .chain([SESSION_PASSWORD_KEY.to_string()]);
+        for key_id in key_ids {
+            security_manager.set_key_version(&key_id, wallets.key_version)
+                .map_err(|_| WalletError::VaultLocked)?;
+        }
+
+        Ok(Self {
+            wallets,
+            ..self.clone()
+        })
+    }
+
+    /// The session after a password change re-wrapped its vault's secrets under `password`
+    pub fn rewrapped_with_password(&self, wallets: Wallets, password: &str) -> Result<Self, WalletError> {
+        get_security_manager().store_key(SESSION_PASSWORD_KEY, password.as_bytes().to_vec(), KeyDerivationMethod::Argon2id);
+        self.rewrapped(wallets)
+    }
+
+    /// Zeroize all key material held for this session
+    pub fn lock(&self, reason: LockReason) -> VaultLockEvent {
+        get_security_manager().clear_keys();
+        log::info!("Vault '{}' locked: {:?}", self.profile, reason);
+
+        VaultLockEvent {
+            profile: self.profile.clone(),
+            reason,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Whether the session's key material is still loaded
+    pub fn is_unlocked(&self) -> bool {
+        get_security_manager().has_key(SESSION_PASSWORD_KEY)
+    }
+
+    /// Last time any session key was used, or the unlock time if none has been
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        get_security_manager()
+            .last_key_access()
+            .map_or(self.unlocked_at, |accessed| accessed.max(self.unlocked_at))
+    }
+
+    /// Why the session must lock at `now` under its policy, if it must
+    pub fn expiry(&self, now: DateTime<Utc>) -> Option<LockReason> {
+        self.policy.expiry(self.unlocked_at, self.last_activity(), now)
+    }
+
+    pub fn unlock_event(&self) -> VaultUnlockEvent {
+        VaultUnlockEvent {
+            profile: self.profile.clone(),
+            unlocked_at: self.unlocked_at,
+            policy: self.policy.clone(),
+        }
+    }
+
+    /// Find a wallet by id or public key
+    pub fn resolve(&self, wallet_id: &str) -> Result<&Wallet, WalletError> {
+        self.wallets.wallets.iter()
+            .find(|wallet| wallet.id == wallet_id || wallet.public_key == wallet_id)
+            .ok_or_else(|| WalletError::WalletNotFound(wallet_id.to_string()))
+    }
+
+    /// Signing keypair for a wallet, built from the key held by the security manager
+    ///
+    /// Watch-only wallets fail with `WalletError::WatchOnly`.
+    pub fn keypair(&self, wallet_id: &str) -> Result<Keypair, WalletError> {
+        let wallet = self.resolve(wallet_id)?;
+        wallet.ensure_can_sign()?;
+
+        get_security_manager()
+            .with_key(&wallet_key_id(&wallet.public_key), keypair_from_secret)
+            .map_err(|_| WalletError::VaultLocked)?
+            .map_err(|e| WalletError::Encryption(e.to_string()))
+    }
+
+    /// The vault password, for operations that need to rewrite the vault
+    pub fn password(&self) -> Result<String, WalletError> {
+        get_security_manager()
+            .with_key(SESSION_PASSWORD_KEY, |bytes| String::from_utf8(bytes.to_vec()))
+            .map_err(|_| WalletError::VaultLocked)?
+            .map_err(|e| {
+                let mut bytes = e.into_bytes();
+                bytes.zeroize();
+                WalletError::VaultLocked
+            })
+    }
+}
+
+include!("session_tests.rs");
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use chrono::Duration as ChronoDuration;
+    use crate::WalletKind;
+
+    fn policy(idle: Option<u64>, max: Option<u64>) -> AutoLockPolicy {
+        AutoLockPolicy {
+            idle_timeout_secs: idle,
+            max_session_secs: max,
+        }
+    }
+
+    #[test]
+    fn test_idle_timeout_expires_session() {
+        let unlocked_at = Utc::now();
+        let last_activity = unlocked_at + ChronoDuration::seconds(30);
+        let policy = policy(Some(60), None);
+
+        assert_eq!(policy.expiry(unlocked_at, last_activity, last_activity + ChronoDuration::seconds(59)), None);
+        assert_eq!(
+            policy.expiry(unlocked_at, last_activity, last_activity + ChronoDuration::seconds(60)),
+            Some(LockReason::IdleTimeout)
+        );
+    }
+
+    #[test]
+    fn test_max_lifetime_applies_despite_activity() {
+        let unlocked_at = Utc::now();
+        let now = unlocked_at + ChronoDuration::seconds(3600);
+        let policy = policy(Some(60), Some(3600));
+
+        assert_eq!(policy.expiry(unlocked_at, now, now), Some(LockReason::MaxLifetime));
+    }
+
+    #[test]
+    fn test_disabled_policy_never_expires() {
+        let unlocked_at = Utc::now();
+        let now = unlocked_at + ChronoDuration::days(30);
+
+        assert_eq!(policy(None, None).expiry(unlocked_at, unlocked_at, now), None);
+    }
+
+    #[test]
+    fn test_watch_only_wallet_cannot_sign() {
+        let now = Utc::now();
+        let mut wallets = Wallets::empty();
+        wallets.wallets.push(Wallet {
+            id: "cold".to_string(),
+            public_key: "11111111111111111111111111111111".to_string(),
+            kind: WalletKind::WatchOnly,
+            encrypted_private_key: vec![],
+            salt: vec![],
+            kdf: None,
+            balance: 0,
+            created_at: now,
+            last_updated: now,
+            network: "mainnet".to_string(),
+            metadata: Default::default(),
+            derivation_path: None,
+            seed_id: None,
+            secret_version: 0,
+        });
+        let session = VaultSession {
+            profile: "default".to_string(),
+            wallets,
+            unlocked_at: now,
+            policy: AutoLockPolicy::default(),
+        };
+
+        assert!(session.resolve("cold").is_ok());
+        assert!(matches!(session.keypair("cold"), Err(WalletError::WatchOnly(_))));
+    }
+
+    #[test]
+    fn test_zero_timeouts_rejected() {
+        assert!(policy(Some(0), None).validate().is_err());
+        assert!(policy(None, Some(0)).validate().is_err());
+        assert!(AutoLockPolicy::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Bits encoded by one word
+const RADIX_BITS: usize = 10;
+/// Identifier, extendable flag, iteration exponent, group and member fields
+const HEADER_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+/// Shortest share: 128-bit secret
+const MIN_SHARE_WORDS: usize = HEADER_WORDS + 13 + CHECKSUM_WORDS;
+const MAX_SHARE_COUNT: usize = 16;
+const MIN_SECRET_LEN: usize = 16;
+/// PBKDF2 iterations over all Feistel rounds at iteration exponent 0
+const BASE_ITERATION_COUNT: u32 = 10_000;
+const ROUND_COUNT: u8 = 4;
+const MAX_ITERATION_EXPONENT: u8 = 15;
+/// x-coordinates of the digest and secret in every polynomial
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_LEN: usize = 4;
+
+include!("slip39_wordlist.rs");
+
+/// SLIP-39 share errors
+#[derive(Error, Debug)]
+pub enum Slip39Error {
+    #[error("Invalid sharing scheme: {0}")]
+    InvalidConfig(String),
+    #[error("Invalid share: {0}")]
+    InvalidShare(String),
+    #[error("Share {0} has an invalid checksum")]
+    Checksum(usize),
+    #[error("Shares do not belong together: {0}")]
+    MixedShares(String),
+    #[error("Not enough shares: {0}")]
+    InsufficientShares(String),
+    #[error("Share digest mismatch; the shares are corrupted")]
+    Digest,
+}
+
+impl From<Slip39Error> for String {
+    fn from(err: Slip39Error) -> String {
+        err.to_string()
+    }
+}
+
+/// Member shares of one group: any `member_threshold` of `member_count` recover the group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupSpec {
+    pub member_threshold: u8,
+    pub member_count: u8,
+}
+
+/// Metadata every share carries in its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareInfo {
+    /// Random 15-bit id common to all shares of one split
+    pub identifier: u16,
+    pub extendable: bool,
+    pub iteration_exponent: u8,
+    pub group_index: u8,
+    pub group_threshold: u8,
+    pub group_count: u8,
+    pub member_index: u8,
+    pub member_threshold: u8,
+}
+
+/// The shares of one group, as produced by `split`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareGroup {
+    pub group_index: u8,
+    pub member_threshold: u8,
+    pub shares: Vec<String>,
+}
+
+/// A decoded share
+struct Share {
+    info: ShareInfo,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    fn parse(mnemonic: &str, position: usize) -> Result<Self, Slip39Error> {
+        let words = mnemonic.split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                WORDLIST.binary_search(&word.as_str())
+                    .map(|index| index as u16)
+                    .map_err(|_| Slip39Error::InvalidShare(format!("unknown word '{}' in share {}", word, position)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if words.len() < MIN_SHARE_WORDS {
+            return Err(Slip39Error::InvalidShare(format!("share {} has only {} words", position, words.len())));
+        }
+
+        let header = (words[0] as u64) << 30 | (words[1] as u64) << 20 | (words[2] as u64) << 10 | words[3] as u64;
+        let field = |offset: u32, bits: u32| ((header >> (40 - offset - bits)) & ((1 << bits) - 1)) as u16;
+        let info = ShareInfo {
+            identifier: field(0, 15),
+            extendable: field(15, 1) == 1,
+            iteration_exponent: field(16, 4) as u8,
+            group_index: field(20, 4) as u8,
+            group_threshold: field(24, 4) as u8 + 1,
+            group_count: field(28, 4) as u8 + 1,
+            member_index: field(32, 4) as u8,
+            member_threshold: field(36, 4) as u8 + 1,
+        };
+
+        if rs1024_polymod(customization(info.extendable), &words) != 1 {
+            return Err(Slip39Error::Checksum(position));
+        }
+        if info.group_threshold > info.group_count || info.group_index >= info.group_count {
+            return Err(Slip39Error::InvalidShare(format!("share {} has inconsistent group fields", position)));
+        }
+
+        let value = words_to_bytes(&words[HEADER_WORDS..words.len() - CHECKSUM_WORDS])
+            .ok_or_else(|| Slip39Error::InvalidShare(format!("share {} has invalid padding", position)))?;
+        Ok(Self { info, value })
+    }
+
+    fn to_mnemonic(&self) -> String {
+        let info = &self.info;
+        let header = (info.identifier as u64) << 25
+            | (info.extendable as u64) << 24
+            | (info.iteration_exponent as u64) << 20
+            | (info.group_index as u64) << 16
+            | ((info.group_threshold - 1) as u64) << 12
+            | ((info.group_count - 1) as u64) << 8
+            | (info.member_index as u64) << 4
+            | (info.member_threshold - 1) as u64;
+
+        let mut words: Vec<u16> = (0..HEADER_WORDS)
+            .map(|i| ((header >> (RADIX_BITS * (HEADER_WORDS - 1 - i))) & 0x3ff) as u16)
+            .collect();
+        words.extend(bytes_to_words(&self.value));
+
+        let checksum = rs1024_polymod(customization(info.extendable), &[&words[..], &[0u16; CHECKSUM_WORDS][..]].concat()) ^ 1;
+        words.extend((0..CHECKSUM_WORDS).map(|i| ((checksum >> (RADIX_BITS * (CHECKSUM_WORDS - 1 - i))) & 0x3ff) as u16));
+
+        words.iter().map(|&word| WORDLIST[word as usize]).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Header metadata of a share, after checking its checksum
+pub fn inspect(mnemonic: &str) -> Result<ShareInfo, Slip39Error> {
+    Ok(Share::parse(mnemonic, 0)?.info)
+}
+
+/// Split `master_secret` into groups of shares; any `group_threshold` recovered groups rebuild it
+///
+/// The secret is first encrypted with `passphrase`; recombining with a different
+/// passphrase yields a different secret rather than an error.
+pub fn split(
+    master_secret: &[u8],
+    passphrase: &str,
+    group_threshold: u8,
+    groups: &[GroupSpec],
+    iteration_exponent: u8,
+) -> Result<Vec<ShareGroup>, Slip39Error> {
+    if master_secret.len() < MIN_SECRET_LEN || master_secret.len() % 2 != 0 {
+        return Err(Slip39Error::InvalidConfig("secret must be an even number of bytes, at least 16".to_string()));
+    }
+    if groups.is_empty() || groups.len() > MAX_SHARE_COUNT {
+        return Err(Slip39Error::InvalidConfig("there must be 1 to 16 groups".to_string()));
+    }
+    if group_threshold == 0 || group_threshold as usize > groups.len() {
+        return Err(Slip39Error::InvalidConfig("group threshold must be between 1 and the number of groups".to_string()));
+    }
+    for group in groups {
+        if group.member_threshold == 0 || group.member_threshold > group.member_count || group.member_count as usize > MAX_SHARE_COUNT {
+            return Err(Slip39Error::InvalidConfig("member threshold must be between 1 and a member count of at most 16".to_string()));
+        }
+        if group.member_threshold == 1 && group.member_count > 1 {
+            return Err(Slip39Error::InvalidConfig("a 1-of-N group is just N copies; use 1-of-1".to_string()));
+        }
+    }
+    if iteration_exponent > MAX_ITERATION_EXPONENT {
+        return Err(Slip39Error::InvalidConfig("iteration exponent must be 15 or less".to_string()));
+    }
+    check_passphrase(passphrase)?;
+
+    let identifier = (OsRng.next_u32() & 0x7fff) as u16;
+    let encrypted = feistel(master_secret, passphrase.as_bytes(), iteration_exponent, identifier, true, true);
+    let group_shares = split_secret(group_threshold, groups.len() as u8, &encrypted);
+
+    let mut result = Vec::with_capacity(groups.len());
+    for ((group_index, group_secret), group) in group_shares.iter().zip(groups) {
+        let shares = split_secret(group.member_threshold, group.member_count, group_secret)
+            .into_iter()
+            .map(|(member_index, value)| {
+                Share {
+                    info: ShareInfo {
+                        identifier,
+                        extendable: true,
+                        iteration_exponent,
+                        group_index: *group_index,
+                        group_threshold,
+                        group_count: groups.len() as u8,
+                        member_index,
+                        member_threshold: group.member_threshold,
+                    },
+                    value,
+                }
+                .to_mnemonic()
+            })
+            .collect();
+
+        result.push(ShareGroup {
+            group_index: *group_index,
+            member_threshold: group.member_threshold,
+            shares,
+        });
+    }
+    Ok(result)
+}
+
+/// Recover the master secret from enough shares of one split
+///
+/// All shares must come from the same split. Extra shares beyond the thresholds
+/// are allowed as long as they agree.
+pub fn combine(mnemonics: &[String], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    check_passphrase(passphrase)?;
+    let shares = mnemonics.iter()
+        .enumerate()
+        .map(|(position, mnemonic)| Share::parse(mnemonic, position))
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = shares.first()
+        .ok_or_else(|| Slip39Error::InsufficientShares("no shares given".to_string()))?;
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in &shares {
+        let (a, b) = (&share.info, &first.info);
+        if (a.identifier, a.extendable, a.iteration_exponent) != (b.identifier, b.extendable, b.iteration_exponent) {
+            return Err(Slip39Error::MixedShares("shares come from different splits".to_string()));
+        }
+        if (a.group_threshold, a.group_count) != (b.group_threshold, b.group_count) {
+            return Err(Slip39Error::MixedShares("shares disagree on the group threshold".to_string()));
+        }
+        if share.value.len() != first.value.len() {
+            return Err(Slip39Error::MixedShares("shares have different lengths".to_string()));
+        }
+        groups.entry(a.group_index).or_default().push(share);
+    }
+
+    let mut group_shares = Vec::new();
+    for (group_index, members) in &groups {
+        let member_threshold = members[0].info.member_threshold;
+        let mut distinct: Vec<(u8, Zeroizing<Vec<u8>>)> = Vec::new();
+        for member in members {
+            if member.info.member_threshold != member_threshold {
+                return Err(Slip39Error::MixedShares(format!("group {} shares disagree on the member threshold", group_index)));
+            }
+            match distinct.iter().find(|(index, _)| *index == member.info.member_index) {
+                Some((_, value)) if *value != member.value => {
+                    return Err(Slip39Error::MixedShares(format!("group {} has two different shares {}", group_index, member.info.member_index)));
+                }
+                Some(_) => {}
+                None => distinct.push((member.info.member_index, member.value.clone())),
+            }
+        }
+
+        // Incomplete groups are ignored; enough complete groups may still be present
+        if distinct.len() >= member_threshold as usize {
+            distinct.truncate(member_threshold as usize);
+            group_shares.push((*group_index, recover_secret(member_threshold, &distinct)?));
+        }
+    }
+
+    let group_threshold = first.info.group_threshold;
+    if group_shares.len() < group_threshold as usize {
+        return Err(Slip39Error::InsufficientShares(format!(
+            "{} of {} required groups are complete", group_shares.len(), group_threshold
+        )));
+    }
+    group_shares.truncate(group_threshold as usize);
+
+    let encrypted = recover_secret(group_threshold, &group_shares)?;
+    let info = &first.info;
+    Ok(feistel(&encrypted, passphrase.as_bytes(), info.iteration_exponent, info.identifier, info.extendable, false))
+}
+
+/// SLIP-39 passphrases are printable ASCII
+fn check_passphrase(passphrase: &str) -> Result<(), Slip39Error> {
+    if !passphrase.bytes().all(|byte| (32..=126).contains(&byte)) {
+        return Err(Slip39Error::InvalidConfig("passphrase must be printable ASCII".to_string()));
+    }
+    Ok(())
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable { b"shamir_extendable" } else { b"shamir" }
+}
+
+/// Reed-Solomon checksum over GF(1024) from the SLIP-39 spec
+fn rs1024_polymod(customization: &[u8], words: &[u16]) -> u32 {
+    const GENERATOR: [u32; 10] = [
+        0xE0E040, 0x1C1C080, 0x3838100, 0x7070200, 0xE0E0009,
+        0x1C0C2412, 0x38086C24, 0x3090FC48, 0x21B1F890, 0x3F3F120,
+    ];
+    let values = customization.iter().map(|&byte| byte as u32).chain(words.iter().map(|&word| word as u32));
+
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 20;
+        checksum = (checksum & 0xFFFFF) << 10 ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+/// Big-endian bytes as 10-bit words, left-padded with zero bits
+fn bytes_to_words(bytes: &[u8]) -> Vec<u16> {
+    let bits = bytes.len() * 8;
+    let word_count = bits.div_ceil(RADIX_BITS);
+    let padding = word_count * RADIX_BITS - bits;
+
+    let mut words = vec![0u16; word_count];
+    for bit in 0..bits {
+        if bytes[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+            let position = bit + padding;
+            words[position / RADIX_BITS] |= 1 << (RADIX_BITS - 1 - position % RADIX_BITS);
+        }
+    }
+    words
+}
+
+/// Inverse of `bytes_to_words`; `None` if the padding is too long or not zero
+fn words_to_bytes(words: &[u16]) -> Option<Zeroizing<Vec<u8>>> {
+    let bits = words.len() * RADIX_BITS;
+    let padding = bits % 16;
+    if padding > 8 {
+        return None;
+    }
+
+    let bit_set = |position: usize| words[position / RADIX_BITS] & (1 << (RADIX_BITS - 1 - position % RADIX_BITS)) != 0;
+    if (0..padding).any(bit_set) {
+        return None;
+    }
+
+    let mut bytes = Zeroizing::new(vec![0u8; (bits - padding) / 8]);
+    for bit in 0..bytes.len() * 8 {
+        if bit_set(bit + padding) {
+            bytes[bit / 8] |= 0x80 >> (bit % 8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Exponent and logarithm tables of GF(256) with the Rijndael polynomial, generator 3
+const fn gf256_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut poly: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = poly as u8;
+        log[poly as usize] = i as u8;
+        poly ^= poly << 1;
+        if poly & 0x100 != 0 {
+            poly ^= 0x11B;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF256: ([u8; 255], [u8; 256]) = gf256_tables();
+
+/// Lagrange interpolation at `x` of the polynomial through `shares`, bytewise over GF(256)
+fn interpolate(shares: &[(u8, Zeroizing<Vec<u8>>)], x: u8) -> Zeroizing<Vec<u8>> {
+    if let Some((_, value)) = shares.iter().find(|(index, _)| *index == x) {
+        return value.clone();
+    }
+
+    let (exp, log) = &GF256;
+    let mut result = Zeroizing::new(vec![0u8; shares[0].1.len()]);
+    for (i, (xi, value)) in shares.iter().enumerate() {
+        let log_basis = shares.iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, (xj, _))| log[(x ^ xj) as usize] as i32 - log[(xi ^ xj) as usize] as i32)
+            .sum::<i32>()
+            .rem_euclid(255);
+
+        for (out, byte) in result.iter_mut().zip(value.iter()) {
+            if *byte != 0 {
+                *out ^= exp[((log[*byte as usize] as i32 + log_basis) % 255) as usize];
+            }
+        }
+    }
+    result
+}
+
+fn share_digest(random_part: &[u8], secret: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random_part).expect("HMAC accepts keys of any length");
+    mac.update(secret);
+    let mut digest = [0u8; DIGEST_LEN];
+    digest.copy_from_slice(&mac.finalize().into_bytes()[..DIGEST_LEN]);
+    digest
+}
+
+fn random_bytes(len: usize) -> Zeroizing<Vec<u8>> {
+    let mut bytes = Zeroizing::new(vec![0u8; len]);
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// `count` shares of which any `threshold` recover `secret`
+///
+/// Besides the secret at x = 255, the polynomial passes through a digest of the
+/// secret at x = 254, so recombining unrelated shares is detected.
+fn split_secret(threshold: u8, count: u8, secret: &[u8]) -> Vec<(u8, Zeroizing<Vec<u8>>)> {
+    if threshold == 1 {
+        return (0..count).map(|index| (index, Zeroizing::new(secret.to_vec()))).collect();
+    }
+
+    let mut shares: Vec<(u8, Zeroizing<Vec<u8>>)> = (0..threshold - 2)
+        .map(|index| (index, random_bytes(secret.len())))
+        .collect();
+
+    let random_part = random_bytes(secret.len() - DIGEST_LEN);
+    let mut digest = Zeroizing::new(share_digest(&random_part, secret).to_vec());
+    digest.extend_from_slice(&random_part);
+
+    let mut base = shares.clone();
+    base.push((DIGEST_INDEX, digest));
+    base.push((SECRET_INDEX, Zeroizing::new(secret.to_vec())));
+
+    for index in threshold - 2..count {
+        shares.push((index, interpolate(&base, index)));
+    }
+    shares
+}
+
+/// Inverse of `split_secret`, checking the digest
+fn recover_secret(threshold: u8, shares: &[(u8, Zeroizing<Vec<u8>>)]) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    if threshold == 1 {
+        return Ok(shares[0].1.clone());
+    }
+
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest = interpolate(shares, DIGEST_INDEX);
+    if share_digest(&digest[DIGEST_LEN..], &secret) != digest[..DIGEST_LEN] {
+        return Err(Slip39Error::Digest);
+    }
+    Ok(secret)
+}
+
+/// Four-round Feistel network keyed by PBKDF2-HMAC-SHA256 of the passphrase
+fn feistel(
+    input: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    encrypt: bool,
+) -> Zeroizing<Vec<u8>> {
+    let half = input.len() / 2;
+    let mut left = Zeroizing::new(input[..half].to_vec());
+    let mut right = Zeroizing::new(input[half..].to_vec());
+
+    // Non-extendable shares also bind the identifier into every round
+    let mut salt = Vec::new();
+    if !extendable {
+        salt.extend_from_slice(b"shamir");
+        salt.extend_from_slice(&identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / ROUND_COUNT as u32;
+
+    let rounds: Vec<u8> = if encrypt { (0..ROUND_COUNT).collect() } else { (0..ROUND_COUNT).rev().collect() };
+    for round in rounds {
+        let mut password = Zeroizing::new(vec![round]);
+        password.extend_from_slice(passphrase);
+        let mut round_salt = salt.clone();
+        round_salt.extend_from_slice(&right);
+
+        let mut key = Zeroizing::new(vec![0u8; half]);
+        pbkdf2::pbkdf2_hmac::<Sha256>(&password, &round_salt, iterations, &mut key);
+        let mixed = Zeroizing::new(left.iter().zip(key.iter()).map(|(l, k)| l ^ k).collect::<Vec<u8>>());
+        left = std::mem::replace(&mut right, mixed);
+    }
+
+    let mut output = Zeroizing::new(right.to_vec());
+    output.extend_from_slice(&left);
+    output
+}
+
+include!("slip39_tests.rs");
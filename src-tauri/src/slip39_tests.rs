@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // SLIP-39 reference vectors, passphrase "TREZOR"
+    const SINGLE_SHARE: &str = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+    const TWO_OF_THREE: [&str; 2] = [
+        "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+        "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+    ];
+
+    fn shares(mnemonics: &[&str]) -> Vec<String> {
+        mnemonics.iter().map(|mnemonic| mnemonic.to_string()).collect()
+    }
+
+    #[test]
+    fn test_wordlist_is_sorted_with_unique_prefixes() {
+        assert!(WORDLIST.windows(2).all(|pair| pair[0] < pair[1]));
+        let prefixes: std::collections::HashSet<&str> = WORDLIST.iter().map(|word| &word[..4]).collect();
+        assert_eq!(prefixes.len(), WORDLIST.len());
+    }
+
+    #[test]
+    fn test_reference_vectors() {
+        let secret = combine(&shares(&[SINGLE_SHARE]), "TREZOR").unwrap();
+        assert_eq!(hex::encode(&secret[..]), "bb54aac4b89dc868ba37d9cc21b2cece");
+
+        let secret = combine(&shares(&TWO_OF_THREE), "TREZOR").unwrap();
+        assert_eq!(hex::encode(&secret[..]), "b43ceb7e57a0ea8766221624d01b0864");
+    }
+
+    #[test]
+    fn test_share_metadata() {
+        let info = inspect(TWO_OF_THREE[0]).unwrap();
+        assert_eq!((info.group_threshold, info.group_count), (1, 1));
+        assert_eq!(info.member_threshold, 2);
+        assert_eq!(info.identifier, inspect(TWO_OF_THREE[1]).unwrap().identifier);
+
+        for mnemonic in TWO_OF_THREE {
+            assert_eq!(Share::parse(mnemonic, 0).unwrap().to_mnemonic(), mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_group_split_roundtrip() {
+        let secret = [0x5au8; 32];
+        let groups = [
+            GroupSpec { member_threshold: 1, member_count: 1 },
+            GroupSpec { member_threshold: 2, member_count: 3 },
+            GroupSpec { member_threshold: 3, member_count: 5 },
+        ];
+        let result = split(&secret, "hunter2", 2, &groups, 0).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[2].shares.len(), 5);
+
+        let info = inspect(&result[2].shares[4]).unwrap();
+        assert_eq!((info.group_index, info.group_threshold, info.group_count), (2, 2, 3));
+        assert_eq!((info.member_index, info.member_threshold), (4, 3));
+        assert!(info.extendable);
+
+        // Group 0 plus two of group 1; an incomplete group 2 is ignored
+        let chosen = vec![
+            result[0].shares[0].clone(),
+            result[1].shares[2].clone(),
+            result[1].shares[0].clone(),
+            result[2].shares[1].clone(),
+        ];
+        assert_eq!(&combine(&chosen, "hunter2").unwrap()[..], &secret[..]);
+
+        // A wrong passphrase silently gives another secret
+        assert_ne!(&combine(&chosen, "hunter3").unwrap()[..], &secret[..]);
+
+        let too_few = vec![result[0].shares[0].clone(), result[1].shares[2].clone()];
+        assert!(matches!(combine(&too_few, "hunter2"), Err(Slip39Error::InsufficientShares(_))));
+    }
+
+    #[test]
+    fn test_mixed_share_sets_rejected() {
+        let mixed = shares(&[SINGLE_SHARE, TWO_OF_THREE[0]]);
+        assert!(matches!(combine(&mixed, "TREZOR"), Err(Slip39Error::MixedShares(_))));
+    }
+
+    #[test]
+    fn test_corrupted_share_rejected() {
+        let corrupted = TWO_OF_THREE[0].replacen("wildlife", "window", 1);
+        assert!(matches!(
+            combine(&shares(&[&corrupted, TWO_OF_THREE[1]]), "TREZOR"),
+            Err(Slip39Error::Checksum(0))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_schemes_rejected() {
+        let secret = [1u8; 16];
+        let group = |member_threshold, member_count| GroupSpec { member_threshold, member_count };
+
+        assert!(split(&secret[..15], "", 1, &[group(1, 1)], 0).is_err());
+        assert!(split(&secret, "", 2, &[group(1, 1)], 0).is_err());
+        assert!(split(&secret, "", 1, &[group(1, 3)], 0).is_err());
+        assert!(split(&secret, "", 1, &[group(3, 2)], 0).is_err());
+        assert!(split(&secret, "pässword", 1, &[group(1, 1)], 0).is_err());
+    }
+}
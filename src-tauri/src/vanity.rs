@@ -0,0 +1,181 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::{Keypair, Signer};
+use thiserror::Error;
+
+pub const VANITY_PROGRESS_EVENT: &str = "vanity-progress";
+pub const VANITY_FOUND_EVENT: &str = "vanity-found";
+pub const VANITY_CANCELLED_EVENT: &str = "vanity-cancelled";
+
+/// How often progress is reported while searching
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the calling thread checks whether the search has ended
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Keys generated by a worker between checks of the shared counters
+const BATCH_SIZE: u64 = 256;
+
+/// Longer patterns take longer than anyone will wait
+const MAX_PATTERN_LEN: usize = 8;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Vanity search errors
+#[derive(Error, Debug)]
+pub enum VanityError {
+    #[error("Invalid vanity pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Vanity search was cancelled")]
+    Cancelled,
+}
+
+impl From<VanityError> for String {
+    fn from(err: VanityError) -> String {
+        err.to_string()
+    }
+}
+
+/// What the base58 public key should start and/or end with
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VanityPattern {
+    pub prefix: String,
+    pub suffix: String,
+    pub case_insensitive: bool,
+}
+
+impl VanityPattern {
+    /// Reject patterns no base58 key can match, e.g. containing 0, O, I or l
+    pub fn validate(&self) -> Result<(), VanityError> {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            return Err(VanityError::InvalidPattern("a prefix or suffix is required".to_string()));
+        }
+        if self.prefix.chars().count() + self.suffix.chars().count() > MAX_PATTERN_LEN {
+            return Err(VanityError::InvalidPattern("prefix and suffix may be 8 characters together".to_string()));
+        }
+        for c in self.prefix.chars().chain(self.suffix.chars()) {
+            if self.alternatives(c) == 0 {
+                return Err(VanityError::InvalidPattern(format!("'{}' cannot appear in a base58 address", c)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, address: &str) -> bool {
+        if self.case_insensitive {
+            let address = address.to_ascii_lowercase();
+            address.starts_with(&self.prefix.to_ascii_lowercase()) && address.ends_with(&self.suffix.to_ascii_lowercase())
+        } else {
+            address.starts_with(&self.prefix) && address.ends_with(&self.suffix)
+        }
+    }
+
+    /// Mean number of keys to try before a match, treating address characters as uniform
+    pub fn expected_attempts(&self) -> f64 {
+        self.prefix.chars()
+            .chain(self.suffix.chars())
+            .map(|c| 58.0 / self.alternatives(c) as f64)
+            .product()
+    }
+
+    /// Base58 characters that match `c`
+    fn alternatives(&self, c: char) -> usize {
+        BASE58_ALPHABET.chars()
+            .filter(|&candidate| {
+                if self.case_insensitive { candidate.eq_ignore_ascii_case(&c) } else { candidate == c }
+            })
+            .count()
+    }
+}
+
+/// Search progress, sent as the payload of `VANITY_PROGRESS_EVENT`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VanityProgress {
+    pub attempts: u64,
+    pub keys_per_sec: f64,
+    pub expected_attempts: f64,
+    /// Time until `expected_attempts` at the current rate; a match may come sooner or later
+    pub eta_secs: Option<f64>,
+    pub elapsed_secs: f64,
+}
+
+/// Payload of `VANITY_FOUND_EVENT`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VanityFound {
+    pub public_key: String,
+    pub attempts: u64,
+}
+
+/// Generate keypairs on `threads` threads until one matches `pattern` or `cancel` is set
+///
+/// `on_progress` is called from the calling thread every `PROGRESS_INTERVAL`.
+pub fn search<F>(
+    pattern: &VanityPattern,
+    threads: usize,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> Result<(Keypair, u64), VanityError>
+where
+    F: FnMut(VanityProgress),
+{
+    pattern.validate()?;
+
+    let attempts = AtomicU64::new(0);
+    let done = AtomicBool::new(false);
+    let found: Mutex<Option<Keypair>> = Mutex::new(None);
+    let started = Instant::now();
+    let expected_attempts = pattern.expected_attempts();
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) && !cancel.load(Ordering::Relaxed) {
+                    for _ in 0..BATCH_SIZE {
+                        let keypair = Keypair::new();
+                        if pattern.matches(&keypair.pubkey().to_string()) {
+                            let mut found = found.lock().unwrap_or_else(|e| e.into_inner());
+                            if found.is_none() {
+                                *found = Some(keypair);
+                            }
+                            done.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                    attempts.fetch_add(BATCH_SIZE, Ordering::Relaxed);
+                }
+            });
+        }
+
+        let mut last_report = Instant::now();
+        while !done.load(Ordering::Relaxed) && !cancel.load(Ordering::Relaxed) {
+            std::thread::sleep(POLL_INTERVAL);
+            if last_report.elapsed() < PROGRESS_INTERVAL {
+                continue;
+            }
+            last_report = Instant::now();
+
+            let attempts = attempts.load(Ordering::Relaxed);
+            let elapsed_secs = started.elapsed().as_secs_f64();
+            let keys_per_sec = attempts as f64 / elapsed_secs.max(f64::EPSILON);
+            on_progress(VanityProgress {
+                attempts,
+                keys_per_sec,
+                expected_attempts,
+                eta_secs: (keys_per_sec > 0.0)
+                    .then_some((expected_attempts - attempts as f64).max(0.0) / keys_per_sec),
+                elapsed_secs,
+            });
+        }
+    });
+
+    let keypair = found.into_inner().unwrap_or_else(|e| e.into_inner());
+    match keypair {
+        Some(keypair) => Ok((keypair, attempts.load(Ordering::Relaxed))),
+        None => Err(VanityError::Cancelled),
+    }
+}
+
+include!("vanity_tests.rs");
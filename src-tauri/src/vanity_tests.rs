@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pattern(prefix: &str, suffix: &str, case_insensitive: bool) -> VanityPattern {
+        VanityPattern {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            case_insensitive,
+        }
+    }
+
+    #[test]
+    fn test_non_base58_characters_rejected() {
+        for bad in ["0", "O", "I", "l", "ab-"] {
+            assert!(matches!(pattern(bad, "", false).validate(), Err(VanityError::InvalidPattern(_))));
+        }
+        assert!(pattern("", "", false).validate().is_err());
+        assert!(pattern("abcde", "fghij", false).validate().is_err());
+
+        // Case-insensitive "l" and "o" can still match "L" and "o"
+        assert!(pattern("l", "", true).validate().is_ok());
+        assert!(pattern("O", "", true).validate().is_ok());
+    }
+
+    #[test]
+    fn test_matching_and_difficulty() {
+        assert!(pattern("So1", "", false).matches("So11111111111111111111111111111111111111112"));
+        assert!(!pattern("so1", "", false).matches("So11111111111111111111111111111111111111112"));
+        assert!(pattern("so1", "12", true).matches("So11111111111111111111111111111111111111112"));
+
+        assert_eq!(pattern("A", "", false).expected_attempts(), 58.0);
+        assert_eq!(pattern("a", "", true).expected_attempts(), 29.0);
+        assert_eq!(pattern("1", "z", true).expected_attempts(), 58.0 * 29.0);
+    }
+
+    #[test]
+    fn test_search_finds_match() {
+        let cancel = AtomicBool::new(false);
+        let target = pattern("a", "", true);
+        let (keypair, attempts) = search(&target, 2, &cancel, |_| {}).unwrap();
+
+        assert!(target.matches(&keypair.pubkey().to_string()));
+        assert!(attempts > 0);
+    }
+
+    #[test]
+    fn test_search_cancelled() {
+        let cancel = AtomicBool::new(true);
+        let result = search(&pattern("zzzzzzzz", "", false), 1, &cancel, |_| {});
+        assert!(matches!(result, Err(VanityError::Cancelled)));
+    }
+}
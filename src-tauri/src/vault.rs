@@ -0,0 +1,241 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+use crate::keystore::KdfParams;
+
+/// Vault storage configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultConfig {
+    pub backup_count: usize,
+    /// Key derivation costs for the vault file, from `calibrate_vault_kdf`; defaults when unset
+    ///
+    /// They apply to the container only, not to the secrets sealed inside it.
+    #[serde(default)]
+    pub kdf_params: Option<KdfParams>,
+    /// Days between scheduled re-wraps of every vault secret; `None` disables rotation
+    #[serde(default)]
+    pub key_rotation_days: Option<u32>,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        Self {
+            backup_count: 3,
+            kdf_params: None,
+            key_rotation_days: None,
+        }
+    }
+}
+
+/// A value loaded from the vault, and which file it came from
+pub struct RecoveredVault<T> {
+    pub value: T,
+    pub source: PathBuf,
+    pub from_backup: bool,
+}
+
+/// Crash-safe storage for an encrypted vault file with rotating backups
+///
+/// Writes go to a temporary file that is fsynced and renamed over the primary,
+/// so the primary is always either the old or the new vault. Before each write
+/// the previous primary is kept as `<name>.bak.1`, shifting older backups up to
+/// `backup_count`.
+#[derive(Debug, Clone)]
+pub struct VaultStorage {
+    path: PathBuf,
+    backup_count: usize,
+    kdf_params: Option<KdfParams>,
+}
+
+impl VaultStorage {
+    pub fn new(path: impl Into<PathBuf>, config: &VaultConfig) -> Self {
+        Self {
+            path: path.into(),
+            backup_count: config.backup_count,
+            kdf_params: config.kdf_params.clone(),
+        }
+    }
+
+    /// Path of the primary vault file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Key derivation costs new writes should be sealed with, if configured
+    pub fn kdf_params(&self) -> Option<&KdfParams> {
+        self.kdf_params.as_ref()
+    }
+
+    /// Path of the n-th backup, 1 being the newest
+    pub fn backup_path(&self, index: usize) -> PathBuf {
+        self.sibling(&format!("bak.{}", index))
+    }
+
+    /// Whether the primary or any backup exists
+    pub fn exists(&self) -> bool {
+        self.path.exists() || self.existing_backups().next().is_some()
+    }
+
+    /// Atomically replace the primary vault, rotating the previous one into the backups
+    pub fn write(&self, data: &[u8]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp_path = self.sibling("tmp");
+        {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_path)?;
+            file.write_all(data)?;
+            file.sync_all()?;
+        }
+
+        if self.path.exists() {
+            self.rotate_backups()?;
+        }
+
+        fs::rename(&temp_path, &self.path)?;
+        self.sync_parent_dir();
+        Ok(())
+    }
+
+    /// Load the vault, falling back to the newest backup that `decode` accepts
+    ///
+    /// If the primary fails to decode but a backup succeeds, the primary is moved
+    /// aside as `<name>.corrupt-<timestamp>` so the next write does not rotate it
+    /// into the backups. If nothing decodes, the primary's error is returned.
+    pub fn load_with_recovery<T, E, F>(&self, mut decode: F) -> Result<RecoveredVault<T>, E>
+    where
+        F: FnMut(&[u8]) -> Result<T, E>,
+        E: From<io::Error> + std::fmt::Display,
+    {
+        let mut first_error: Option<E> = None;
+
+        if self.path.exists() {
+            match fs::read(&self.path).map_err(E::from).and_then(|data| decode(&data)) {
+                Ok(value) => {
+                    return Ok(RecoveredVault {
+                        value,
+                        source: self.path.clone(),
+                        from_backup: false,
+                    });
+                }
+                Err(e) => {
+                    log::warn!("Primary vault {} failed to load: {}", self.path.display(), e);
+                    first_error = Some(e);
+                }
+            }
+        }
+
+        for backup in self.existing_backups() {
+            match fs::read(&backup).map_err(E::from).and_then(|data| decode(&data)) {
+                Ok(value) => {
+                    log::warn!("Recovered vault from backup {}", backup.display());
+                    if self.path.exists() {
+                        let quarantine = self.sibling(&format!("corrupt-{}", Utc::now().timestamp()));
+                        fs::rename(&self.path, &quarantine).map_err(E::from)?;
+                    }
+                    return Ok(RecoveredVault {
+                        value,
+                        source: backup,
+                        from_backup: true,
+                    });
+                }
+                Err(e) => {
+                    log::warn!("Backup {} failed to load: {}", backup.display(), e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        Err(first_error.unwrap_or_else(|| {
+            E::from(io::Error::new(io::ErrorKind::NotFound, "Vault file not found"))
+        }))
+    }
+
+    /// Contents of the existing backups, newest first
+    pub fn read_backups(&self) -> io::Result<Vec<Vec<u8>>> {
+        self.existing_backups().map(fs::read).collect()
+    }
+
+    /// Replace the backups with `backups`, newest first, deleting any beyond them
+    ///
+    /// Each backup is replaced atomically, e.g. to re-seal them after a password change.
+    pub fn replace_backups(&self, backups: &[Vec<u8>]) -> io::Result<()> {
+        let temp_path = self.sibling("bak.tmp");
+        for (index, data) in backups.iter().take(self.backup_count).enumerate() {
+            {
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(&temp_path)?;
+                file.write_all(data)?;
+                file.sync_all()?;
+            }
+            fs::rename(&temp_path, self.backup_path(index + 1))?;
+        }
+
+        let kept = backups.len().min(self.backup_count);
+        for backup in self.existing_backups().skip(kept).collect::<Vec<_>>() {
+            fs::remove_file(backup)?;
+        }
+        self.sync_parent_dir();
+        Ok(())
+    }
+
+    fn rotate_backups(&self) -> io::Result<()> {
+        if self.backup_count == 0 {
+            return Ok(());
+        }
+
+        let oldest = self.backup_path(self.backup_count);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+
+        for index in (1..self.backup_count).rev() {
+            let from = self.backup_path(index);
+            if from.exists() {
+                fs::rename(&from, self.backup_path(index + 1))?;
+            }
+        }
+
+        // Copy rather than rename so the primary is never missing
+        fs::copy(&self.path, self.backup_path(1))?;
+        Ok(())
+    }
+
+    fn existing_backups(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        (1..=self.backup_count.max(1))
+            .map(move |index| self.backup_path(index))
+            .filter(|path| path.exists())
+    }
+
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(suffix);
+        self.path.with_file_name(name)
+    }
+
+    fn sync_parent_dir(&self) {
+        // Persist the rename itself; directories cannot be opened this way on Windows
+        #[cfg(unix)]
+        {
+            if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                if let Ok(dir) = File::open(parent) {
+                    let _ = dir.sync_all();
+                }
+            }
+        }
+    }
+}
+
+include!("vault_tests.rs");
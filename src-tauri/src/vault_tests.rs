@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn decode(data: &[u8]) -> Result<String, io::Error> {
+        let text = String::from_utf8(data.to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if !text.starts_with("vault:") {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "corrupted"));
+        }
+        Ok(text)
+    }
+
+    #[test]
+    fn test_write_rotates_backups() {
+        let dir = tempdir().unwrap();
+        let storage = VaultStorage::new(dir.path().join("wallets.enc"), &VaultConfig { backup_count: 2, ..VaultConfig::default() });
+
+        storage.write(b"vault:1").unwrap();
+        storage.write(b"vault:2").unwrap();
+        storage.write(b"vault:3").unwrap();
+        storage.write(b"vault:4").unwrap();
+
+        assert_eq!(fs::read(storage.path()).unwrap(), b"vault:4".to_vec());
+        assert_eq!(fs::read(storage.backup_path(1)).unwrap(), b"vault:3".to_vec());
+        assert_eq!(fs::read(storage.backup_path(2)).unwrap(), b"vault:2".to_vec());
+        assert!(!storage.backup_path(3).exists());
+        assert!(!dir.path().join("wallets.enc.tmp").exists());
+    }
+
+    #[test]
+    fn test_recovers_from_truncated_primary() {
+        let dir = tempdir().unwrap();
+        let storage = VaultStorage::new(dir.path().join("wallets.enc"), &VaultConfig::default());
+
+        storage.write(b"vault:1").unwrap();
+        storage.write(b"vault:2").unwrap();
+        fs::write(storage.path(), b"vau").unwrap();
+
+        let recovered = storage.load_with_recovery(decode).unwrap();
+        assert_eq!(recovered.value, "vault:1");
+        assert!(recovered.from_backup);
+        assert_eq!(recovered.source, storage.backup_path(1));
+        assert!(!storage.path().exists());
+    }
+
+    #[test]
+    fn test_primary_error_returned_when_nothing_decodes() {
+        let dir = tempdir().unwrap();
+        let storage = VaultStorage::new(dir.path().join("wallets.enc"), &VaultConfig::default());
+
+        storage.write(b"garbage").unwrap();
+        let result = storage.load_with_recovery(decode);
+        assert!(result.is_err());
+        assert!(storage.path().exists());
+    }
+
+    #[test]
+    fn test_zero_backups_keeps_only_primary() {
+        let dir = tempdir().unwrap();
+        let storage = VaultStorage::new(dir.path().join("wallets.enc"), &VaultConfig { backup_count: 0, ..VaultConfig::default() });
+
+        storage.write(b"vault:1").unwrap();
+        storage.write(b"vault:2").unwrap();
+
+        assert_eq!(fs::read(storage.path()).unwrap(), b"vault:2".to_vec());
+        assert!(!storage.backup_path(1).exists());
+    }
+
+    #[test]
+    fn test_replace_backups() {
+        let dir = tempdir().unwrap();
+        let storage = VaultStorage::new(dir.path().join("wallets.enc"), &VaultConfig::default());
+
+        for n in 1..=4 {
+            storage.write(format!("vault:{}", n).as_bytes()).unwrap();
+        }
+        assert_eq!(storage.read_backups().unwrap(), vec![b"vault:3".to_vec(), b"vault:2".to_vec(), b"vault:1".to_vec()]);
+
+        storage.replace_backups(&[b"vault:3'".to_vec(), b"vault:2'".to_vec()]).unwrap();
+        assert_eq!(fs::read(storage.path()).unwrap(), b"vault:4".to_vec());
+        assert_eq!(storage.read_backups().unwrap(), vec![b"vault:3'".to_vec(), b"vault:2'".to_vec()]);
+        assert!(!storage.backup_path(3).exists());
+        assert!(!dir.path().join("wallets.enc.bak.tmp").exists());
+    }
+}
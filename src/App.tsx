@@ -1,5 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import WalletList from './components/WalletList';
 import CreateWallet from './components/CreateWallet';
 import ImportWallet from './components/ImportWallet';
@@ -18,6 +19,7 @@ import Toast from './components/Toast';
 import './styles.css';
 
 interface Wallet {
+  id: string;
   public_key: string;
   encrypted_private_key: number[];
   salt: number[];
@@ -28,6 +30,7 @@ interface Wallet {
 }
 
 interface LegacyWallet {
+  id: string;
   public_key: string;
   private_key: number[];
   salt: number[];
@@ -49,8 +52,18 @@ const App: React.FC = () => {
   const [isAuthenticated, setIsAuthenticated] = useState<boolean>(false);
 
   useEffect(() => {
-    loadWallets();
-  }, [password]);
+    // The backend locks the vault on its own once the auto-lock policy expires the session
+    const unlisten = listen('vault-locked', () => {
+      setWallets([]);
+      setSelectedWallet(null);
+      setSelectedLegacyWallet(null);
+      setPassword('');
+      setIsAuthenticated(false);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
 
   useEffect(() => {
     // Set loading to false after component mounts
@@ -58,11 +71,11 @@ const App: React.FC = () => {
     return () => clearTimeout(timer);
   }, []);
 
-  const loadWallets = async () => {
+  const unlockVault = async () => {
     if (!password) return;
 
     try {
-      const loadedWallets: any = await invoke('load_wallets', { password });
+      const loadedWallets: any = await invoke('unlock_vault', { password });
       const convertedWallets: Wallet[] = loadedWallets.wallets.map((wallet: any) => ({
         ...wallet,
         encrypted_private_key: Array.from(wallet.encrypted_private_key),
@@ -71,27 +84,18 @@ const App: React.FC = () => {
       setWallets(convertedWallets);
       setIsAuthenticated(true);
     } catch (error) {
-      console.error('Failed to load wallets:', error);
-      showToast('Failed to load wallets - check password', 'error');
+      console.error('Failed to unlock vault:', error);
+      showToast('Failed to unlock vault - check password', 'error');
     }
   };
 
-  const saveWallets = async (updatedWallets: Wallet[]) => {
-    if (!password) return;
-
+  const lockVault = async () => {
     try {
-      const backendWallets = {
-        wallets: updatedWallets.map(wallet => ({
-          ...wallet,
-          encrypted_private_key: wallet.encrypted_private_key
-        })),
-        version: "1.0"
-      };
-      await invoke('save_wallets', { wallets: backendWallets, password });
-      setWallets(updatedWallets);
+      // The vault-locked event resets the UI
+      await invoke('lock_vault');
     } catch (error) {
-      console.error('Failed to save wallets:', error);
-      showToast('Failed to save wallets', 'error');
+      console.error('Failed to lock vault:', error);
+      showToast('Failed to lock vault', 'error');
     }
   };
 
@@ -110,14 +114,14 @@ const App: React.FC = () => {
     }
 
     try {
-      const newWallet: any = await invoke('generate_wallet', { password, network });
+      // The backend adds the wallet to the unlocked vault before returning it
+      const newWallet: any = await invoke('generate_wallet', { network });
       const convertedWallet: Wallet = {
         ...newWallet,
         encrypted_private_key: Array.from(newWallet.encrypted_private_key),
         salt: Array.from(newWallet.salt || [])
       };
-      const updatedWallets = [...wallets, convertedWallet];
-      await saveWallets(updatedWallets);
+      setWallets([...wallets, convertedWallet]);
       setCurrentView('wallets');
       showToast('Wallet created successfully!', 'success');
     } catch (error) {
@@ -130,6 +134,7 @@ const App: React.FC = () => {
     setSelectedWallet(wallet);
     // Convert to legacy format for components that still expect it
     setSelectedLegacyWallet({
+      id: wallet.id,
       public_key: wallet.public_key,
       private_key: [], // Will be decrypted when needed
       salt: wallet.salt,
@@ -152,8 +157,8 @@ const App: React.FC = () => {
         encrypted_private_key: Array.from(wallet.encrypted_private_key),
         salt: Array.from(wallet.salt || [])
       };
-      const updatedWallets = [...wallets, convertedWallet];
-      await saveWallets(updatedWallets);
+      // Already stored in the vault by the import command
+      setWallets([...wallets, convertedWallet]);
       setCurrentView('wallets');
       showToast('Wallet imported successfully!', 'success');
     } catch (error) {
@@ -174,9 +179,9 @@ const App: React.FC = () => {
         showToast('Seed phrase export is not available for security reasons', 'error');
         return;
       } else {
+        // Exporting asks for the password again even while the vault is unlocked
         data = await invoke('export_wallet_private_key', {
-          publicKey: wallet.public_key,
-          encryptedPrivateKey: wallet.encrypted_private_key,
+          walletId: wallet.id,
           password
         });
       }
@@ -219,7 +224,7 @@ const App: React.FC = () => {
             onChange={(e) => setPassword(e.target.value)}
             className="password-input"
           />
-          <button onClick={loadWallets} className="auth-btn">Unlock</button>
+          <button onClick={unlockVault} className="auth-btn">Unlock</button>
         </div>
       </div>
     );
@@ -289,6 +294,9 @@ const App: React.FC = () => {
                 </button>
               </>
             )}
+            <button onClick={lockVault}>
+              Lock
+            </button>
           </nav>
         </div>
       </header>
@@ -300,6 +308,7 @@ const App: React.FC = () => {
         {currentView === 'wallets' && (
           <WalletList
             wallets={wallets.map(w => ({
+              id: w.id,
               public_key: w.public_key,
               private_key: [], // Legacy compatibility
               balance: w.balance,
@@ -323,13 +332,13 @@ const App: React.FC = () => {
           <CreateWallet onCreateWallet={handleCreateWallet} />
         )}
         {currentView === 'import' && (
-          <ImportWallet onImportWallet={handleImportWallet} password={password} network={network} />
+          <ImportWallet onImportWallet={handleImportWallet} network={network} />
         )}
         {currentView === 'export' && selectedWallet && (
           <ExportWallet wallet={selectedWallet} onExport={handleExportWallet} />
         )}
         {currentView === 'transfer' && selectedLegacyWallet && (
-          <TransferTokens wallet={selectedLegacyWallet} onTransferComplete={() => loadWallets()} network={network} showToast={showToast} />
+          <TransferTokens wallet={selectedLegacyWallet} onTransferComplete={() => {}} network={network} showToast={showToast} />
         )}
         {currentView === 'dapp' && selectedLegacyWallet && (
           <DappBrowser wallet={selectedLegacyWallet} />
@@ -344,10 +353,10 @@ const App: React.FC = () => {
           <AddressBook />
         )}
         {currentView === 'staking' && selectedLegacyWallet && selectedWallet && (
-          <StakingInterface wallet={selectedLegacyWallet} network={network} showToast={showToast} encryptedWallet={selectedWallet} />
+          <StakingInterface wallet={selectedLegacyWallet} network={network} showToast={showToast} />
         )}
         {currentView === 'swap' && selectedLegacyWallet && (
-          <SwapInterface wallet={selectedLegacyWallet} network={network} password={password} showToast={showToast} />
+          <SwapInterface wallet={selectedLegacyWallet} network={network} showToast={showToast} />
         )}
         {currentView === 'security' && (
           <SecuritySettings showToast={showToast} />
@@ -2,8 +2,8 @@ import React, { useState, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 
 interface Wallet {
+  id: string;
   public_key: string;
-  private_key: number[];
   balance: number;
 }
 
@@ -43,8 +43,7 @@ const DappBrowser: React.FC<DappBrowserProps> = ({ wallet }) => {
   const handleSignTransaction = async (transaction: TransactionRequest) => {
     try {
       const signature: string = await invoke('sign_transaction', {
-        publicKey: wallet.public_key,
-        privateKey: wallet.private_key,
+        walletId: wallet.id,
         recipient: transaction.to,
         amount: Math.floor(transaction.amount * 1e9), // Convert SOL to lamports
       });
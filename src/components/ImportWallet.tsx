@@ -12,11 +12,10 @@ interface Wallet {
 
 interface ImportWalletProps {
   onImportWallet: (wallet: Wallet) => void;
-  password: string;
   network: string;
 }
 
-const ImportWallet: React.FC<ImportWalletProps> = ({ onImportWallet, password, network }) => {
+const ImportWallet: React.FC<ImportWalletProps> = ({ onImportWallet, network }) => {
   const [importType, setImportType] = useState<'seed' | 'privateKey'>('seed');
   const [input, setInput] = useState('');
   const [error, setError] = useState('');
@@ -28,9 +27,9 @@ const ImportWallet: React.FC<ImportWalletProps> = ({ onImportWallet, password, n
       setError('');
       let wallet: any;
       if (importType === 'seed') {
-        wallet = await invoke('import_wallet_from_seed_phrase', { seedPhrase: input, password, network });
+        wallet = await invoke('import_wallet_from_seed_phrase', { seedPhrase: input, network });
       } else {
-        wallet = await invoke('import_wallet_from_private_key', { privateKeyHex: input, password, network });
+        wallet = await invoke('import_wallet_from_private_key', { privateKey: input, network });
       }
       const convertedWallet: Wallet = {
         ...wallet,
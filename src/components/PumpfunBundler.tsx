@@ -2,6 +2,7 @@ import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 
 interface Wallet {
+  id: string;
   public_key: string;
   encrypted_private_key: number[];
   salt: number[];
@@ -102,7 +103,7 @@ const PumpfunBundler: React.FC<PumpfunBundlerProps> = ({ wallets, password, netw
     }
 
     try {
-      const newWallet: any = await invoke('generate_wallet', { password, network });
+      const newWallet: any = await invoke('generate_wallet', { network });
       const bundleWallet: BundleWallet = {
         ...newWallet,
         encrypted_private_key: Array.from(newWallet.encrypted_private_key),
@@ -127,7 +128,6 @@ const PumpfunBundler: React.FC<PumpfunBundlerProps> = ({ wallets, password, netw
     try {
       const importedWallet: any = await invoke('import_wallet_from_private_key', {
         privateKey: importPrivateKey,
-        password,
         network
       });
       const bundleWallet: BundleWallet = {
@@ -195,9 +195,8 @@ const PumpfunBundler: React.FC<PumpfunBundlerProps> = ({ wallets, password, netw
       // Step 1: Create token (10% progress)
       setLaunchProgress(10);
       const tokenAddressResult = await invoke('create_pump_fun_token', {
-        devWallet,
+        devWalletId: devWallet.id,
         tokenMetadata,
-        password,
         network
       });
       setTokenAddress(tokenAddressResult as string);
@@ -219,21 +218,10 @@ const PumpfunBundler: React.FC<PumpfunBundlerProps> = ({ wallets, password, netw
 
       // Step 3: Execute bundle buys (70% progress)
       setLaunchProgress(70);
-      const backendBundleWallets = bundleWallets.map(w => ({
-        public_key: w.public_key,
-        encrypted_private_key: w.encrypted_private_key,
-        salt: w.salt,
-        balance: w.balance,
-        created_at: w.created_at,
-        last_updated: w.last_updated,
-        network: w.network
-      }));
-
       await invoke('execute_bundle_buy', {
-        bundleWallets: backendBundleWallets,
+        bundleWalletIds: bundleWallets.map(w => w.id),
         tokenAddress: tokenAddressResult as string,
         amountPerWallet: 100000, // 0.0001 SOL per buy
-        password,
         swapDapp: selectedSwapDapp,
         network
       });
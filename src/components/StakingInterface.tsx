@@ -21,6 +21,7 @@ interface Validator {
 
 interface StakingInterfaceProps {
   wallet: {
+    id: string;
     public_key: string;
     private_key: number[];
     salt: number[];
@@ -31,18 +32,9 @@ interface StakingInterfaceProps {
   };
   network: 'mainnet' | 'devnet' | 'testnet';
   showToast?: (message: string, type: 'success' | 'error' | 'info') => void;
-  encryptedWallet?: {
-    public_key: string;
-    encrypted_private_key: number[];
-    salt: number[];
-    balance: number;
-    created_at: string;
-    last_updated: string;
-    network: string;
-  };
 }
 
-const StakingInterface: React.FC<StakingInterfaceProps> = ({ wallet, network, showToast, encryptedWallet }) => {
+const StakingInterface: React.FC<StakingInterfaceProps> = ({ wallet, network, showToast }) => {
   const [stakingAccounts, setStakingAccounts] = useState<StakingAccount[]>([]);
   const [validators, setValidators] = useState<Validator[]>([]);
   const [selectedValidator, setSelectedValidator] = useState<string>('');
@@ -143,8 +135,7 @@ const StakingInterface: React.FC<StakingInterfaceProps> = ({ wallet, network, sh
 
       // Call real Solana staking function from backend
       const signature = await invoke('delegate_stake', {
-        wallet: encryptedWallet, // Pass the actual wallet with encrypted key
-        password: '', // TODO: Get password from context/state
+        walletId: wallet.id,
         validator: selectedValidator,
         amount: Math.floor(amount * 1e9), // Convert SOL to lamports
         network
@@ -171,8 +162,7 @@ const StakingInterface: React.FC<StakingInterfaceProps> = ({ wallet, network, sh
 
       // Call real Solana unstaking function from backend
       const signature = await invoke('deactivate_stake', {
-        wallet: encryptedWallet, // Pass the actual wallet with encrypted key
-        password: '', // TODO: Get password from context/state
+        walletId: wallet.id,
         stakeAccountAddress: stakeAccount,
         network
       });
@@ -20,6 +20,7 @@ interface SwapQuote {
 
 interface SwapInterfaceProps {
   wallet: {
+    id: string;
     public_key: string;
     private_key: number[];
     salt: number[];
@@ -29,11 +30,10 @@ interface SwapInterfaceProps {
     network: string;
   };
   network: 'mainnet' | 'devnet' | 'testnet';
-  password: string;
   showToast?: (message: string, type: 'success' | 'error' | 'info') => void;
 }
 
-const SwapInterface: React.FC<SwapInterfaceProps> = ({ wallet, network, password, showToast }) => {
+const SwapInterface: React.FC<SwapInterfaceProps> = ({ wallet, network, showToast }) => {
   const [tokens, setTokens] = useState<Token[]>([]);
   const [fromToken, setFromToken] = useState<Token | null>(null);
   const [toToken, setToToken] = useState<Token | null>(null);
@@ -165,9 +165,7 @@ const SwapInterface: React.FC<SwapInterfaceProps> = ({ wallet, network, password
       // Execute the swap
       const signature = await invoke('execute_jupiter_swap', {
         quoteResponse: JSON.stringify(quote),
-        userPublicKey: wallet.public_key,
-        wallet: wallet,
-        password: password,
+        walletId: wallet.id,
         network: network
       });
 
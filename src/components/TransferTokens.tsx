@@ -2,8 +2,8 @@ import React, { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 
 interface Wallet {
+  id: string;
   public_key: string;
-  private_key: number[];
   balance: number;
 }
 
@@ -37,15 +37,15 @@ const TransferTokens: React.FC<TransferTokensProps> = ({ wallet, onTransferCompl
 
       const amountLamports = Math.floor(parseFloat(amount) * 1e9); // Convert SOL to lamports
 
+      // The backend signs with the unlocked vault's key for this wallet
       const signature = useJito ? await invoke('send_bundle_transaction', {
-        public_key: wallet.public_key,
-        private_key: wallet.private_key,
+        walletId: wallet.id,
         recipient,
         amount: amountLamports,
         network,
+        useJito: true,
       }) : await invoke('transfer_tokens', {
-        fromPublicKey: wallet.public_key,
-        fromPrivateKey: wallet.private_key,
+        walletId: wallet.id,
         toPublicKey: recipient,
         amount: amountLamports,
         tokenMint: isSOL ? null : tokenMint || null,